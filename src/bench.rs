@@ -0,0 +1,117 @@
+use crate::config::Config;
+use crate::parser::{self, StageTimings};
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub files: usize,
+    pub iterations: usize,
+    pub documents: usize,
+    pub errors: usize,
+    pub bytes: u64,
+    pub seconds: f64,
+    pub docs_per_sec: f64,
+    pub mb_per_sec: f64,
+    pub stages: Vec<Stage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stage {
+    pub name: &'static str,
+    pub seconds: f64,
+    pub share: f64,
+    pub micros_per_doc: f64,
+}
+
+// Parses every file `iterations` times, timing each stage of the pipeline
+// and the JSON serialization `parse` would do afterwards.
+pub fn run(files: &[PathBuf], cfg: &Config, iterations: usize) -> Result<BenchReport> {
+    let mut timings = StageTimings::default();
+    let mut serialize = Duration::ZERO;
+    let mut documents = 0;
+    let mut errors = 0;
+    let mut bytes = 0;
+    let started = Instant::now();
+    for _ in 0..iterations {
+        for path in files {
+            match parser::parse_timed(path, cfg, &mut timings) {
+                Ok(doc) => {
+                    let serializing = Instant::now();
+                    let json = serde_json::to_vec(&doc)?;
+                    serialize += serializing.elapsed();
+                    bytes += doc.source.bytes;
+                    documents += 1;
+                    std::hint::black_box(json);
+                }
+                Err(_) => errors += 1,
+            }
+        }
+    }
+    let seconds = started.elapsed().as_secs_f64();
+
+    let measured = [
+        ("read", timings.read),
+        ("hash", timings.hash),
+        ("dom_parse", timings.dom),
+        ("extraction", timings.extract),
+        ("serialization", serialize),
+    ];
+    let stage_total: f64 = measured.iter().map(|(_, d)| d.as_secs_f64()).sum();
+    let stages = measured
+        .into_iter()
+        .map(|(name, duration)| Stage {
+            name,
+            seconds: duration.as_secs_f64(),
+            share: ratio(duration.as_secs_f64(), stage_total),
+            micros_per_doc: ratio(duration.as_secs_f64() * 1e6, documents as f64),
+        })
+        .collect();
+    Ok(BenchReport {
+        files: files.len(),
+        iterations,
+        documents,
+        errors,
+        bytes,
+        seconds,
+        docs_per_sec: ratio(documents as f64, seconds),
+        mb_per_sec: ratio(bytes as f64 / 1e6, seconds),
+        stages,
+    })
+}
+
+fn ratio(value: f64, total: f64) -> f64 {
+    if total > 0.0 { value / total } else { 0.0 }
+}
+
+pub fn write_report_table<W: Write>(out: &mut W, report: &BenchReport) -> Result<()> {
+    writeln!(
+        out,
+        "{} files x {} iterations: {} documents, {} errors in {:.3}s",
+        report.files, report.iterations, report.documents, report.errors, report.seconds
+    )?;
+    writeln!(
+        out,
+        "{:.1} docs/sec, {:.2} MB/sec\n",
+        report.docs_per_sec, report.mb_per_sec
+    )?;
+    writeln!(
+        out,
+        "{:<14}  {:>9}  {:>6}  {:>10}",
+        "STAGE", "SECONDS", "SHARE", "µs/DOC"
+    )?;
+    for stage in &report.stages {
+        writeln!(
+            out,
+            "{:<14}  {:>9.3}  {:>5.1}%  {:>10.1}",
+            stage.name,
+            stage.seconds,
+            stage.share * 100.0,
+            stage.micros_per_doc
+        )?;
+    }
+    Ok(())
+}
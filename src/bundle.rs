@@ -0,0 +1,208 @@
+use crate::config::{Compression, OutputFormat};
+use crate::model::{OutputBundle, ParseError, ParsedDocument, SCHEMA_VERSION, Stats, ToolInfo};
+use crate::output;
+use anyhow::{Context, Result, bail};
+use flate2::read::MultiGzDecoder;
+use serde_json::Value;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+// Reads a saved result back in. `format` defaults to the one implied by the
+// file extension (ignoring `.gz`/`.zst`); without a recognised extension the
+// text is sniffed as a JSON bundle or an NDJSON record stream (documents,
+// `error` records and a closing `summary` record).
+pub fn load(path: &Path) -> Result<OutputBundle> {
+    load_as(path, None)
+}
+
+pub fn load_as(path: &Path, format: Option<OutputFormat>) -> Result<OutputBundle> {
+    let format = format.or_else(|| format_for_path(path));
+    let bundle = match format {
+        Some(OutputFormat::Sqlite) => from_sqlite(path),
+        Some(format @ (OutputFormat::Msgpack | OutputFormat::Cbor)) => {
+            decode_values(&read_input(path)?, format).and_then(from_values)
+        }
+        Some(OutputFormat::Json | OutputFormat::Ndjson) | None => {
+            let text = String::from_utf8(read_input(path)?).context("input is not UTF-8")?;
+            match serde_json::from_str::<Value>(&text) {
+                Ok(value) if value.get("documents").is_some() => from_values(vec![value]),
+                _ => from_ndjson(&text),
+            }
+        }
+        // The tabular formats flatten documents into columns; there is no
+        // way back to the nested model.
+        Some(other) => bail!(
+            "{} output is a flattened table and cannot be read back",
+            format!("{other:?}").to_lowercase()
+        ),
+    };
+    bundle.with_context(|| format!("load bundle {}", path.display()))
+}
+
+// The output format an extension stands for, looking through `.gz`/`.zst`.
+pub fn format_for_path(path: &Path) -> Option<OutputFormat> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+    let name = name
+        .strip_suffix(".gz")
+        .or_else(|| name.strip_suffix(".zst"))
+        .or_else(|| name.strip_suffix(".zstd"))
+        .unwrap_or(&name);
+    let format = match name.rsplit_once('.')?.1 {
+        "json" => OutputFormat::Json,
+        "ndjson" | "jsonl" => OutputFormat::Ndjson,
+        "csv" => OutputFormat::Csv,
+        "tsv" => OutputFormat::Tsv,
+        "md" | "markdown" => OutputFormat::Markdown,
+        "msgpack" | "mpk" => OutputFormat::Msgpack,
+        "cbor" => OutputFormat::Cbor,
+        "xlsx" => OutputFormat::Xlsx,
+        "sqlite" | "sqlite3" | "db" => OutputFormat::Sqlite,
+        "parquet" => OutputFormat::Parquet,
+        "arrow" | "arrows" => OutputFormat::Arrow,
+        _ => return None,
+    };
+    Some(format)
+}
+
+// MessagePack and CBOR output is a bundle value or records back to back.
+pub fn decode_values(bytes: &[u8], format: OutputFormat) -> Result<Vec<Value>> {
+    let mut cursor = Cursor::new(bytes);
+    let mut values = Vec::new();
+    while (cursor.position() as usize) < bytes.len() {
+        values.push(match format {
+            OutputFormat::Cbor => ciborium::from_reader::<Value, _>(&mut cursor)?,
+            _ => rmp_serde::from_read::<_, Value>(&mut cursor)?,
+        });
+    }
+    Ok(values)
+}
+
+pub fn read_input(path: &Path) -> Result<Vec<u8>> {
+    let file = std::fs::File::open(path).with_context(|| format!("read {}", path.display()))?;
+    let mut reader: Box<dyn Read> = match output::resolve_compression(Compression::Auto, Some(path))
+    {
+        Compression::Gzip => Box::new(MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+        Compression::Auto | Compression::None => Box::new(file),
+    };
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("read {}", path.display()))?;
+    Ok(bytes)
+}
+
+// A whole-bundle value, or the record stream `--records` writes.
+fn from_values(values: Vec<Value>) -> Result<OutputBundle> {
+    if let [value] = values.as_slice()
+        && value.get("documents").is_some()
+    {
+        let bundle: OutputBundle =
+            serde_json::from_value(value.clone()).context("not a complete bundle")?;
+        check_schema(bundle.schema_version)?;
+        return Ok(bundle);
+    }
+    let records = values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| (format!("record {}", i + 1), Ok(value)));
+    from_records(records)
+}
+
+fn from_ndjson(text: &str) -> Result<OutputBundle> {
+    let records = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            (
+                format!("line {}", i + 1),
+                serde_json::from_str(line).map_err(Into::into),
+            )
+        });
+    from_records(records)
+}
+
+fn from_sqlite(path: &Path) -> Result<OutputBundle> {
+    let conn =
+        rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("open sqlite {}", path.display()))?;
+    let mut stmt = conn.prepare("SELECT doc_id, data FROM documents ORDER BY rowid")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    let mut records = Vec::new();
+    for row in rows {
+        let (doc_id, data) = row?;
+        records.push((
+            format!("document {doc_id}"),
+            serde_json::from_str(&data).map_err(Into::into),
+        ));
+    }
+    from_records(records.into_iter())
+}
+
+// `location` names each record in error messages.
+fn from_records(records: impl Iterator<Item = (String, Result<Value>)>) -> Result<OutputBundle> {
+    let mut documents = Vec::new();
+    let mut errors = Vec::new();
+    let mut summary: Option<(u32, Stats)> = None;
+
+    for (location, record) in records {
+        let record = record.with_context(|| format!("{location}: invalid JSON"))?;
+        match record.get("type").and_then(Value::as_str) {
+            Some("error") => errors.push(
+                serde_json::from_value::<ParseError>(record["data"].clone())
+                    .with_context(|| format!("{location}: invalid error record"))?,
+            ),
+            Some("summary") => {
+                let version = record
+                    .get("schema_version")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0) as u32;
+                let stats = serde_json::from_value::<Stats>(record["data"].clone())
+                    .with_context(|| format!("{location}: invalid summary record"))?;
+                summary = Some((version, stats));
+            }
+            _ => documents.push(
+                serde_json::from_value::<ParsedDocument>(record)
+                    .with_context(|| format!("{location}: not a document record"))?,
+            ),
+        }
+    }
+
+    let (schema_version, stats) = summary.unwrap_or_else(|| {
+        (
+            SCHEMA_VERSION,
+            Stats {
+                input_count: documents.len() + errors.len(),
+                parsed_ok: documents.len(),
+                parsed_err: errors.len(),
+                duplicates_discarded: 0,
+                discarded_duplicates: vec![],
+                filtered_out: 0,
+                already_present: 0,
+                release_gaps: None,
+            },
+        )
+    });
+    check_schema(schema_version)?;
+
+    Ok(OutputBundle {
+        schema_version,
+        tool: ToolInfo::current(),
+        stats,
+        documents,
+        errors,
+        infohash_index: Default::default(),
+    })
+}
+
+fn check_schema(version: u32) -> Result<()> {
+    if version > SCHEMA_VERSION {
+        bail!(
+            "bundle schema_version {version} is newer than this build supports ({SCHEMA_VERSION})"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use crate::config::Config;
+use crate::fs;
+use crate::output;
+use crate::parser;
+use crate::project::Projection;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const EXPECTED_SUFFIX: &str = ".expected.json";
+// Differ between checkouts of the same corpus, never between parser versions.
+const VOLATILE_FIELDS: [&str; 2] = ["source.path", "source.mtime"];
+const MAX_VALUE_WIDTH: usize = 80;
+
+pub struct CheckOptions<'a> {
+    pub ignore: &'a [String],
+    pub update: bool,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Pass,
+    Updated,
+    MissingExpected,
+    ParseFailed(String),
+    Mismatch(Vec<Mismatch>),
+}
+
+#[derive(Debug)]
+pub struct Mismatch {
+    pub field: String,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+// `<name>.expected.json` beside each `<name>.html`.
+pub fn expected_path(fixture: &Path) -> PathBuf {
+    let stem = fixture
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    fixture.with_file_name(format!("{stem}{EXPECTED_SUFFIX}"))
+}
+
+pub fn run(dir: &Path, cfg: &Config, opts: &CheckOptions) -> Result<Vec<(PathBuf, Outcome)>> {
+    let fixtures = fs::collect_html_inputs(&[dir.to_path_buf()], true, false)
+        .with_context(|| format!("collect fixtures in {}", dir.display()))?;
+    let mut ignore: Vec<String> = VOLATILE_FIELDS.iter().map(|f| f.to_string()).collect();
+    ignore.extend(opts.ignore.iter().cloned());
+    let strip = Projection::new(&[], &ignore);
+
+    let mut outcomes = Vec::with_capacity(fixtures.len());
+    for fixture in fixtures {
+        let outcome = check_fixture(&fixture, cfg, &strip, opts.update)?;
+        outcomes.push((fixture, outcome));
+    }
+    Ok(outcomes)
+}
+
+fn check_fixture(
+    fixture: &PathBuf,
+    cfg: &Config,
+    strip: &Projection,
+    update: bool,
+) -> Result<Outcome> {
+    let actual = match parser::parse_file(fixture, cfg) {
+        Ok(doc) => output::document_value(&doc, strip, cfg.output.include_nulls)?,
+        Err(err) => return Ok(Outcome::ParseFailed(err.error)),
+    };
+    let expected_path = expected_path(fixture);
+    if update {
+        let mut json = serde_json::to_string_pretty(&actual)?;
+        json.push('\n');
+        std::fs::write(&expected_path, json)
+            .with_context(|| format!("write {}", expected_path.display()))?;
+        return Ok(Outcome::Updated);
+    }
+    if !expected_path.exists() {
+        return Ok(Outcome::MissingExpected);
+    }
+    let text = std::fs::read_to_string(&expected_path)
+        .with_context(|| format!("read {}", expected_path.display()))?;
+    let expected: Value = serde_json::from_str(&text)
+        .with_context(|| format!("parse {}", expected_path.display()))?;
+    let expected = strip.apply(expected);
+
+    let mut mismatches = Vec::new();
+    diff("", Some(&expected), Some(&actual), &mut mismatches);
+    Ok(if mismatches.is_empty() {
+        Outcome::Pass
+    } else {
+        Outcome::Mismatch(mismatches)
+    })
+}
+
+fn diff(path: &str, expected: Option<&Value>, actual: Option<&Value>, out: &mut Vec<Mismatch>) {
+    match (expected, actual) {
+        (Some(Value::Object(exp)), Some(Value::Object(act))) => {
+            let keys: std::collections::BTreeSet<&String> = exp.keys().chain(act.keys()).collect();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff(&child, exp.get(key), act.get(key), out);
+            }
+        }
+        (Some(Value::Array(exp)), Some(Value::Array(act))) => {
+            for i in 0..exp.len().max(act.len()) {
+                diff(&format!("{path}[{i}]"), exp.get(i), act.get(i), out);
+            }
+        }
+        (exp, act) if exp != act => out.push(Mismatch {
+            field: path.to_string(),
+            expected: exp.cloned(),
+            actual: act.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+pub fn write_report<W: Write>(out: &mut W, outcomes: &[(PathBuf, Outcome)]) -> Result<()> {
+    for (fixture, outcome) in outcomes {
+        let name = fixture.display();
+        match outcome {
+            Outcome::Pass => writeln!(out, "ok       {name}")?,
+            Outcome::Updated => writeln!(out, "updated  {name}")?,
+            Outcome::MissingExpected => writeln!(
+                out,
+                "missing  {name} (no {}; run with --update)",
+                expected_path(fixture).display()
+            )?,
+            Outcome::ParseFailed(error) => {
+                writeln!(out, "FAIL     {name}\n  parse failed: {error}")?
+            }
+            Outcome::Mismatch(mismatches) => {
+                writeln!(out, "FAIL     {name}")?;
+                for m in mismatches {
+                    match (&m.expected, &m.actual) {
+                        (Some(exp), Some(act)) => writeln!(
+                            out,
+                            "  {}: expected {}, got {}",
+                            m.field,
+                            short(exp),
+                            short(act)
+                        )?,
+                        (Some(exp), None) => {
+                            writeln!(out, "  {}: missing (expected {})", m.field, short(exp))?
+                        }
+                        (None, Some(act)) => {
+                            writeln!(out, "  {}: unexpected {}", m.field, short(act))?
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+    }
+    let failed = outcomes.iter().filter(|(_, o)| is_failure(o)).count();
+    writeln!(
+        out,
+        "\n{} fixtures, {} passed, {failed} failed",
+        outcomes.len(),
+        outcomes
+            .iter()
+            .filter(|(_, o)| matches!(o, Outcome::Pass))
+            .count()
+    )?;
+    Ok(())
+}
+
+pub fn is_failure(outcome: &Outcome) -> bool {
+    matches!(
+        outcome,
+        Outcome::ParseFailed(_) | Outcome::Mismatch(_) | Outcome::MissingExpected
+    )
+}
+
+pub(crate) fn short(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() <= MAX_VALUE_WIDTH {
+        return text;
+    }
+    let mut cut: String = text.chars().take(MAX_VALUE_WIDTH - 1).collect();
+    cut.push('…');
+    cut
+}
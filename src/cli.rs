@@ -1,7 +1,15 @@
-use anyhow::Result;
+use crate::config::{
+    Compression, DedupeBy, EnrichSource, OutputFormat, RedactTarget, SortBy, SortOrder,
+};
+use crate::merge::Winner;
+use crate::output::feed::FeedKind;
+use crate::output::shard::ShardSize;
+use crate::progress;
+use anyhow::{Context, Result};
 use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{Shell, generate};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tracing_subscriber::EnvFilter;
 
 #[derive(Parser, Debug)]
@@ -22,6 +30,12 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub no_color: bool,
 
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    #[arg(long, global = true)]
+    pub porcelain: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -29,9 +43,39 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Command {
     Parse(ParseArgs),
+    Site(SiteArgs),
+    Feed(FeedArgs),
+    Nfo(NfoArgs),
+    Export(ExportArgs),
+    Stats(StatsArgs),
+    Report(ReportArgs),
+    Timeline(TimelineArgs),
+    Query(QueryArgs),
+    Search(SearchArgs),
+    Index(IndexArgs),
+    Tui(TuiArgs),
+    Serve(ServeArgs),
+    Daemon(DaemonArgs),
+    Merge(MergeArgs),
+    Dedupe(DedupeArgs),
+    Enrich(EnrichArgs),
+    #[command(
+        about = "Rewrite a saved bundle in another format (reads JSON, NDJSON, MessagePack, CBOR \
+                 or SQLite; CSV, TSV, xlsx, Parquet and Arrow are output-only)"
+    )]
+    Convert(ConvertArgs),
+    Validate(ValidateArgs),
+    Verify(VerifyArgs),
+    Check(CheckArgs),
+    Bench(BenchArgs),
+    Explain(ExplainArgs),
+    Extract(ExtractArgs),
+    Schema(SchemaArgs),
     InitConfig(InitConfigArgs),
     PrintConfig(PrintConfigArgs),
+    Config(ConfigArgs),
     Completions(CompletionsArgs),
+    Man(ManArgs),
 }
 
 #[derive(Args, Debug)]
@@ -48,23 +92,636 @@ pub struct ParseArgs {
     #[arg(short, long, value_name = "PATH")]
     pub output: Option<PathBuf>,
 
+    #[arg(long, requires = "output")]
+    pub append: bool,
+
+    #[arg(long)]
+    pub no_atomic: bool,
+
+    #[arg(long, value_name = "PATH")]
+    pub errors_out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "output",
+        conflicts_with_all = ["append", "output_dir"]
+    )]
+    pub shard_size: Option<ShardSize>,
+
+    #[arg(long, value_name = "DIR", conflicts_with = "output")]
+    pub output_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "TEMPLATE", default_value = "{stem}.json")]
+    pub output_template: String,
+
     #[arg(long)]
     pub pretty: bool,
 
     #[arg(long)]
     pub ndjson: bool,
+
+    #[arg(long, value_enum, value_name = "FORMAT", conflicts_with = "ndjson")]
+    pub format: Option<FormatArg>,
+
+    #[arg(long)]
+    pub records: bool,
+
+    #[arg(long, value_enum, value_name = "CODEC")]
+    pub compress: Option<CompressArg>,
+
+    #[arg(long, value_name = "SEP")]
+    pub list_delimiter: Option<String>,
+
+    #[arg(long)]
+    pub explain: bool,
+
+    #[arg(long, value_name = "EXPR")]
+    pub filter: Option<String>,
+
+    #[arg(long, value_name = "PATHS", value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    #[arg(long, value_name = "PATHS", value_delimiter = ',')]
+    pub exclude_fields: Vec<String>,
+
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub sort: Option<SortArg>,
+
+    #[arg(long, value_enum, value_name = "ORDER")]
+    pub sort_order: Option<SortOrderArg>,
+
+    #[arg(long, value_enum, value_name = "WHAT", value_delimiter = ',')]
+    pub redact: Vec<RedactArg>,
+
+    #[arg(long)]
+    pub fail_on_error: bool,
+
+    #[arg(long)]
+    pub no_progress: bool,
+
+    #[arg(
+        long,
+        value_name = "FIELD",
+        conflicts_with_all = ["output", "output_dir", "ndjson", "format", "records", "fields", "exclude_fields", "sort"]
+    )]
+    pub get: Option<String>,
+
+    #[arg(long, value_name = "RATE", conflicts_with = "fail_on_error")]
+    pub max_error_rate: Option<ErrorRate>,
+}
+
+#[derive(Args, Debug)]
+pub struct SiteArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(short, long, value_name = "DIR", default_value = "site")]
+    pub out_dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct FeedArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value = "atom")]
+    pub format: FeedFormatArg,
+
+    #[arg(long, value_name = "N", default_value_t = 50)]
+    pub limit: usize,
+
+    #[arg(long, default_value = "Parsed releases")]
+    pub title: String,
+
+    #[arg(long, value_name = "URL")]
+    pub link: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum FeedFormatArg {
+    Atom,
+    Rss,
+}
+
+impl From<FeedFormatArg> for FeedKind {
+    fn from(value: FeedFormatArg) -> Self {
+        match value {
+            FeedFormatArg::Atom => FeedKind::Atom,
+            FeedFormatArg::Rss => FeedKind::Rss,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct NfoArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(short, long, value_name = "DIR", default_value = "nfo")]
+    pub out_dir: PathBuf,
+
+    #[arg(long, value_enum, default_value = "movie")]
+    pub root: NfoRootArg,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum NfoRootArg {
+    Movie,
+    Game,
+}
+
+impl NfoRootArg {
+    pub fn element(self) -> &'static str {
+        match self {
+            Self::Movie => "movie",
+            Self::Game => "game",
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(long, value_enum)]
+    pub to: ExportTarget,
+
+    #[arg(short, long, value_name = "DIR", default_value = "magnets")]
+    pub out_dir: PathBuf,
+
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_name = "TEMPLATE", default_value = "{slug}")]
+    pub dir_template: String,
+
+    #[arg(long, value_name = "TEMPLATE")]
+    pub out_template: Option<String>,
+
+    #[arg(long, value_name = "URL")]
+    pub url: Option<String>,
+
+    #[arg(long)]
+    pub username: Option<String>,
+
+    #[arg(long, env = "GAME_SCRAPER_TORRENT_PASSWORD", hide_env_values = true)]
+    pub password: Option<String>,
+
+    #[arg(long)]
+    pub category: Option<String>,
+
+    #[arg(long, value_name = "PATH")]
+    pub save_path: Option<String>,
+
+    #[arg(long)]
+    pub paused: bool,
+
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ExportTarget {
+    Qbittorrent,
+    Transmission,
+    Magnets,
+    Aria2,
+}
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    #[arg(value_name = "BUNDLE", conflicts_with = "index")]
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: ReportFormat,
+
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top: usize,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    #[arg(value_name = "BUNDLE", conflicts_with = "index")]
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: ReportFormat,
+
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top: usize,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long)]
+    pub domains: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct TimelineArgs {
+    #[arg(value_name = "BUNDLE", conflicts_with = "index")]
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value = "chart")]
+    pub format: TimelineFormat,
+
+    // Width of the longest bar.
+    #[arg(long, value_name = "COLUMNS", default_value_t = 50)]
+    pub width: usize,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum TimelineFormat {
+    Chart,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct QueryArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(value_name = "EXPRESSION")]
+    pub expression: String,
+
+    #[arg(short, long)]
+    pub raw: bool,
+
+    #[arg(long)]
+    pub compact: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    #[arg(value_name = "QUERY")]
+    pub query: String,
+
+    #[arg(short, long, value_name = "BUNDLE", conflicts_with = "index")]
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 10)]
+    pub limit: usize,
+
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct IndexArgs {
+    #[arg(value_name = "INPUT", required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    #[arg(long)]
+    pub follow_symlinks: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct TuiArgs {
+    #[arg(value_name = "BUNDLE", conflicts_with = "index")]
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    #[arg(value_name = "BUNDLE", conflicts_with = "index")]
+    pub input: Option<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    pub listen: String,
+}
+
+#[derive(Args, Debug)]
+pub struct DaemonArgs {
+    #[arg(value_name = "DIR", required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(long, value_name = "DIR")]
+    pub index: Option<PathBuf>,
+
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    #[arg(long)]
+    pub follow_symlinks: bool,
+
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    pub listen: String,
+
+    #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+    pub interval: u64,
+}
+
+#[derive(Args, Debug)]
+pub struct MergeArgs {
+    #[arg(value_name = "BUNDLE", required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<FormatArg>,
+
+    #[arg(long)]
+    pub pretty: bool,
+
+    #[arg(long, value_enum, default_value = "newest")]
+    pub on_conflict: OnConflictArg,
+
+    #[arg(long)]
+    pub prefer_non_null: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub from: Option<FormatArg>,
+
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub to: Option<FormatArg>,
+
+    #[arg(long)]
+    pub pretty: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    #[arg(value_name = "DIR")]
+    pub fixtures: PathBuf,
+
+    #[arg(long, value_name = "FIELD")]
+    pub ignore: Vec<String>,
+
+    #[arg(long)]
+    pub update: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    #[arg(value_name = "FILE")]
+    pub input: PathBuf,
+
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+#[command(group(clap::ArgGroup::new("kind").required(true).multiple(true)))]
+pub struct ExtractArgs {
+    #[arg(value_name = "INPUT", required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    #[arg(long)]
+    pub follow_symlinks: bool,
+
+    #[arg(long, group = "kind")]
+    pub magnets: bool,
+
+    #[arg(long, group = "kind")]
+    pub torrents: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    #[arg(value_name = "INPUT", required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 3)]
+    pub iterations: usize,
+
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub bundle: PathBuf,
+
+    #[arg(value_name = "INPUT")]
+    pub inputs: Vec<PathBuf>,
+
+    #[arg(short, long)]
+    pub recursive: bool,
+
+    #[arg(long)]
+    pub follow_symlinks: bool,
+
+    #[arg(long, value_enum, default_value = "table")]
+    pub format: ReportFormat,
+}
+
+#[derive(Args, Debug)]
+pub struct DedupeArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<FormatArg>,
+
+    #[arg(long)]
+    pub pretty: bool,
+
+    #[arg(long, value_enum, default_value = "doc-id")]
+    pub by: DedupeByArg,
+
+    // JSON list of the dropped documents; without it the list goes to stderr.
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct EnrichArgs {
+    #[arg(value_name = "BUNDLE")]
+    pub input: PathBuf,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    pub format: Option<FormatArg>,
+
+    #[arg(long)]
+    pub pretty: bool,
+
+    #[arg(long, value_enum)]
+    pub source: Option<EnrichSourceArg>,
+
+    #[arg(long, value_name = "SCORE")]
+    pub min_score: Option<f64>,
+
+    #[arg(long, env = "GAME_SCRAPER_STEAM_API_KEY", hide_env_values = true)]
+    pub steam_api_key: Option<String>,
+
+    #[arg(long, env = "GAME_SCRAPER_IGDB_TOKEN", hide_env_values = true)]
+    pub igdb_token: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum EnrichSourceArg {
+    Steam,
+    Igdb,
+    Hltb,
+}
+
+impl From<EnrichSourceArg> for EnrichSource {
+    fn from(value: EnrichSourceArg) -> Self {
+        match value {
+            EnrichSourceArg::Steam => EnrichSource::Steam,
+            EnrichSourceArg::Igdb => EnrichSource::Igdb,
+            EnrichSourceArg::Hltb => EnrichSource::Hltb,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum DedupeByArg {
+    DocId,
+    Sha256,
+    TitleSlug,
+    PostId,
+}
+
+impl From<DedupeByArg> for DedupeBy {
+    fn from(value: DedupeByArg) -> Self {
+        match value {
+            DedupeByArg::DocId => DedupeBy::DocId,
+            DedupeByArg::Sha256 => DedupeBy::Sha256,
+            DedupeByArg::TitleSlug => DedupeBy::TitleSlug,
+            DedupeByArg::PostId => DedupeBy::PostId,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum OnConflictArg {
+    Newest,
+    First,
+    Last,
+}
+
+impl From<OnConflictArg> for Winner {
+    fn from(value: OnConflictArg) -> Self {
+        match value {
+            OnConflictArg::Newest => Winner::Newest,
+            OnConflictArg::First => Winner::First,
+            OnConflictArg::Last => Winner::Last,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct SchemaArgs {
+    #[arg(long, value_enum, default_value = "bundle")]
+    pub target: SchemaTarget,
+
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum SchemaTarget {
+    Bundle,
+    Document,
 }
 
 #[derive(Args, Debug)]
 pub struct InitConfigArgs {
     #[arg(long, value_name = "PATH", default_value = "scrape.toml")]
     pub path: PathBuf,
+
+    #[arg(short, long)]
+    pub interactive: bool,
 }
 
 #[derive(Args, Debug)]
 pub struct PrintConfigArgs {
     #[arg(short, long, value_name = "PATH")]
     pub output: Option<PathBuf>,
+
+    #[arg(long)]
+    pub diff: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    Validate(ConfigValidateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigValidateArgs {
+    // Falls back to the global `--config`.
+    #[arg(value_name = "PATH")]
+    pub path: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -73,6 +730,13 @@ pub struct CompletionsArgs {
     pub shell: ShellArg,
 }
 
+#[derive(Args, Debug)]
+pub struct ManArgs {
+    // Without a directory only the top-level page is printed to stdout.
+    #[arg(short, long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum ShellArg {
     Bash,
@@ -94,6 +758,107 @@ impl From<ShellArg> for Shell {
     }
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum FormatArg {
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Markdown,
+    Table,
+    Msgpack,
+    Cbor,
+    Xlsx,
+    Sqlite,
+    Parquet,
+    Arrow,
+}
+
+impl From<FormatArg> for OutputFormat {
+    fn from(v: FormatArg) -> Self {
+        match v {
+            FormatArg::Json => OutputFormat::Json,
+            FormatArg::Ndjson => OutputFormat::Ndjson,
+            FormatArg::Csv => OutputFormat::Csv,
+            FormatArg::Tsv => OutputFormat::Tsv,
+            FormatArg::Markdown => OutputFormat::Markdown,
+            FormatArg::Table => OutputFormat::Table,
+            FormatArg::Msgpack => OutputFormat::Msgpack,
+            FormatArg::Cbor => OutputFormat::Cbor,
+            FormatArg::Xlsx => OutputFormat::Xlsx,
+            FormatArg::Sqlite => OutputFormat::Sqlite,
+            FormatArg::Parquet => OutputFormat::Parquet,
+            FormatArg::Arrow => OutputFormat::Arrow,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum CompressArg {
+    Auto,
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl From<CompressArg> for Compression {
+    fn from(v: CompressArg) -> Self {
+        match v {
+            CompressArg::Auto => Compression::Auto,
+            CompressArg::None => Compression::None,
+            CompressArg::Gzip => Compression::Gzip,
+            CompressArg::Zstd => Compression::Zstd,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum SortArg {
+    None,
+    ReleaseNumber,
+    EntryDatetime,
+    Path,
+}
+
+impl From<SortArg> for SortBy {
+    fn from(v: SortArg) -> Self {
+        match v {
+            SortArg::None => SortBy::None,
+            SortArg::ReleaseNumber => SortBy::ReleaseNumber,
+            SortArg::EntryDatetime => SortBy::EntryDatetime,
+            SortArg::Path => SortBy::Path,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum SortOrderArg {
+    Asc,
+    Desc,
+}
+
+impl From<SortOrderArg> for SortOrder {
+    fn from(v: SortOrderArg) -> Self {
+        match v {
+            SortOrderArg::Asc => SortOrder::Asc,
+            SortOrderArg::Desc => SortOrder::Desc,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum RedactArg {
+    Links,
+}
+
+impl From<RedactArg> for RedactTarget {
+    fn from(v: RedactArg) -> Self {
+        match v {
+            RedactArg::Links => RedactTarget::Links,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, ValueEnum)]
 pub enum LogFormat {
     Auto,
@@ -101,9 +866,42 @@ pub enum LogFormat {
     Json,
 }
 
+// `--max-error-rate`: a percentage (`5%`) or a fraction (`0.05`) of the
+// inputs that may fail to parse before the run exits non-zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorRate(pub f64);
+
+impl FromStr for ErrorRate {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let raw = raw.trim();
+        let (number, scale) = match raw.strip_suffix('%') {
+            Some(number) => (number.trim(), 100.0),
+            None => (raw, 1.0),
+        };
+        let rate = number
+            .parse::<f64>()
+            .map_err(|_| format!("invalid error rate `{raw}`"))?
+            / scale;
+        if !(0.0..=1.0).contains(&rate) {
+            return Err(format!("error rate `{raw}` must be between 0% and 100%"));
+        }
+        Ok(Self(rate))
+    }
+}
+
 pub fn init_tracing(cli: &Cli) -> Result<()> {
-    let filter =
-        EnvFilter::try_new(cli.log_level.clone()).unwrap_or_else(|_| EnvFilter::new("info"));
+    // Porcelain mode owns stderr: the status line is all a script sees there.
+    if cli.porcelain {
+        return Ok(());
+    }
+    let level = if cli.quiet { "error" } else { &cli.log_level };
+    let mut filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new("info"));
+    // tantivy logs every index commit at info; keep it quiet unless asked for.
+    if !cli.log_level.contains("tantivy") {
+        filter = filter.add_directive("tantivy=warn".parse()?);
+    }
     let ansi = !cli.no_color;
 
     match cli.log_format {
@@ -111,6 +909,7 @@ pub fn init_tracing(cli: &Cli) -> Result<()> {
             tracing_subscriber::fmt()
                 .with_env_filter(filter)
                 .with_ansi(ansi)
+                .with_writer(|| progress::Stderr)
                 .compact()
                 .init();
         }
@@ -118,6 +917,7 @@ pub fn init_tracing(cli: &Cli) -> Result<()> {
             tracing_subscriber::fmt()
                 .with_env_filter(filter)
                 .with_ansi(ansi)
+                .with_writer(std::io::stderr)
                 .json()
                 .init();
         }
@@ -131,3 +931,19 @@ pub fn print_completions(shell: ShellArg) {
     let shell: Shell = shell.into();
     generate(shell, &mut cmd, "game-scraper", &mut std::io::stdout());
 }
+
+// Writes `game-scraper.1` plus `game-scraper-<subcommand>.1` for every
+// subcommand into `dir`.
+pub fn write_man_pages(dir: Option<&Path>) -> Result<()> {
+    let cmd = Cli::command();
+    match dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+            clap_mangen::generate_to(cmd, dir)
+                .with_context(|| format!("write man pages to {}", dir.display()))
+        }
+        None => clap_mangen::Man::new(cmd)
+            .render(&mut std::io::stdout())
+            .context("write man page"),
+    }
+}
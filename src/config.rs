@@ -1,6 +1,9 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use tracing::warn;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
@@ -9,6 +12,12 @@ pub struct Config {
     pub scrape: ScrapeConfig,
     pub links: LinkConfig,
     pub profile: ProfileConfig,
+    pub taxonomy: TaxonomyConfig,
+    pub dedupe: DedupeConfig,
+    pub normalize: NormalizeConfig,
+    pub export: ExportConfig,
+    pub index: IndexConfig,
+    pub enrich: EnrichConfig,
 }
 
 impl Config {
@@ -21,23 +30,137 @@ impl Config {
                     .with_context(|| format!("read config {}", path.display()))?;
                 let parsed: Config = toml::from_str(&raw)
                     .with_context(|| format!("parse TOML {}", path.display()))?;
+                // Unknown keys are not fatal here, but a typo silently falling
+                // back to the default is worse than a warning.
+                for (offset, message) in crate::config_check::check(&raw) {
+                    warn!(
+                        location = %format!("{}:{}", path.display(), crate::config_check::line_col(&raw, offset)),
+                        "{message}"
+                    );
+                }
                 cfg = parsed;
             }
         }
 
+        if crate::parser::normalize::parse_utc_offset(&cfg.profile.default_utc_offset).is_none() {
+            warn!(
+                value = %cfg.profile.default_utc_offset,
+                "invalid [profile] default_utc_offset (expected +HH:MM); using +00:00"
+            );
+        }
+
         Ok(cfg)
     }
 
     pub fn to_pretty_toml(&self) -> Result<String> {
-        toml::to_string_pretty(self).context("serialize config to TOML")
+        toml::to_string_pretty(&self.redacted()).context("serialize config to TOML")
+    }
+
+    // Only the keys whose value differs from `Config::default()`, as TOML.
+    pub fn to_diff_toml(&self) -> Result<String> {
+        let ours = toml::Table::try_from(self.redacted()).context("serialize config")?;
+        let defaults = toml::Table::try_from(Config::default()).context("serialize defaults")?;
+        toml::to_string_pretty(&diff_table(ours, &defaults)).context("serialize config diff")
+    }
+
+    // Printed configs end up in bug reports; the torrent client password never
+    // goes into one.
+    fn redacted(&self) -> Config {
+        let mut cfg = self.clone();
+        if !cfg.export.password.is_empty() {
+            cfg.export.password = REDACTED.into();
+        }
+        cfg
     }
 }
 
+const REDACTED: &str = "<redacted>";
+
+fn diff_table(ours: toml::Table, defaults: &toml::Table) -> toml::Table {
+    ours.into_iter()
+        .filter_map(|(key, value)| match (value, defaults.get(&key)) {
+            (toml::Value::Table(table), Some(toml::Value::Table(default)))
+                // A map section that dropped default entries was replaced
+                // wholesale; listing only the changed keys would hide that.
+                if default.keys().all(|k| table.contains_key(k)) =>
+            {
+                let table = diff_table(table, default);
+                (!table.is_empty()).then_some((key, toml::Value::Table(table)))
+            }
+            (value, default) => (default != Some(&value)).then_some((key, value)),
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct OutputConfig {
     pub pretty_json: bool,
     pub include_nulls: bool,
     pub ndjson: bool,
+    pub format: OutputFormat,
+    pub records: bool,
+    pub compress: Compression,
+    pub atomic: bool,
+    pub list_delimiter: String,
+    pub explain: bool,
+    pub fields: Vec<String>,
+    pub exclude_fields: Vec<String>,
+    pub sort: SortBy,
+    pub sort_order: SortOrder,
+    pub redact: Vec<RedactTarget>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Ndjson,
+    Csv,
+    Tsv,
+    Markdown,
+    Table,
+    Msgpack,
+    Cbor,
+    Xlsx,
+    Sqlite,
+    Parquet,
+    Arrow,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    #[default]
+    Auto,
+    None,
+    Gzip,
+    Zstd,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactTarget {
+    Links,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    #[default]
+    None,
+    ReleaseNumber,
+    EntryDatetime,
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
 }
 
 impl Default for OutputConfig {
@@ -46,79 +169,166 @@ impl Default for OutputConfig {
             pretty_json: true,
             include_nulls: false,
             ndjson: false,
+            format: OutputFormat::Json,
+            records: false,
+            compress: Compression::Auto,
+            atomic: true,
+            list_delimiter: "; ".to_string(),
+            explain: false,
+            fields: vec![],
+            exclude_fields: vec![],
+            sort: SortBy::None,
+            sort_order: SortOrder::Asc,
+            redact: vec![],
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ScrapeConfig {
+    pub doc_id: bool,
     pub page_title: bool,
     pub canonical_url: bool,
     pub meta_tags: bool,
+    pub head_links: bool,
+    pub hreflang: bool,
+    pub generator: bool,
 
     pub post_id: bool,
+    pub slug: bool,
     pub categories: bool,
+    pub genre_taxonomy: bool,
+    pub tag_aliases: bool,
     pub wp_tags: bool,
+    pub breadcrumbs: bool,
 
     pub entry_title: bool,
     pub entry_datetime: bool,
+    pub entry_modified: bool,
+    pub datetime_normalize: bool,
     pub author: bool,
     pub comments_count: bool,
+    pub static_comments: bool,
+    pub poll: bool,
 
     pub release_number: bool,
+    pub release_number_gaps: bool,
     pub game_title_line: bool,
+    pub release_group: bool,
+    pub title_slug: bool,
+    pub version: bool,
     pub genres_tags: bool,
     pub companies: bool,
+    pub developers_publishers: bool,
     pub languages: bool,
+    pub languages_parsed: bool,
     pub original_size: bool,
     pub repack_size: bool,
+    pub size_bytes: bool,
+    pub pricing: bool,
+    pub age_ratings: bool,
+    pub steam_appids: bool,
+    pub gog_ids: bool,
+    pub review_scores: bool,
 
+    pub content_language: bool,
     pub spoiler_sections: bool,
+    pub direct_download_links: bool,
+    pub site_donations: bool,
     pub download_section_presence: bool,
+    pub download_sections: bool,
     pub torrent_file: bool,
     pub torrent_file_name: bool,
     pub torrent_file_link: bool,
     pub magnet: bool,
+
+    pub images: bool,
+    pub raw_html: bool,
+
+    pub confidence: bool,
 }
 
 impl Default for ScrapeConfig {
     fn default() -> Self {
         Self {
+            doc_id: true,
             page_title: true,
             canonical_url: true,
             meta_tags: true,
+            head_links: true,
+            hreflang: true,
+            generator: true,
 
             post_id: true,
+            slug: true,
             categories: true,
+            genre_taxonomy: true,
+            tag_aliases: true,
             wp_tags: true,
+            breadcrumbs: true,
 
             entry_title: true,
             entry_datetime: true,
+            entry_modified: true,
+            datetime_normalize: true,
             author: true,
             comments_count: true,
+            static_comments: true,
+            poll: true,
 
             release_number: true,
+            release_number_gaps: true,
             game_title_line: true,
+            release_group: true,
+            title_slug: true,
+            version: true,
             genres_tags: true,
             companies: true,
+            developers_publishers: true,
             languages: true,
+            languages_parsed: true,
             original_size: true,
             repack_size: true,
+            size_bytes: true,
+            pricing: true,
+            age_ratings: true,
+            steam_appids: true,
+            gog_ids: true,
+            review_scores: true,
 
+            content_language: true,
             spoiler_sections: true,
+            direct_download_links: false,
+            site_donations: false,
             download_section_presence: true,
+            download_sections: true,
             torrent_file: true,
             torrent_file_name: true,
             torrent_file_link: true,
             magnet: true,
+
+            images: true,
+            raw_html: false,
+
+            confidence: true,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LinkConfig {
     pub domain_counts: bool,
     pub ignore_magnet: bool,
+    pub internal_external: bool,
+    pub section_density: bool,
+    pub classify_hosts: bool,
+    pub anchor_inventory: bool,
+    pub resolve_relative: bool,
+    pub strip_tracking_params: bool,
+    pub tracking_params: Vec<String>,
+    pub host_categories: BTreeMap<String, String>,
 }
 
 impl Default for LinkConfig {
@@ -126,14 +336,64 @@ impl Default for LinkConfig {
         Self {
             domain_counts: true,
             ignore_magnet: true,
+            internal_external: true,
+            section_density: true,
+            classify_hosts: true,
+            anchor_inventory: false,
+            resolve_relative: true,
+            strip_tracking_params: true,
+            tracking_params: [
+                "utm_*", "ref", "ref_src", "fbclid", "gclid", "yclid", "mc_cid", "mc_eid", "_ga",
+                "igshid",
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect(),
+            host_categories: [
+                ("1337x.to", "torrent_index"),
+                ("rutor.info", "torrent_index"),
+                ("rutor.org", "torrent_index"),
+                ("thepiratebay.org", "torrent_index"),
+                ("rarbg.to", "torrent_index"),
+                ("tapochek.net", "torrent_index"),
+                ("nyaa.si", "torrent_index"),
+                ("fuckingfast.co", "ddl_filehoster"),
+                ("datanodes.to", "ddl_filehoster"),
+                ("multiup.io", "ddl_filehoster"),
+                ("mega.nz", "ddl_filehoster"),
+                ("mediafire.com", "ddl_filehoster"),
+                ("1fichier.com", "ddl_filehoster"),
+                ("gofile.io", "ddl_filehoster"),
+                ("pixeldrain.com", "ddl_filehoster"),
+                ("qiwi.gg", "ddl_filehoster"),
+                ("filecrypt.cc", "ddl_filehoster"),
+                ("uploadhaven.com", "ddl_filehoster"),
+                ("paste.fitgirl-repacks.site", "paste"),
+                ("pastebin.com", "paste"),
+                ("privatebin.net", "paste"),
+                ("bit.ly", "ad_shortener"),
+                ("adf.ly", "ad_shortener"),
+                ("ouo.io", "ad_shortener"),
+                ("shorte.st", "ad_shortener"),
+                ("linkvertise.com", "ad_shortener"),
+            ]
+            .into_iter()
+            .map(|(host, category)| (host.to_string(), category.to_string()))
+            .collect(),
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProfileConfig {
     pub wordpress_release_layout: bool,
     pub spoiler_denylist: Vec<String>,
+    pub raw_html_selectors: Vec<String>,
+    pub release_groups: Vec<String>,
+    pub default_utc_offset: String,
+    pub developer_selectors: Vec<String>,
+    pub publisher_selectors: Vec<String>,
 }
 
 impl Default for ProfileConfig {
@@ -146,6 +406,241 @@ impl Default for ProfileConfig {
                 "magnet".into(),
                 "torrent".into(),
             ],
+            raw_html_selectors: vec!["div.entry-content".into()],
+            default_utc_offset: "+00:00".into(),
+            developer_selectors: vec![
+                "#developers_list a".into(),
+                "[selenium-id='ProductDetailsDeveloper'] a".into(),
+            ],
+            publisher_selectors: vec![
+                ".dev_row:has(#developers_list) + .dev_row .summary a".into(),
+                "[selenium-id='ProductDetailsPublisher'] a".into(),
+            ],
+            release_groups: vec![
+                "FitGirl".into(),
+                "DODI".into(),
+                "Masquerade".into(),
+                "ElAmigos".into(),
+                "KaOs".into(),
+                "xatab".into(),
+                "R.G. Mechanics".into(),
+                "Decepticon".into(),
+                "Chovka".into(),
+                "Darck".into(),
+                "Qoob".into(),
+                "CorePack".into(),
+            ],
+        }
+    }
+}
+
+impl ProfileConfig {
+    // An invalid offset was already warned about when the config loaded.
+    pub fn utc_offset(&self) -> chrono::FixedOffset {
+        crate::parser::normalize::parse_utc_offset(&self.default_utc_offset)
+            .unwrap_or(chrono::FixedOffset::east_opt(0).expect("valid offset"))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaxonomyConfig {
+    pub genres: BTreeMap<String, String>,
+    pub tag_aliases: BTreeMap<String, String>,
+}
+
+impl Default for TaxonomyConfig {
+    fn default() -> Self {
+        Self {
+            genres: [
+                ("action games", "Action"),
+                ("adventure games", "Adventure"),
+                ("arcade", "Arcade"),
+                ("fighting", "Fighting"),
+                ("horror", "Horror"),
+                ("platformer", "Platformer"),
+                ("puzzle", "Puzzle"),
+                ("racing", "Racing"),
+                ("rpg", "RPG"),
+                ("role-playing", "RPG"),
+                ("shooter", "Shooter"),
+                ("fps", "Shooter"),
+                ("simulation", "Simulation"),
+                ("simulator", "Simulation"),
+                ("sports", "Sports"),
+                ("strategy", "Strategy"),
+                ("rts", "Strategy"),
+                ("survival", "Survival"),
+            ]
+            .into_iter()
+            .map(|(raw, genre)| (raw.to_string(), genre.to_string()))
+            .collect(),
+            tag_aliases: [
+                ("rpg", "RPG"),
+                ("role playing", "RPG"),
+                ("jrpg", "JRPG"),
+                ("horror", "Horror"),
+                ("horrors", "Horror"),
+                ("survival horror", "Horror"),
+                ("fps", "FPS"),
+                ("shooter", "Shooter"),
+                ("shooters", "Shooter"),
+                ("3d", "3D"),
+                ("2d", "2D"),
+                ("first person", "First-person"),
+                ("third person", "Third-person"),
+                ("open world", "Open World"),
+                ("sci fi", "Sci-Fi"),
+                ("science fiction", "Sci-Fi"),
+                ("strategy", "Strategy"),
+                ("strategies", "Strategy"),
+                ("simulator", "Simulation"),
+                ("simulation", "Simulation"),
+                ("racing", "Racing"),
+                ("racings", "Racing"),
+            ]
+            .into_iter()
+            .map(|(alias, canonical)| (alias.to_string(), canonical.to_string()))
+            .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupeBy {
+    #[default]
+    None,
+    PostId,
+    DocId,
+    Sha256,
+    TitleSlug,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupeConfig {
+    pub by: DedupeBy,
+    pub merge: bool,
+    pub infohash_index: bool,
+}
+
+impl Default for DedupeConfig {
+    fn default() -> Self {
+        Self {
+            by: DedupeBy::None,
+            merge: false,
+            infohash_index: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalizeConfig {
+    pub enabled: bool,
+    pub nfc: bool,
+    pub ascii_punctuation: bool,
+    pub strip_zero_width: bool,
+    pub transliterate_cyrillic: bool,
+}
+
+impl Default for NormalizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            nfc: true,
+            ascii_punctuation: true,
+            strip_zero_width: true,
+            transliterate_cyrillic: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ExportConfig {
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub category: String,
+    pub save_path: String,
+    pub paused: bool,
+    pub genre_categories: BTreeMap<String, String>,
+}
+
+impl fmt::Debug for ExportConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let password = if self.password.is_empty() {
+            ""
+        } else {
+            REDACTED
+        };
+        f.debug_struct("ExportConfig")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &password)
+            .field("category", &self.category)
+            .field("save_path", &self.save_path)
+            .field("paused", &self.paused)
+            .field("genre_categories", &self.genre_categories)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexConfig {
+    pub dir: PathBuf,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("index"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichSource {
+    #[default]
+    Steam,
+    Igdb,
+    Hltb,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnrichConfig {
+    pub source: EnrichSource,
+    pub min_score: f64,
+    pub steam_app_list: String,
+    pub steam_api_key: String,
+    pub steam_release_year: bool,
+    pub igdb_client_id: String,
+    pub igdb_access_token: String,
+    pub igdb_dump: String,
+    pub hltb_url: String,
+    pub hltb_cache: String,
+    pub hltb_cache_days: u32,
+}
+
+impl Default for EnrichConfig {
+    fn default() -> Self {
+        Self {
+            source: EnrichSource::Steam,
+            min_score: 0.9,
+            steam_app_list: String::new(),
+            steam_api_key: String::new(),
+            steam_release_year: true,
+            igdb_client_id: String::new(),
+            igdb_access_token: String::new(),
+            igdb_dump: String::new(),
+            hltb_url: "https://howlongtobeat.com/api/search".to_string(),
+            hltb_cache: "hltb-cache.json".to_string(),
+            hltb_cache_days: 30,
         }
     }
 }
@@ -155,48 +650,290 @@ pub fn write_default_config(path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
-const DEFAULT_CONFIG_TOML: &str = r#"# game-scraper configuration
+const DEFAULT_CONFIG_TOML: &str = r##"# game-scraper configuration
 # Field toggles let you control exactly what is extracted.
 
 [output]
 pretty_json = true
 include_nulls = false
 ndjson = false
+# "json" (bundle), "ndjson", "csv", "tsv", "markdown" (catalogue), "table"
+# (aligned terminal quick view), "msgpack", "cbor", "xlsx", "sqlite",
+# "parquet" or "arrow" (IPC stream).
+# `ndjson = true` is the same as format = "ndjson". "msgpack"/"cbor" encode
+# the bundle, or with `records = true` the NDJSON record sequence as
+# back-to-back values. CSV/TSV, xlsx, Parquet and Arrow write one row per
+# document with the `fields` columns (or a fixed default set); CSV/TSV join
+# list values with `list_delimiter`. "sqlite" upserts into the database at
+# `parse --output`. "parquet" and "arrow" need a build with
+# `--features parquet` / `--features arrow`.
+format = "json"
+records = false
+# Compress output on the fly: "gzip", "zstd", "none", or "auto" (from a
+# `.gz`/`.zst` output extension).
+compress = "auto"
+# Write to a temporary file and rename it into place on success, so an
+# interrupted run never leaves a truncated output (same as omitting
+# `parse --no-atomic`). Pipes and device files are always written directly.
+atomic = true
+list_delimiter = "; "
+# Record which selector/regex/profile produced each field (`provenance`).
+explain = false
+# Document field projection by dot-path, e.g. ["post.entry_title",
+# "release.repack_size_raw"]; empty keeps everything. Exclusions run after.
+fields = []
+exclude_fields = []
+# Document order: "none" (input order), "release_number", "entry_datetime" or
+# "path"; documents missing the key sort last either way.
+sort = "none"
+sort_order = "asc"
+# ["links"] strips magnet URIs, infohashes, torrent/direct links and mirror
+# hrefs (counts, hosts and categories are kept) for shareable bundles.
+redact = []
 
 [scrape]
+# Deterministic UUIDv5 over the canonical URL (or site + post_id).
+doc_id = true
 page_title = true
 canonical_url = true
 meta_tags = true
+head_links = true
+hreflang = true
+generator = true
 
 post_id = true
+slug = true
 categories = true
+genre_taxonomy = true
+tag_aliases = true
 wp_tags = true
+breadcrumbs = true
 
 entry_title = true
 entry_datetime = true
+entry_modified = true
+datetime_normalize = true
 author = true
 comments_count = true
+static_comments = true
+poll = true
 
 release_number = true
+# Report missing numbers between the lowest and highest release number in the
+# bundle under `stats.release_gaps`.
+release_number_gaps = true
 game_title_line = true
+release_group = true
+title_slug = true
+version = true
 genres_tags = true
 companies = true
+developers_publishers = true
 languages = true
+languages_parsed = true
 original_size = true
 repack_size = true
+size_bytes = true
+pricing = true
+age_ratings = true
+steam_appids = true
+gog_ids = true
+review_scores = true
 
+# whatlang detection on the post body (ISO 639-3 code, name, script).
+content_language = true
 spoiler_sections = true
+direct_download_links = false
+site_donations = false
 download_section_presence = true
+download_sections = true
 torrent_file = true
 torrent_file_name = true
 torrent_file_link = true
 magnet = true
 
+images = true
+raw_html = false
+
+# Score heuristic fields (0.0-1.0) plus an overall document confidence.
+confidence = true
+
 [links]
 domain_counts = true
 ignore_magnet = true
+internal_external = true
+section_density = true
+classify_hosts = true
+anchor_inventory = false
+# Resolve relative hrefs against <base href> / the canonical URL so they are
+# counted and extracted like absolute links.
+resolve_relative = true
+# Drop tracking query parameters from torrent/mirror/anchor links. A trailing
+# `*` matches any parameter with that prefix.
+strip_tracking_params = true
+tracking_params = ["utm_*", "ref", "ref_src", "fbclid", "gclid", "yclid", "mc_cid", "mc_eid", "_ga", "igshid"]
+
+# Host -> category map; subdomains of a listed host match too.
+[links.host_categories]
+"1337x.to" = "torrent_index"
+"rutor.info" = "torrent_index"
+"rutor.org" = "torrent_index"
+"thepiratebay.org" = "torrent_index"
+"rarbg.to" = "torrent_index"
+"tapochek.net" = "torrent_index"
+"nyaa.si" = "torrent_index"
+"fuckingfast.co" = "ddl_filehoster"
+"datanodes.to" = "ddl_filehoster"
+"multiup.io" = "ddl_filehoster"
+"mega.nz" = "ddl_filehoster"
+"mediafire.com" = "ddl_filehoster"
+"1fichier.com" = "ddl_filehoster"
+"gofile.io" = "ddl_filehoster"
+"pixeldrain.com" = "ddl_filehoster"
+"qiwi.gg" = "ddl_filehoster"
+"filecrypt.cc" = "ddl_filehoster"
+"uploadhaven.com" = "ddl_filehoster"
+"paste.fitgirl-repacks.site" = "paste"
+"pastebin.com" = "paste"
+"privatebin.net" = "paste"
+"bit.ly" = "ad_shortener"
+"adf.ly" = "ad_shortener"
+"ouo.io" = "ad_shortener"
+"shorte.st" = "ad_shortener"
+"linkvertise.com" = "ad_shortener"
 
 [profile]
 wordpress_release_layout = true
 spoiler_denylist = ["click to show direct links", "direct links", "magnet", "torrent"]
-"#;
+raw_html_selectors = ["div.entry-content"]
+# Offset assumed for timestamps that carry no timezone.
+default_utc_offset = "+00:00"
+# Selectors tried before the "Developer:"/"Publisher:" label heuristics.
+developer_selectors = ["#developers_list a", "[selenium-id='ProductDetailsDeveloper'] a"]
+publisher_selectors = [".dev_row:has(#developers_list) + .dev_row .summary a", "[selenium-id='ProductDetailsPublisher'] a"]
+release_groups = ["FitGirl", "DODI", "Masquerade", "ElAmigos", "KaOs", "xatab", "R.G. Mechanics", "Decepticon", "Chovka", "Darck", "Qoob", "CorePack"]
+
+[dedupe]
+# "none" keeps every document; "post_id" keeps only the newest save of each
+# post (by modified time, then file mtime). "doc_id", "sha256" (identical
+# files) and "title_slug" (same game) group documents by those keys instead.
+by = "none"
+# Fold older saves into the newest one as a `history` array instead of
+# dropping them.
+merge = false
+# Bundle-level map of magnet btih infohash -> document paths; documents that
+# share a payload list each other in `shares_payload_with`. Needs every
+# document before writing, so NDJSON output is buffered instead of streamed.
+infohash_index = false
+
+# Text cleanup for extracted strings, off unless `enabled = true`. Raw input
+# (`source`, `raw_html`) and URL, hash and address fields are never touched.
+[normalize]
+enabled = false
+# Unicode NFC composition.
+nfc = true
+# Smart quotes, dashes, ellipses and non-breaking spaces -> ASCII.
+ascii_punctuation = true
+# Drop zero-width spaces/joiners, BOMs and soft hyphens.
+strip_zero_width = true
+# Add `*_latin` copies of Cyrillic titles, categories and tags (originals are
+# kept) for matching against English titles.
+transliterate_cyrillic = false
+
+# Torrent client for `export --to qbittorrent|transmission`. An empty `url`
+# uses the client's default local Web UI / RPC address; empty credentials skip
+# login. Prefer GAME_SCRAPER_TORRENT_PASSWORD or `export --password` to a
+# password here; `print-config` shows a set one as `<redacted>`.
+[export]
+url = ""
+username = ""
+password = ""
+# qBittorrent category / Transmission label for releases whose genres have no
+# `genre_categories` entry; empty adds them uncategorised.
+category = ""
+save_path = ""
+paused = false
+
+# Genre -> category/label (case-insensitive); a release takes the first of its
+# genres that has an entry.
+[export.genre_categories]
+
+# Persistent store written by `index` (SQLite catalogue plus tantivy search
+# index); `search`, `stats`, `tui` and `serve` read it when no bundle is
+# given.
+[index]
+dir = "index"
+
+# Metadata sources for `enrich`: "steam" matches titles against the Steam app
+# list, "igdb" against IGDB, "hltb" looks up HowLongToBeat playtimes. Titles
+# are compared by their normalized slug; a fuzzy match needs at least
+# `min_score` similarity (0-1).
+[enrich]
+source = "steam"
+min_score = 0.9
+# Local app list dump (GetAppList JSON); empty fetches the list with
+# `steam_api_key` (or GAME_SCRAPER_STEAM_API_KEY).
+steam_app_list = ""
+steam_api_key = ""
+# Look up each matched app's release year on the Steam store.
+steam_release_year = true
+# Twitch client ID and app access token for the IGDB API (the token may come
+# from GAME_SCRAPER_IGDB_TOKEN), or a local JSON dump of IGDB game objects.
+igdb_client_id = ""
+igdb_access_token = ""
+igdb_dump = ""
+# HowLongToBeat search endpoint (the site moves it now and then) and a local
+# cache of lookups, refreshed after `hltb_cache_days`; empty disables it.
+hltb_url = "https://howlongtobeat.com/api/search"
+hltb_cache = "hltb-cache.json"
+hltb_cache_days = 30
+
+# Category -> genre map (case-insensitive). Unmapped categories ending in
+# "Games" fall back to the name without that suffix.
+[taxonomy.genres]
+"action games" = "Action"
+"adventure games" = "Adventure"
+"arcade" = "Arcade"
+"fighting" = "Fighting"
+"horror" = "Horror"
+"platformer" = "Platformer"
+"puzzle" = "Puzzle"
+"racing" = "Racing"
+"rpg" = "RPG"
+"role-playing" = "RPG"
+"shooter" = "Shooter"
+"fps" = "Shooter"
+"simulation" = "Simulation"
+"simulator" = "Simulation"
+"sports" = "Sports"
+"strategy" = "Strategy"
+"rts" = "Strategy"
+"survival" = "Survival"
+
+# Tag alias -> canonical tag, applied to `genres_tags` and `wp_tags` (output as
+# `*_canonical`). Matching ignores case and treats `-`/`_` as spaces; tags
+# without an alias are kept as-is.
+[taxonomy.tag_aliases]
+"rpg" = "RPG"
+"role playing" = "RPG"
+"jrpg" = "JRPG"
+"horror" = "Horror"
+"horrors" = "Horror"
+"survival horror" = "Horror"
+"fps" = "FPS"
+"shooter" = "Shooter"
+"shooters" = "Shooter"
+"3d" = "3D"
+"2d" = "2D"
+"first person" = "First-person"
+"third person" = "Third-person"
+"open world" = "Open World"
+"sci fi" = "Sci-Fi"
+"science fiction" = "Sci-Fi"
+"strategy" = "Strategy"
+"strategies" = "Strategy"
+"simulator" = "Simulation"
+"simulation" = "Simulation"
+"racing" = "Racing"
+"racings" = "Racing"
+"##;
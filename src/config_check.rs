@@ -0,0 +1,104 @@
+use crate::config::Config;
+use crate::validate::Issue;
+use anyhow::{Context, Result};
+use std::path::Path;
+use toml::de::{DeTable, DeValue};
+use toml::{Table, Value};
+
+// Upper bound on the edit distance for a "did you mean" suggestion; short keys
+// get a tighter one so `by` does not suggest every two-letter key.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+pub fn validate_file(path: &Path) -> Result<Vec<Issue>> {
+    let raw =
+        std::fs::read_to_string(path).with_context(|| format!("read config {}", path.display()))?;
+    Ok(check(&raw)
+        .into_iter()
+        .map(|(offset, message)| Issue {
+            location: format!("{}:{}", path.display(), line_col(&raw, offset)),
+            message,
+        })
+        .collect())
+}
+
+// Byte offset and message of each problem. A syntax or type error stops the
+// check; otherwise every key the config does not know is reported.
+pub fn check(raw: &str) -> Vec<(usize, String)> {
+    let doc = match DeTable::parse(raw) {
+        Ok(doc) => doc,
+        Err(err) => return vec![error_at(&err)],
+    };
+    let cfg: Config = match toml::from_str(raw) {
+        Ok(cfg) => cfg,
+        Err(err) => return vec![error_at(&err)],
+    };
+    // Serializing what was parsed gives every field of every section, and
+    // map-valued fields keep exactly the user's keys, so only keys serde
+    // skipped are left over.
+    let Ok(Value::Table(known)) = Value::try_from(&cfg) else {
+        return Vec::new();
+    };
+    let mut issues = Vec::new();
+    unknown_keys("", doc.get_ref(), &known, &mut issues);
+    issues.sort_by_key(|(offset, _)| *offset);
+    issues
+}
+
+fn unknown_keys(path: &str, table: &DeTable, known: &Table, issues: &mut Vec<(usize, String)>) {
+    for (key, value) in table {
+        let name: &str = key.get_ref();
+        let dotted = if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{path}.{name}")
+        };
+        match known.get(name) {
+            Some(known) => nested(&dotted, value.get_ref(), known, issues),
+            None => {
+                let message = match suggest(name, known.keys()) {
+                    Some(suggestion) => {
+                        format!("unknown key `{dotted}`; did you mean `{suggestion}`?")
+                    }
+                    None => format!("unknown key `{dotted}`"),
+                };
+                issues.push((key.span().start, message));
+            }
+        }
+    }
+}
+
+fn nested(path: &str, value: &DeValue, known: &Value, issues: &mut Vec<(usize, String)>) {
+    match (value, known) {
+        (DeValue::Table(table), Value::Table(known)) => unknown_keys(path, table, known, issues),
+        (DeValue::Array(items), Value::Array(known)) => {
+            for (i, (item, known)) in items.iter().zip(known).enumerate() {
+                nested(&format!("{path}[{i}]"), item.get_ref(), known, issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    let limit = (name.chars().count() / 3).clamp(1, MAX_SUGGESTION_DISTANCE);
+    candidates
+        .map(|candidate| (strsim::damerau_levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn error_at(err: &toml::de::Error) -> (usize, String) {
+    (
+        err.span().map_or(0, |span| span.start),
+        err.message().trim().to_string(),
+    )
+}
+
+// 1-based `line:column` of a byte offset.
+pub fn line_col(raw: &str, offset: usize) -> String {
+    let before = raw.get(..offset).unwrap_or(raw);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    format!("{line}:{column}")
+}
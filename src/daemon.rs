@@ -0,0 +1,101 @@
+use crate::config::Config;
+use crate::fs;
+use crate::parser;
+use crate::serve::SharedCatalogue;
+use crate::store::Store;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+// Files modified more recently than this may still be being written by the
+// browser; they are picked up on a later scan.
+const SETTLE: Duration = Duration::from_secs(2);
+
+pub struct WatchOptions {
+    pub inputs: Vec<PathBuf>,
+    pub recursive: bool,
+    pub follow_symlinks: bool,
+    pub interval: Duration,
+}
+
+// Size and mtime (epoch seconds) as `source.bytes` / `source.mtime` record
+// them, so files already in the store are not parsed again after a restart.
+type Fingerprint = (u64, Option<i64>);
+
+// Polls the input directories and feeds new or changed saves through the
+// parser into the store and the served catalogue. Runs until the process
+// exits; a failed scan is logged and retried on the next tick.
+pub fn watch(cfg: &Config, store: &mut Store, catalogue: &SharedCatalogue, opts: &WatchOptions) {
+    let mut seen: HashMap<PathBuf, Fingerprint> = match store.sources() {
+        Ok(sources) => sources
+            .into_iter()
+            .map(|(path, print)| (PathBuf::from(path), print))
+            .collect(),
+        Err(err) => {
+            warn!(error = %format!("{err:#}"), "could not read indexed sources; re-parsing all inputs");
+            HashMap::new()
+        }
+    };
+    loop {
+        if let Err(err) = scan(cfg, store, catalogue, opts, &mut seen) {
+            warn!(error = %format!("{err:#}"), "scan failed");
+        }
+        std::thread::sleep(opts.interval);
+    }
+}
+
+fn scan(
+    cfg: &Config,
+    store: &mut Store,
+    catalogue: &SharedCatalogue,
+    opts: &WatchOptions,
+    seen: &mut HashMap<PathBuf, Fingerprint>,
+) -> Result<()> {
+    let files = fs::collect_html_inputs(&opts.inputs, opts.recursive, opts.follow_symlinks)
+        .context("collect inputs")?;
+    let settled_before = SystemTime::now() - SETTLE;
+    let mut changed = Vec::new();
+    for path in files {
+        let Ok(meta) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let modified = meta.modified().ok();
+        if modified.is_some_and(|m| m > settled_before) {
+            debug!(path = %path.display(), "waiting for file to settle");
+            continue;
+        }
+        let print = (
+            meta.len(),
+            modified
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64),
+        );
+        if seen.get(&path) != Some(&print) {
+            changed.push((path, print));
+        }
+    }
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    let paths: Vec<PathBuf> = changed.iter().map(|(path, _)| path.clone()).collect();
+    let bundle = parser::parse_many(&paths, cfg).context("parse inputs")?;
+    store.ingest(&bundle)?;
+    let documents = bundle.documents.len();
+    match catalogue.write() {
+        Ok(mut catalogue) => catalogue.upsert(bundle.documents),
+        Err(_) => warn!("catalogue lock poisoned; served data is stale"),
+    }
+    // Failed files are remembered too, so they are retried only once they
+    // change again.
+    seen.extend(changed);
+    info!(
+        files = paths.len(),
+        documents,
+        errors = bundle.errors.len(),
+        "indexed new saves"
+    );
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use crate::config::{DedupeBy, DedupeConfig};
+use crate::model::{DiscardedDuplicate, ParsedDocument, SaveSnapshot};
+use crate::parser::links;
+use crate::parser::normalize::parse_datetime;
+use chrono::{DateTime, FixedOffset};
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use tracing::debug;
+use uuid::Uuid;
+
+const HISTORY_IGNORED_FIELDS: &[&str] = &["source", "history"];
+
+pub fn dedupe_documents(
+    docs: &mut Vec<ParsedDocument>,
+    cfg: &DedupeConfig,
+    offset: FixedOffset,
+) -> Vec<DiscardedDuplicate> {
+    if cfg.by == DedupeBy::None {
+        return vec![];
+    }
+
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (idx, doc) in docs.iter().enumerate() {
+        if let Some(key) = dedupe_key(doc, cfg.by) {
+            groups.entry(key).or_default().push(idx);
+        }
+    }
+
+    let mut discarded = Vec::new();
+    let mut keep = vec![true; docs.len()];
+    let mut histories: Vec<(usize, Vec<SaveSnapshot>)> = Vec::new();
+    for (key, mut members) in groups {
+        if members.len() < 2 {
+            continue;
+        }
+        // Oldest first; on ties the earliest input sorts last and is kept.
+        members.sort_by_key(|&idx| (recency(&docs[idx], offset), Reverse(idx)));
+        let kept = *members.last().expect("group is non-empty");
+
+        for &idx in &members[..members.len() - 1] {
+            let doc = &docs[idx];
+            debug!(key = %key, path = %doc.source.path, kept = %docs[kept].source.path, "discarding duplicate");
+            keep[idx] = false;
+            discarded.push(DiscardedDuplicate {
+                key: key.clone(),
+                path: doc.source.path.clone(),
+                kept_path: docs[kept].source.path.clone(),
+            });
+        }
+
+        if cfg.merge {
+            let snapshots: Vec<&ParsedDocument> = members.iter().map(|&idx| &docs[idx]).collect();
+            histories.push((kept, build_history(&snapshots, offset)));
+        }
+    }
+
+    for (idx, history) in histories {
+        docs[idx].history = history;
+    }
+
+    let mut flags = keep.into_iter();
+    docs.retain(|_| flags.next().unwrap_or(true));
+    discarded
+}
+
+pub fn infohash_index(docs: &mut [ParsedDocument]) -> BTreeMap<String, Vec<String>> {
+    let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for doc in docs.iter() {
+        for hash in &doc.infohashes {
+            let paths = index.entry(hash.clone()).or_default();
+            if !paths.contains(&doc.source.path) {
+                paths.push(doc.source.path.clone());
+            }
+        }
+    }
+
+    for doc in docs.iter_mut() {
+        let mut shared: Vec<String> = doc
+            .infohashes
+            .iter()
+            .filter_map(|hash| index.get(hash))
+            .flatten()
+            .filter(|path| **path != doc.source.path)
+            .cloned()
+            .collect();
+        shared.sort();
+        shared.dedup();
+        doc.shares_payload_with = shared;
+    }
+
+    index
+}
+
+// Canonical URL first, then the WordPress shortlink form of site + post_id, so
+// re-parses of the same post keep their ID; the content hash is a last resort.
+pub fn doc_id(doc: &ParsedDocument) -> String {
+    let canonical = doc
+        .page
+        .as_ref()
+        .and_then(|p| p.canonical_url.as_deref())
+        .map(str::trim)
+        .filter(|u| !u.is_empty());
+    let post_key = doc.post.as_ref().and_then(|post| {
+        let id = post.post_id?;
+        let host = post
+            .shortlink
+            .as_deref()
+            .or(doc.page.as_ref().and_then(|p| p.shortlink.as_deref()))
+            .and_then(links::host_of)
+            .or_else(|| doc.link_profile.as_ref().and_then(|l| l.site_host.clone()))?;
+        Some(format!("https://{host}/?p={id}"))
+    });
+
+    let id = match canonical.map(str::to_string).or(post_key) {
+        Some(url) => Uuid::new_v5(&Uuid::NAMESPACE_URL, url.as_bytes()),
+        None => Uuid::new_v5(&Uuid::NAMESPACE_OID, doc.source.sha256.as_bytes()),
+    };
+    id.to_string()
+}
+
+pub fn dedupe_key(doc: &ParsedDocument, by: DedupeBy) -> Option<String> {
+    match by {
+        DedupeBy::None => None,
+        DedupeBy::PostId => doc
+            .post
+            .as_ref()
+            .and_then(|p| p.post_id)
+            .map(|id| format!("post_id:{id}")),
+        DedupeBy::DocId => Some(format!(
+            "doc_id:{}",
+            doc.doc_id.clone().unwrap_or_else(|| doc_id(doc))
+        )),
+        DedupeBy::Sha256 => Some(&doc.source.sha256)
+            .filter(|hash| !hash.is_empty())
+            .map(|hash| format!("sha256:{hash}")),
+        DedupeBy::TitleSlug => doc
+            .release
+            .as_ref()
+            .and_then(|r| r.title_slug.as_deref())
+            .filter(|slug| !slug.is_empty())
+            .map(|slug| format!("title_slug:{slug}")),
+    }
+}
+
+// Offset-less dates are read in `offset` ([profile] default_utc_offset).
+pub fn recency(doc: &ParsedDocument, offset: FixedOffset) -> (i64, i64) {
+    (
+        content_timestamp(doc, offset).unwrap_or(i64::MIN),
+        doc.source.mtime.unwrap_or(i64::MIN),
+    )
+}
+
+fn content_timestamp(doc: &ParsedDocument, offset: FixedOffset) -> Option<i64> {
+    doc.post.as_ref().and_then(|p| {
+        p.modified_epoch.or_else(|| {
+            p.modified
+                .as_deref()
+                .or(p.entry_datetime.as_deref())
+                .and_then(|raw| parse_datetime(raw, offset))
+                .map(|ts| ts.timestamp())
+        })
+    })
+}
+
+fn build_history(snapshots: &[&ParsedDocument], offset: FixedOffset) -> Vec<SaveSnapshot> {
+    let values: Vec<Value> = snapshots
+        .iter()
+        .map(|doc| serde_json::to_value(doc).unwrap_or(Value::Null))
+        .collect();
+
+    snapshots
+        .iter()
+        .enumerate()
+        .map(|(i, doc)| SaveSnapshot {
+            path: doc.source.path.clone(),
+            saved_at: saved_at(doc, offset),
+            changed_fields: match i {
+                0 => vec![],
+                _ => changed_fields(&values[i - 1], &values[i]),
+            },
+        })
+        .collect()
+}
+
+fn saved_at(doc: &ParsedDocument, offset: FixedOffset) -> Option<String> {
+    content_timestamp(doc, offset)
+        .or(doc.source.mtime)
+        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        .map(|ts| ts.to_rfc3339())
+}
+
+// Top-level fields are compared, and objects one level down are reported as
+// `section.field` so a price bump shows as `pricing.current` rather than `pricing`.
+fn changed_fields(prev: &Value, next: &Value) -> Vec<String> {
+    let empty = serde_json::Map::new();
+    let prev = prev.as_object().unwrap_or(&empty);
+    let next = next.as_object().unwrap_or(&empty);
+
+    let mut keys: Vec<&String> = prev.keys().chain(next.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut changed = Vec::new();
+    for key in keys {
+        if HISTORY_IGNORED_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        match (prev.get(key), next.get(key)) {
+            (Some(Value::Object(a)), Some(Value::Object(b))) => {
+                let mut inner: Vec<&String> = a.keys().chain(b.keys()).collect();
+                inner.sort();
+                inner.dedup();
+                for sub in inner {
+                    if a.get(sub) != b.get(sub) {
+                        changed.push(format!("{key}.{sub}"));
+                    }
+                }
+            }
+            (a, b) if a != b => changed.push(key.clone()),
+            _ => {}
+        }
+    }
+    changed
+}
@@ -0,0 +1,195 @@
+use super::{Lookup, similarity, slug};
+use crate::config::EnrichConfig;
+use crate::model::Playtime;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use ureq::Agent;
+
+// The search endpoint is unofficial and rejects clients without a browser
+// user agent and referer.
+const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+const REFERER: &str = "https://howlongtobeat.com/";
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const SEARCH_SIZE: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    data: Vec<Game>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Game {
+    game_id: u64,
+    game_name: String,
+    // Seconds; 0 when nobody has submitted a time.
+    #[serde(default)]
+    comp_main: u64,
+    #[serde(default)]
+    comp_plus: u64,
+    #[serde(default)]
+    comp_100: u64,
+}
+
+// The best search result per slug, kept even when it scores below
+// `min_score` so a later run with a lower threshold needs no request.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: i64,
+    best: Option<Playtime>,
+}
+
+pub struct Hltb {
+    agent: Agent,
+    url: String,
+    min_score: f64,
+    cache_path: Option<PathBuf>,
+    cache: BTreeMap<String, CacheEntry>,
+    max_age: i64,
+    changed: bool,
+    last_request: Option<Instant>,
+}
+
+impl Hltb {
+    pub fn new(agent: Agent, cfg: &EnrichConfig) -> Result<Self> {
+        if cfg.hltb_url.is_empty() {
+            bail!("HowLongToBeat lookups need `[enrich] hltb_url`");
+        }
+        let cache_path = (!cfg.hltb_cache.is_empty()).then(|| PathBuf::from(&cfg.hltb_cache));
+        let cache = match &cache_path {
+            Some(path) if path.exists() => {
+                let text = std::fs::read_to_string(path)
+                    .with_context(|| format!("read {}", path.display()))?;
+                match serde_json::from_str(&text) {
+                    Ok(cache) => cache,
+                    Err(err) => {
+                        warn!(path = %path.display(), error = %err, "ignoring unreadable HowLongToBeat cache");
+                        BTreeMap::new()
+                    }
+                }
+            }
+            _ => BTreeMap::new(),
+        };
+        info!(entries = cache.len(), "loaded HowLongToBeat cache");
+        Ok(Self {
+            agent,
+            url: cfg.hltb_url.clone(),
+            min_score: cfg.min_score,
+            cache_path,
+            cache,
+            max_age: i64::from(cfg.hltb_cache_days) * 86_400,
+            changed: false,
+            last_request: None,
+        })
+    }
+
+    fn search(&mut self, name: &str) -> Result<Vec<Game>> {
+        if let Some(elapsed) = self.last_request.map(|at| at.elapsed())
+            && elapsed < REQUEST_INTERVAL
+        {
+            std::thread::sleep(REQUEST_INTERVAL - elapsed);
+        }
+        self.last_request = Some(Instant::now());
+        let body = json!({
+            "searchType": "games",
+            "searchTerms": name.split_whitespace().collect::<Vec<_>>(),
+            "searchPage": 1,
+            "size": SEARCH_SIZE,
+            "searchOptions": {
+                "games": {
+                    "userId": 0,
+                    "platform": "",
+                    "sortCategory": "popular",
+                    "rangeCategory": "main",
+                    "rangeTime": { "min": null, "max": null },
+                    "gameplay": { "perspective": "", "flow": "", "genre": "" },
+                    "rangeYear": { "min": "", "max": "" },
+                    "modifier": "",
+                },
+                "users": { "sortCategory": "postcount" },
+                "lists": { "sortCategory": "follows" },
+                "filter": "",
+                "sort": 0,
+                "randomizer": 0,
+            },
+        });
+        let response: SearchResponse = self
+            .agent
+            .post(&self.url)
+            .header("User-Agent", USER_AGENT)
+            .header("Referer", REFERER)
+            .header("Origin", REFERER.trim_end_matches('/'))
+            .send_json(body)
+            .with_context(|| format!("search HowLongToBeat at {}", self.url))?
+            .body_mut()
+            .read_json()
+            .context("parse HowLongToBeat response")?;
+        Ok(response.data)
+    }
+}
+
+impl Lookup for Hltb {
+    type Found = Playtime;
+
+    fn lookup(&mut self, name: &str, slug: &str) -> Result<Option<Playtime>> {
+        let now = chrono::Utc::now().timestamp();
+        let best = match self.cache.get(slug) {
+            Some(entry) if now - entry.fetched_at < self.max_age => {
+                debug!(title = %name, "HowLongToBeat cache hit");
+                entry.best.clone()
+            }
+            _ => {
+                let best = self
+                    .search(name)?
+                    .into_iter()
+                    .map(|game| {
+                        let score = similarity(slug, &self::slug(&game.game_name));
+                        to_playtime(game, score)
+                    })
+                    .max_by(|a, b| a.score.total_cmp(&b.score));
+                self.cache.insert(
+                    slug.to_string(),
+                    CacheEntry {
+                        fetched_at: now,
+                        best: best.clone(),
+                    },
+                );
+                self.changed = true;
+                best
+            }
+        };
+        Ok(best.filter(|found| found.score >= self.min_score))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let Some(path) = self.cache_path.as_ref().filter(|_| self.changed) else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.cache)?;
+        std::fs::write(path, json + "\n").with_context(|| format!("write {}", path.display()))?;
+        info!(path = %path.display(), entries = self.cache.len(), "wrote HowLongToBeat cache");
+        Ok(())
+    }
+}
+
+fn to_playtime(game: Game, score: f64) -> Playtime {
+    Playtime {
+        id: game.game_id,
+        url: Some(format!("https://howlongtobeat.com/game/{}", game.game_id)),
+        canonical_name: game.game_name,
+        score,
+        main_hours: hours(game.comp_main),
+        extra_hours: hours(game.comp_plus),
+        completionist_hours: hours(game.comp_100),
+    }
+}
+
+// Rounded to a tenth of an hour.
+fn hours(seconds: u64) -> Option<f64> {
+    (seconds > 0).then(|| (seconds as f64 / 360.0).round() / 10.0)
+}
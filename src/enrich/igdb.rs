@@ -0,0 +1,137 @@
+use super::{Lookup, Matcher, similarity, slug};
+use crate::config::EnrichConfig;
+use crate::model::MetadataMatch;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::info;
+use ureq::Agent;
+
+const GAMES_URL: &str = "https://api.igdb.com/v4/games";
+// IGDB allows four requests per second per client.
+const REQUEST_INTERVAL: Duration = Duration::from_millis(250);
+const SEARCH_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Deserialize)]
+struct Game {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    first_release_date: Option<i64>,
+    #[serde(default)]
+    cover: Option<Cover>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Cover {
+    image_id: String,
+}
+
+pub struct Igdb {
+    backend: Backend,
+    min_score: f64,
+}
+
+enum Backend {
+    // A JSON array of game objects as the API returns them.
+    Dump {
+        games: Vec<Game>,
+        matcher: Matcher,
+    },
+    Api {
+        agent: Agent,
+        client_id: String,
+        token: String,
+    },
+}
+
+impl Igdb {
+    pub fn new(agent: Agent, cfg: &EnrichConfig) -> Result<Self> {
+        if !cfg.igdb_dump.is_empty() {
+            let text = std::fs::read_to_string(&cfg.igdb_dump)
+                .with_context(|| format!("read {}", cfg.igdb_dump))?;
+            let games: Vec<Game> = serde_json::from_str(&text)
+                .with_context(|| format!("parse IGDB dump {}", cfg.igdb_dump))?;
+            info!(games = games.len(), "loaded IGDB dump");
+            let matcher = Matcher::new(games.iter().map(|game| game.name.as_str()));
+            return Ok(Self {
+                backend: Backend::Dump { games, matcher },
+                min_score: cfg.min_score,
+            });
+        }
+        if cfg.igdb_client_id.is_empty() || cfg.igdb_access_token.is_empty() {
+            bail!(
+                "IGDB matching needs `[enrich] igdb_client_id` and `igdb_access_token`, or an `igdb_dump`"
+            );
+        }
+        Ok(Self {
+            backend: Backend::Api {
+                agent,
+                client_id: cfg.igdb_client_id.clone(),
+                token: cfg.igdb_access_token.clone(),
+            },
+            min_score: cfg.min_score,
+        })
+    }
+}
+
+impl Lookup for Igdb {
+    type Found = MetadataMatch;
+
+    fn lookup(&mut self, name: &str, slug: &str) -> Result<Option<MetadataMatch>> {
+        let min_score = self.min_score;
+        match &self.backend {
+            Backend::Dump { games, matcher } => Ok(matcher
+                .best(slug, min_score)
+                .map(|(i, score)| to_match(&games[i], score))),
+            Backend::Api {
+                agent,
+                client_id,
+                token,
+            } => {
+                let query = format!(
+                    "search \"{}\"; fields name,first_release_date,cover.image_id,url; limit {SEARCH_LIMIT};",
+                    name.replace('\\', "\\\\").replace('"', "\\\"")
+                );
+                let games: Vec<Game> = agent
+                    .post(GAMES_URL)
+                    .header("Client-ID", client_id.as_str())
+                    .header("Authorization", format!("Bearer {token}"))
+                    .send(query)
+                    .context("query IGDB")?
+                    .body_mut()
+                    .read_json()
+                    .context("parse IGDB response")?;
+                std::thread::sleep(REQUEST_INTERVAL);
+                Ok(games
+                    .iter()
+                    .map(|game| (game, similarity(slug, &self::slug(&game.name))))
+                    .filter(|(_, score)| *score >= min_score)
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(game, score)| to_match(game, score)))
+            }
+        }
+    }
+}
+
+fn to_match(game: &Game, score: f64) -> MetadataMatch {
+    MetadataMatch {
+        id: game.id,
+        canonical_name: game.name.clone(),
+        score,
+        release_year: game
+            .first_release_date
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .map(|date| date.year()),
+        cover_url: game.cover.as_ref().map(|cover| {
+            format!(
+                "https://images.igdb.com/igdb/image/upload/t_cover_big/{}.jpg",
+                cover.image_id
+            )
+        }),
+        url: game.url.clone(),
+    }
+}
@@ -0,0 +1,161 @@
+pub mod hltb;
+pub mod igdb;
+pub mod steam;
+
+use crate::config::{EnrichConfig, EnrichSource};
+use crate::model::{Enrichment, ParsedDocument};
+use crate::parser::normalize;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, warn};
+use ureq::Agent;
+
+#[derive(Debug, Default)]
+pub struct EnrichStats {
+    pub matched: usize,
+    pub unmatched: usize,
+    pub failed: usize,
+}
+
+// A metadata source: the best match for a game, or None when nothing is
+// close enough.
+trait Lookup {
+    type Found: Clone;
+
+    fn lookup(&mut self, name: &str, slug: &str) -> Result<Option<Self::Found>>;
+
+    // Called once every document has been looked up.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+pub fn enrich(docs: &mut [ParsedDocument], cfg: &EnrichConfig) -> Result<EnrichStats> {
+    let agent: Agent = Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(30)))
+        .build()
+        .into();
+    match cfg.source {
+        EnrichSource::Steam => {
+            let mut source = steam::Steam::new(agent, cfg)?;
+            attach(docs, &mut source, |e| &mut e.steam)
+        }
+        EnrichSource::Igdb => {
+            let mut source = igdb::Igdb::new(agent, cfg)?;
+            attach(docs, &mut source, |e| &mut e.igdb)
+        }
+        EnrichSource::Hltb => {
+            let mut source = hltb::Hltb::new(agent, cfg)?;
+            attach(docs, &mut source, |e| &mut e.hltb)
+        }
+    }
+}
+
+// Looks every document up once per distinct title and stores the result in
+// the source's slot, replacing what an earlier run attached.
+fn attach<L: Lookup>(
+    docs: &mut [ParsedDocument],
+    source: &mut L,
+    slot: fn(&mut Enrichment) -> &mut Option<L::Found>,
+) -> Result<EnrichStats> {
+    let mut stats = EnrichStats::default();
+    let mut cache: HashMap<String, Option<L::Found>> = HashMap::new();
+    for doc in docs {
+        let Some((name, slug)) = game_title(doc) else {
+            stats.unmatched += 1;
+            continue;
+        };
+        let found = match cache.get(&slug) {
+            Some(found) => found.clone(),
+            None => match source.lookup(&name, &slug) {
+                Ok(found) => {
+                    cache.insert(slug.clone(), found.clone());
+                    found
+                }
+                Err(err) => {
+                    warn!(title = %name, error = %format!("{err:#}"), "metadata lookup failed");
+                    stats.failed += 1;
+                    continue;
+                }
+            },
+        };
+        match found {
+            Some(found) => {
+                debug!(title = %name, "matched");
+                *slot(doc.enrichment.get_or_insert_default()) = Some(found);
+                stats.matched += 1;
+            }
+            None => stats.unmatched += 1,
+        }
+    }
+    source.finish()?;
+    Ok(stats)
+}
+
+// The game name without release number or version tail, and its slug.
+fn game_title(doc: &ParsedDocument) -> Option<(String, String)> {
+    let line = doc
+        .release
+        .as_ref()
+        .and_then(|r| r.game_title_line.clone())
+        .or_else(|| doc.post.as_ref().and_then(|p| p.entry_title.clone()))
+        .or_else(|| doc.page.as_ref().and_then(|p| p.title.clone()))?;
+    let name = normalize::game_name(&line);
+    let slug = slug(&name);
+    (!slug.is_empty()).then_some((name, slug))
+}
+
+// Store names carry trademark signs that NFKD would fold into letters
+// ("Game™" -> "gametm").
+fn slug(name: &str) -> String {
+    normalize::title_slug(&name.replace(['™', '®', '©'], " "))
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    strsim::normalized_levenshtein(a, b)
+}
+
+// Slug index over a local title list: exact slugs first, then fuzzy matches
+// among titles sharing the first word, which keeps a 200k-entry Steam list
+// fast to search.
+struct Matcher {
+    slugs: Vec<String>,
+    exact: HashMap<String, usize>,
+    by_first_word: HashMap<String, Vec<usize>>,
+}
+
+impl Matcher {
+    fn new<'a>(names: impl Iterator<Item = &'a str>) -> Self {
+        let slugs: Vec<String> = names.map(slug).collect();
+        let mut exact = HashMap::new();
+        let mut by_first_word: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, slug) in slugs.iter().enumerate() {
+            if slug.is_empty() {
+                continue;
+            }
+            exact.entry(slug.clone()).or_insert(i);
+            if let Some(first) = slug.split('-').next() {
+                by_first_word.entry(first.to_string()).or_default().push(i);
+            }
+        }
+        Self {
+            slugs,
+            exact,
+            by_first_word,
+        }
+    }
+
+    fn best(&self, slug: &str, min_score: f64) -> Option<(usize, f64)> {
+        if let Some(&i) = self.exact.get(slug) {
+            return Some((i, 1.0));
+        }
+        let first = slug.split('-').next()?;
+        self.by_first_word
+            .get(first)?
+            .iter()
+            .map(|&i| (i, similarity(slug, &self.slugs[i])))
+            .filter(|(_, score)| *score >= min_score)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
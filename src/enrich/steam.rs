@@ -0,0 +1,180 @@
+use super::{Lookup, Matcher};
+use crate::config::EnrichConfig;
+use crate::model::MetadataMatch;
+use anyhow::{Context, Result, bail};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{info, warn};
+use ureq::Agent;
+
+const APP_LIST_URL: &str = "https://api.steampowered.com/IStoreService/GetAppList/v1/";
+const APP_DETAILS_URL: &str = "https://store.steampowered.com/api/appdetails";
+const PAGE_SIZE: &str = "50000";
+
+static RE_YEAR: Lazy<Regex> = Lazy::new(|| Regex::new(r"\b(?:19|20)\d{2}\b").expect("valid regex"));
+
+#[derive(Debug, Deserialize)]
+struct App {
+    appid: u64,
+    name: String,
+}
+
+// `IStoreService/GetAppList` pages, the older `ISteamApps/GetAppList/v2`
+// dump, or a bare array of apps.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AppList {
+    Store { response: StorePage },
+    Legacy { applist: LegacyList },
+    Plain(Vec<App>),
+}
+
+#[derive(Debug, Deserialize)]
+struct StorePage {
+    #[serde(default)]
+    apps: Vec<App>,
+    #[serde(default)]
+    have_more_results: bool,
+    #[serde(default)]
+    last_appid: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyList {
+    apps: Vec<App>,
+}
+
+pub struct Steam {
+    agent: Agent,
+    apps: Vec<App>,
+    matcher: Matcher,
+    min_score: f64,
+    release_year: bool,
+    years: HashMap<u64, Option<i32>>,
+}
+
+impl Steam {
+    pub fn new(agent: Agent, cfg: &EnrichConfig) -> Result<Self> {
+        let apps = if !cfg.steam_app_list.is_empty() {
+            load_app_list(&cfg.steam_app_list)?
+        } else if !cfg.steam_api_key.is_empty() {
+            fetch_app_list(&agent, &cfg.steam_api_key)?
+        } else {
+            bail!(
+                "Steam matching needs an app list: set `[enrich] steam_app_list` to a local dump or `steam_api_key`"
+            );
+        };
+        info!(apps = apps.len(), "loaded Steam app list");
+        let matcher = Matcher::new(apps.iter().map(|app| app.name.as_str()));
+        Ok(Self {
+            agent,
+            apps,
+            matcher,
+            min_score: cfg.min_score,
+            release_year: cfg.steam_release_year,
+            years: HashMap::new(),
+        })
+    }
+
+    fn release_year(&mut self, appid: u64) -> Option<i32> {
+        if !self.release_year {
+            return None;
+        }
+        if let Some(&year) = self.years.get(&appid) {
+            return year;
+        }
+        match fetch_release_year(&self.agent, appid) {
+            Ok(year) => {
+                self.years.insert(appid, year);
+                year
+            }
+            Err(err) => {
+                // Usually the store being unreachable; asking again for every
+                // title would only repeat the warning.
+                warn!(error = %format!("{err:#}"), "Steam store lookup failed; skipping release years");
+                self.release_year = false;
+                None
+            }
+        }
+    }
+}
+
+impl Lookup for Steam {
+    type Found = MetadataMatch;
+
+    fn lookup(&mut self, _name: &str, slug: &str) -> Result<Option<MetadataMatch>> {
+        let Some((i, score)) = self.matcher.best(slug, self.min_score) else {
+            return Ok(None);
+        };
+        let appid = self.apps[i].appid;
+        let release_year = self.release_year(appid);
+        Ok(Some(MetadataMatch {
+            id: appid,
+            canonical_name: self.apps[i].name.clone(),
+            score,
+            release_year,
+            cover_url: Some(format!(
+                "https://cdn.cloudflare.steamstatic.com/steam/apps/{appid}/header.jpg"
+            )),
+            url: Some(format!("https://store.steampowered.com/app/{appid}/")),
+        }))
+    }
+}
+
+fn load_app_list(path: &str) -> Result<Vec<App>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("read {path}"))?;
+    let list: AppList =
+        serde_json::from_str(&text).with_context(|| format!("parse Steam app list {path}"))?;
+    Ok(match list {
+        AppList::Store { response } => response.apps,
+        AppList::Legacy { applist } => applist.apps,
+        AppList::Plain(apps) => apps,
+    })
+}
+
+fn fetch_app_list(agent: &Agent, key: &str) -> Result<Vec<App>> {
+    let mut apps = Vec::new();
+    let mut last_appid = 0;
+    loop {
+        let page: AppList = agent
+            .get(APP_LIST_URL)
+            .query("key", key)
+            .query("max_results", PAGE_SIZE)
+            .query("last_appid", last_appid.to_string())
+            .call()
+            .context("fetch Steam app list")?
+            .body_mut()
+            .read_json()
+            .context("parse Steam app list")?;
+        let AppList::Store { response } = page else {
+            bail!("unexpected Steam app list response");
+        };
+        apps.extend(response.apps);
+        if !response.have_more_results || response.last_appid <= last_appid {
+            return Ok(apps);
+        }
+        last_appid = response.last_appid;
+    }
+}
+
+fn fetch_release_year(agent: &Agent, appid: u64) -> Result<Option<i32>> {
+    let details: Value = agent
+        .get(APP_DETAILS_URL)
+        .query("appids", appid.to_string())
+        .query("filters", "release_date")
+        .call()
+        .with_context(|| format!("fetch Steam app {appid}"))?
+        .body_mut()
+        .read_json()
+        .with_context(|| format!("parse Steam app {appid}"))?;
+    // Dates are localized ("14 Oct, 2020", "Oct 14, 2020"); the year is the
+    // only part worth keeping.
+    Ok(details
+        .pointer(&format!("/{appid}/data/release_date/date"))
+        .and_then(Value::as_str)
+        .and_then(|date| RE_YEAR.find_iter(date).last())
+        .and_then(|year| year.as_str().parse().ok()))
+}
@@ -0,0 +1,85 @@
+use crate::check::short;
+use crate::config::Config;
+use crate::parser::{self, release_page};
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize)]
+pub struct Explanation {
+    pub path: String,
+    pub site: String,
+    pub fields: Vec<FieldReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldReport {
+    pub field: String,
+    pub matched: bool,
+    // The source that produced the value, or every source tried when none did.
+    pub source: String,
+    pub value: Option<Value>,
+}
+
+pub fn run(path: &PathBuf, cfg: &Config) -> Result<Explanation> {
+    let mut cfg = cfg.clone();
+    cfg.output.explain = true;
+    let doc = parser::parse_one(path, &cfg)?;
+    let value = serde_json::to_value(&doc)?;
+
+    let mut sources = release_page::field_sources(doc.site == "wordpress_release");
+    // Anything recorded that the tables do not cover still gets a row.
+    for field in doc.provenance.keys() {
+        if !sources.iter().any(|(f, _)| f == field) {
+            sources.push((field.as_str(), ""));
+        }
+    }
+    let fields = sources
+        .into_iter()
+        .map(|(field, tried)| {
+            let value = release_page::field_value(&value, field)
+                .filter(|v| release_page::is_present(v))
+                .cloned();
+            FieldReport {
+                field: field.to_string(),
+                matched: value.is_some(),
+                source: doc
+                    .provenance
+                    .get(field)
+                    .cloned()
+                    .unwrap_or_else(|| tried.to_string()),
+                value,
+            }
+        })
+        .collect();
+    Ok(Explanation {
+        path: doc.source.path.clone(),
+        site: doc.site.clone(),
+        fields,
+    })
+}
+
+pub fn write_report_table<W: Write>(out: &mut W, explanation: &Explanation) -> Result<()> {
+    writeln!(out, "{} ({} layout)\n", explanation.path, explanation.site)?;
+    for field in &explanation.fields {
+        writeln!(
+            out,
+            "{} {}\n    source: {}",
+            if field.matched { "ok  " } else { "miss" },
+            field.field,
+            field.source
+        )?;
+        if let Some(value) = &field.value {
+            writeln!(out, "    value:  {}", short(value))?;
+        }
+    }
+    let matched = explanation.fields.iter().filter(|f| f.matched).count();
+    writeln!(
+        out,
+        "\n{matched} of {} fields matched",
+        explanation.fields.len()
+    )?;
+    Ok(())
+}
@@ -0,0 +1,327 @@
+use anyhow::{Result, bail};
+use serde_json::Value;
+
+// Bare field names that are not top-level document fields are looked up in
+// these sections, so `genres_tags` works as well as `release.genres_tags`.
+const IMPLICIT_SECTIONS: &[&str] = &["release", "post", "page", "pricing", "source"];
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Truthy(Vec<String>),
+    Compare(Vec<String>, Op, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+pub fn parse(input: &str) -> Result<Expr> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.or()?;
+    if let Some(tok) = parser.tokens.get(parser.pos) {
+        bail!(
+            "unexpected {} at token {}",
+            describe(Some(tok)),
+            parser.pos + 1
+        );
+    }
+    Ok(expr)
+}
+
+pub fn matches(expr: &Expr, doc: &Value) -> bool {
+    match expr {
+        Expr::Or(a, b) => matches(a, doc) || matches(b, doc),
+        Expr::And(a, b) => matches(a, doc) && matches(b, doc),
+        Expr::Not(e) => !matches(e, doc),
+        Expr::Truthy(path) => lookup(doc, path).is_some_and(truthy),
+        Expr::Compare(path, op, rhs) => {
+            let lhs = lookup(doc, path).unwrap_or(&Value::Null);
+            compare(lhs, *op, rhs)
+        }
+    }
+}
+
+fn lookup<'a>(doc: &'a Value, path: &[String]) -> Option<&'a Value> {
+    let direct = path.iter().try_fold(doc, |v, key| v.get(key));
+    if direct.is_some() || path.len() != 1 {
+        return direct;
+    }
+    IMPLICIT_SECTIONS
+        .iter()
+        .find_map(|section| doc.get(section).and_then(|s| s.get(&path[0])))
+}
+
+fn truthy(v: &Value) -> bool {
+    match v {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn compare(lhs: &Value, op: Op, rhs: &Value) -> bool {
+    match op {
+        Op::Eq => loosely_equal(lhs, rhs),
+        Op::Ne => !loosely_equal(lhs, rhs),
+        Op::Contains => match (lhs, rhs) {
+            (Value::Array(items), _) => items.iter().any(|item| loosely_equal(item, rhs)),
+            (Value::String(s), Value::String(needle)) => {
+                s.to_lowercase().contains(&needle.to_lowercase())
+            }
+            (Value::Object(o), Value::String(key)) => o.contains_key(key),
+            _ => false,
+        },
+        Op::Gt | Op::Ge | Op::Lt | Op::Le => {
+            let ordering = match (lhs, rhs) {
+                (Value::Number(a), Value::Number(b)) => a
+                    .as_f64()
+                    .zip(b.as_f64())
+                    .and_then(|(a, b)| a.partial_cmp(&b)),
+                (Value::String(a), Value::String(b)) => Some(a.as_str().cmp(b.as_str())),
+                _ => None,
+            };
+            ordering.is_some_and(|o| match op {
+                Op::Gt => o.is_gt(),
+                Op::Ge => o.is_ge(),
+                Op::Lt => o.is_lt(),
+                _ => o.is_le(),
+            })
+        }
+    }
+}
+
+// Strings compare case-insensitively and numbers by value, so
+// `genres_tags contains "rpg"` and `release_number == 4000` behave as expected.
+fn loosely_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.eq_ignore_ascii_case(b),
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => a == b,
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut lhs = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut lhs = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            lhs = Expr::And(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::LParen) => {
+                let inner = self.or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(inner),
+                    other => bail!("expected `)`, found {}", describe(other.as_ref())),
+                }
+            }
+            Some(Token::Ident(name)) => {
+                let path: Vec<String> = name.split('.').map(str::to_string).collect();
+                if path.iter().any(String::is_empty) {
+                    bail!("invalid field path `{name}`");
+                }
+                match self.peek() {
+                    Some(Token::Op(op)) => {
+                        let op = *op;
+                        self.pos += 1;
+                        Ok(Expr::Compare(path, op, self.literal()?))
+                    }
+                    _ => Ok(Expr::Truthy(path)),
+                }
+            }
+            other => bail!(
+                "expected a field, `!` or `(`, found {}",
+                describe(other.as_ref())
+            ),
+        }
+    }
+
+    fn literal(&mut self) -> Result<Value> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(Value::String(s)),
+            Some(Token::Num(n)) => Ok(serde_json::Number::from_f64(n)
+                .map(Value::Number)
+                .unwrap_or(Value::Null)),
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                "null" => Ok(Value::Null),
+                _ => bail!("expected a value, found `{word}` (quote strings with \"...\")"),
+            },
+            other => bail!("expected a value, found {}", describe(other.as_ref())),
+        }
+    }
+}
+
+fn describe(tok: Option<&Token>) -> String {
+    match tok {
+        None => "end of expression".to_string(),
+        Some(Token::Ident(s)) => format!("`{s}`"),
+        Some(Token::Str(s)) => format!("\"{s}\""),
+        Some(Token::Num(n)) => format!("`{n}`"),
+        Some(Token::Op(op)) => format!("operator {op:?}"),
+        Some(Token::And) => "`&&`".to_string(),
+        Some(Token::Or) => "`||`".to_string(),
+        Some(Token::Not) => "`!`".to_string(),
+        Some(Token::LParen) => "`(`".to_string(),
+        Some(Token::RParen) => "`)`".to_string(),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '"' | '\'' => {
+                let mut s = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => bail!("unterminated string literal"),
+                        Some('\\') if chars.get(i + 1).is_some() => {
+                            s.push(chars[i + 1]);
+                            i += 2;
+                        }
+                        Some(&q) if q == c => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&other) => {
+                            s.push(other);
+                            i += 1;
+                        }
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ if two == "&&" => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            _ if two == "||" => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            _ if ["==", "!=", ">=", "<="].contains(&two.as_str()) => {
+                tokens.push(Token::Op(match two.as_str() {
+                    "==" => Op::Eq,
+                    "!=" => Op::Ne,
+                    ">=" => Op::Ge,
+                    _ => Op::Le,
+                }));
+                i += 2;
+            }
+            '>' | '<' => {
+                tokens.push(Token::Op(if c == '>' { Op::Gt } else { Op::Lt }));
+                i += 1;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            _ if c.is_ascii_digit()
+                || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) =>
+            {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let raw: String = chars[start..i].iter().collect();
+                let n = raw
+                    .parse::<f64>()
+                    .map_err(|_| anyhow::anyhow!("invalid number `{raw}`"))?;
+                tokens.push(Token::Num(n));
+            }
+            _ if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.as_str() {
+                    "contains" => Token::Op(Op::Contains),
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Ident(word),
+                });
+            }
+            _ => bail!("unexpected character `{c}` at position {}", i + 1),
+        }
+    }
+
+    Ok(tokens)
+}
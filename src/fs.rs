@@ -43,7 +43,7 @@ pub fn collect_html_inputs(
     Ok(out.into_iter().collect())
 }
 
-fn is_html(path: &Path) -> bool {
+pub fn is_html(path: &Path) -> bool {
     match path.extension().and_then(|s| s.to_str()) {
         Some(ext) => matches!(ext.to_ascii_lowercase().as_str(), "html" | "htm"),
         None => false,
@@ -1,22 +1,73 @@
 #![forbid(unsafe_code)]
 
+mod bench;
+mod bundle;
+mod check;
 mod cli;
 mod config;
+mod config_check;
+mod daemon;
+mod dedupe;
+mod enrich;
+mod explain;
+mod filter;
 mod fs;
+mod merge;
 mod model;
+mod output;
 mod parser;
+mod porcelain;
+mod progress;
+mod project;
+mod query;
+mod redact;
+mod report;
+mod search;
+mod serve;
+mod stats;
+mod store;
+mod tui;
+mod validate;
+mod verify;
+mod wizard;
 
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{Context, Result, bail};
+use clap::{CommandFactory, FromArgMatches};
+use model::SCHEMA_VERSION;
 use std::io::Write;
+use std::ops::ControlFlow;
+use std::sync::{Arc, RwLock};
 use tracing::{info, warn};
 
 fn main() -> Result<()> {
-    let cli = cli::Cli::parse();
+    let started = std::time::Instant::now();
+    let matches = cli::Cli::command().get_matches();
+    let cli = cli::Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
 
     cli::init_tracing(&cli).context("init tracing")?;
 
+    let result = run(&cli);
+    if cli.porcelain {
+        let command = matches.subcommand_name().unwrap_or_default();
+        porcelain::write_status(&mut std::io::stderr().lock(), command, started, &result)?;
+        std::process::exit(i32::from(result.is_err()));
+    }
+    result
+}
+
+fn run(cli: &cli::Cli) -> Result<()> {
     match &cli.command {
+        cli::Command::InitConfig(args) if args.interactive => {
+            // Prompts go to stderr so stdout stays free for piping.
+            let toml = wizard::run(
+                &mut std::io::stdin().lock(),
+                &mut std::io::stderr().lock(),
+                &args.path,
+            )?;
+            std::fs::write(&args.path, toml)
+                .with_context(|| format!("write config to {}", args.path.display()))?;
+            info!(path = %args.path.display(), "wrote config");
+        }
         cli::Command::InitConfig(args) => {
             config::write_default_config(&args.path)
                 .with_context(|| format!("write default config to {}", args.path.display()))?;
@@ -24,7 +75,14 @@ fn main() -> Result<()> {
         }
         cli::Command::PrintConfig(args) => {
             let cfg = config::Config::load(cli.config.as_deref())?;
-            let toml = cfg.to_pretty_toml()?;
+            let toml = if args.diff {
+                cfg.to_diff_toml()?
+            } else {
+                cfg.to_pretty_toml()?
+            };
+            if toml.is_empty() {
+                info!("config does not differ from the defaults");
+            }
             if let Some(path) = &args.output {
                 std::fs::write(path, toml)
                     .with_context(|| format!("write config to {}", path.display()))?;
@@ -33,92 +91,1021 @@ fn main() -> Result<()> {
                 print!("{toml}");
             }
         }
+        cli::Command::Config(args) => match &args.command {
+            cli::ConfigCommand::Validate(args) => {
+                let Some(path) = args.path.as_deref().or(cli.config.as_deref()) else {
+                    bail!("no config file given; pass a path or --config");
+                };
+                let issues = config_check::validate_file(path)?;
+                let mut out = std::io::stdout().lock();
+                for issue in &issues {
+                    writeln!(out, "{}: {}", issue.location, issue.message)?;
+                }
+                if !issues.is_empty() {
+                    bail!("{} has {} config issues", path.display(), issues.len());
+                }
+                info!(path = %path.display(), "config is valid");
+            }
+        },
         cli::Command::Completions(args) => {
             cli::print_completions(args.shell);
         }
-        cli::Command::Parse(args) => {
+        cli::Command::Man(args) => {
+            cli::write_man_pages(args.out_dir.as_deref())?;
+            if let Some(dir) = &args.out_dir {
+                info!(path = %dir.display(), "wrote man pages");
+            }
+        }
+        cli::Command::Site(args) => {
+            let bundle = bundle::load(&args.input)?;
+            output::site::write_site(&args.out_dir, &bundle)
+                .with_context(|| format!("write site to {}", args.out_dir.display()))?;
+            info!(
+                path = %args.out_dir.display(),
+                releases = bundle.documents.len(),
+                "wrote static site"
+            );
+        }
+        cli::Command::Feed(args) => {
+            let cfg = config::Config::load(cli.config.as_deref())?;
+            let bundle = bundle::load(&args.input)?;
+            let mut out =
+                output::open_output(args.output.as_deref(), config::Compression::Auto, true)?;
+            output::feed::write_feed(
+                &mut out,
+                &bundle,
+                &output::feed::FeedOptions {
+                    kind: args.format.into(),
+                    limit: args.limit,
+                    title: &args.title,
+                    link: args.link.as_deref(),
+                    utc_offset: cfg.profile.utc_offset(),
+                },
+            )?;
+            out.finish()?;
+            if let Some(path) = &args.output {
+                info!(path = %path.display(), format = ?args.format, "wrote feed");
+            }
+        }
+        cli::Command::Nfo(args) => {
+            let cfg = config::Config::load(cli.config.as_deref())?;
+            let bundle = bundle::load(&args.input)?;
+            let written = output::nfo::write_nfo_files(
+                &args.out_dir,
+                &bundle,
+                args.root.element(),
+                cfg.profile.utc_offset(),
+            )
+            .with_context(|| format!("write NFO files to {}", args.out_dir.display()))?;
+            info!(path = %args.out_dir.display(), files = written, "wrote NFO files");
+        }
+        cli::Command::Export(args) => run_export(cli, args)?,
+        cli::Command::Stats(args) => {
+            let bundle = match &args.input {
+                Some(path) => bundle::load(path)?,
+                None => open_store(cli, args.index.as_deref())?.bundle()?,
+            };
+            let report = stats::report(&bundle, args.top);
+            let mut out =
+                output::open_output(args.output.as_deref(), config::Compression::Auto, true)?;
+            match args.format {
+                cli::ReportFormat::Table => stats::write_report_table(&mut out, &report)?,
+                cli::ReportFormat::Json => {
+                    serde_json::to_writer_pretty(&mut out, &report)?;
+                    writeln!(out)?;
+                }
+            }
+            out.finish()?;
+            if let Some(path) = &args.output {
+                info!(path = %path.display(), "wrote bundle stats");
+            }
+        }
+        cli::Command::Report(args) => {
+            let bundle = match &args.input {
+                Some(path) => bundle::load(path)?,
+                None => open_store(cli, args.index.as_deref())?.bundle()?,
+            };
+            let mut out =
+                output::open_output(args.output.as_deref(), config::Compression::Auto, true)?;
+            if args.domains {
+                let cfg = config::Config::load(cli.config.as_deref())?;
+                let report = report::domains(&bundle, &cfg.links.host_categories, args.top);
+                match args.format {
+                    cli::ReportFormat::Table => report::write_domains_table(&mut out, &report)?,
+                    cli::ReportFormat::Json => {
+                        serde_json::to_writer_pretty(&mut out, &report)?;
+                        writeln!(out)?;
+                    }
+                }
+            } else {
+                let report = report::report(&bundle, args.top)?;
+                match args.format {
+                    cli::ReportFormat::Table => report::write_report_table(&mut out, &report)?,
+                    cli::ReportFormat::Json => {
+                        serde_json::to_writer_pretty(&mut out, &report)?;
+                        writeln!(out)?;
+                    }
+                }
+            }
+            out.finish()?;
+            if let Some(path) = &args.output {
+                info!(path = %path.display(), "wrote run report");
+            }
+        }
+        cli::Command::Timeline(args) => {
+            let bundle = match &args.input {
+                Some(path) => bundle::load(path)?,
+                None => open_store(cli, args.index.as_deref())?.bundle()?,
+            };
+            let timeline = stats::timeline(&bundle);
+            let mut out =
+                output::open_output(args.output.as_deref(), config::Compression::Auto, true)?;
+            match args.format {
+                cli::TimelineFormat::Chart => {
+                    stats::write_timeline_chart(&mut out, &timeline, args.width)?
+                }
+                cli::TimelineFormat::Json => {
+                    serde_json::to_writer_pretty(&mut out, &timeline)?;
+                    writeln!(out)?;
+                }
+            }
+            out.finish()?;
+            if let Some(path) = &args.output {
+                info!(path = %path.display(), "wrote timeline");
+            }
+        }
+        cli::Command::Merge(args) => run_merge(cli, args)?,
+        cli::Command::Dedupe(args) => run_dedupe(cli, args)?,
+        cli::Command::Enrich(args) => run_enrich(cli, args)?,
+        cli::Command::Daemon(args) => run_daemon(cli, args)?,
+        cli::Command::Index(args) => run_index(cli, args)?,
+        cli::Command::Tui(args) => {
+            let bundle = match &args.input {
+                Some(path) => bundle::load(path)?,
+                None => open_store(cli, args.index.as_deref())?.bundle()?,
+            };
+            tui::run(bundle)?;
+        }
+        cli::Command::Serve(args) => {
+            let catalogue = match &args.input {
+                Some(path) => serve::Catalogue::from_bundle(bundle::load(path)?)?,
+                None => serve::Catalogue::from_store(&open_store(cli, args.index.as_deref())?)?,
+            };
+            info!(documents = catalogue.document_count(), "loaded catalogue");
+            serve::serve(&args.listen, Arc::new(RwLock::new(catalogue)))?;
+        }
+        cli::Command::Convert(args) => run_convert(cli, args)?,
+        cli::Command::Check(args) => {
+            let cfg = config::Config::load(cli.config.as_deref())?;
+            let outcomes = check::run(
+                &args.fixtures,
+                &cfg,
+                &check::CheckOptions {
+                    ignore: &args.ignore,
+                    update: args.update,
+                },
+            )?;
+            check::write_report(&mut std::io::stdout().lock(), &outcomes)?;
+            let failed = outcomes
+                .iter()
+                .filter(|(_, o)| check::is_failure(o))
+                .count();
+            if failed > 0 {
+                bail!("{failed} of {} fixtures failed", outcomes.len());
+            }
+        }
+        cli::Command::Explain(args) => {
+            let cfg = config::Config::load(cli.config.as_deref())?;
+            let explanation = explain::run(&args.input, &cfg)
+                .with_context(|| format!("explain {}", args.input.display()))?;
+            let mut out = std::io::stdout().lock();
+            match args.format {
+                cli::ReportFormat::Table => explain::write_report_table(&mut out, &explanation)?,
+                cli::ReportFormat::Json => {
+                    serde_json::to_writer_pretty(&mut out, &explanation)?;
+                    writeln!(out)?;
+                }
+            }
+        }
+        cli::Command::Extract(args) => {
             let cfg = config::Config::load(cli.config.as_deref())?;
             let files = fs::collect_html_inputs(&args.inputs, args.recursive, args.follow_symlinks)
                 .context("collect inputs")?;
-
+            run_extract(&files, &cfg, args.magnets, args.torrents)?;
+        }
+        cli::Command::Bench(args) => {
+            let cfg = config::Config::load(cli.config.as_deref())?;
+            let files = fs::collect_html_inputs(&args.inputs, args.recursive, false)
+                .context("collect inputs")?;
             if files.is_empty() {
-                warn!("no input HTML files found");
-            } else {
-                info!(count = files.len(), "collected input HTML files");
+                bail!("no input HTML files found");
             }
-
-            let bundle = parser::parse_many(&files, &cfg).context("parse inputs")?;
-            let use_ndjson = args.ndjson || cfg.output.ndjson;
-
-            match &args.output {
+            let report = bench::run(&files, &cfg, args.iterations.max(1))?;
+            let mut out = std::io::stdout().lock();
+            match args.format {
+                cli::ReportFormat::Table => bench::write_report_table(&mut out, &report)?,
+                cli::ReportFormat::Json => {
+                    serde_json::to_writer_pretty(&mut out, &report)?;
+                    writeln!(out)?;
+                }
+            }
+        }
+        cli::Command::Validate(args) => {
+            let issues = validate::validate_file(&args.input)?;
+            let mut out = std::io::stdout().lock();
+            for issue in &issues {
+                writeln!(out, "{}: {}", issue.location, issue.message)?;
+            }
+            if !issues.is_empty() {
+                bail!(
+                    "{} failed validation with {} issues",
+                    args.input.display(),
+                    issues.len()
+                );
+            }
+            info!(path = %args.input.display(), "bundle is valid");
+        }
+        cli::Command::Verify(args) => {
+            let bundle = bundle::load(&args.bundle)?;
+            let report = verify::run(&bundle, &args.inputs, args.recursive, args.follow_symlinks)?;
+            let mut out = std::io::stdout().lock();
+            match args.format {
+                cli::ReportFormat::Table => verify::write_report_table(&mut out, &report)?,
+                cli::ReportFormat::Json => {
+                    serde_json::to_writer_pretty(&mut out, &report)?;
+                    writeln!(out)?;
+                }
+            }
+            let failures = report.failures();
+            if failures > 0 {
+                bail!(
+                    "{failures} of {} files changed, moved or disappeared since {} was written",
+                    report.checked,
+                    args.bundle.display()
+                );
+            }
+        }
+        cli::Command::Query(args) => {
+            let bundle = bundle::load(&args.input)?;
+            let result = query::run(&bundle, &args.expression)?;
+            let mut out = std::io::stdout().lock();
+            query::write_result(&mut out, &result, args.raw, args.compact)?;
+        }
+        cli::Command::Search(args) => {
+            let hits = match &args.input {
                 Some(path) => {
-                    let mut out = std::io::BufWriter::new(
-                        std::fs::File::create(path)
-                            .with_context(|| format!("create output {}", path.display()))?,
-                    );
-                    write_output(
-                        &mut out,
-                        &bundle,
-                        args.pretty || cfg.output.pretty_json,
-                        use_ndjson,
-                    )?;
-                    out.flush()?;
-                    info!(path = %path.display(), ndjson = use_ndjson, "wrote output");
+                    let mut index = search::SearchIndex::in_memory()?;
+                    index.add_bundle(&bundle::load(path)?)?;
+                    index.search(&args.query, args.limit)?
                 }
-                None => {
-                    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
-                    write_output(
-                        &mut out,
-                        &bundle,
-                        args.pretty || cfg.output.pretty_json,
-                        use_ndjson,
-                    )?;
-                    out.flush()?;
+                None => open_store(cli, args.index.as_deref())?
+                    .search()
+                    .search(&args.query, args.limit)?,
+            };
+            let mut out = std::io::stdout().lock();
+            match args.format {
+                cli::ReportFormat::Table => search::write_hits_table(&mut out, &hits)?,
+                cli::ReportFormat::Json => {
+                    serde_json::to_writer_pretty(&mut out, &hits)?;
+                    writeln!(out)?;
                 }
             }
         }
+        cli::Command::Schema(args) => {
+            let schema = match args.target {
+                cli::SchemaTarget::Bundle => schemars::schema_for!(model::OutputBundle),
+                cli::SchemaTarget::Document => schemars::schema_for!(model::ParsedDocument),
+            };
+            let json = serde_json::to_string_pretty(&schema)?;
+            if let Some(path) = &args.output {
+                std::fs::write(path, format!("{json}\n"))
+                    .with_context(|| format!("write schema to {}", path.display()))?;
+                info!(path = %path.display(), "wrote JSON schema");
+            } else {
+                println!("{json}");
+            }
+        }
+        cli::Command::Parse(args) => run_parse(cli, args)?,
     }
 
     Ok(())
 }
 
-fn write_output<W: Write>(
-    out: &mut W,
-    bundle: &model::OutputBundle,
-    pretty_json: bool,
-    ndjson: bool,
-) -> Result<()> {
-    if ndjson {
-        for doc in &bundle.documents {
-            let line = serde_json::to_string(doc)?;
-            out.write_all(line.as_bytes())?;
-            out.write_all(b"\n")?;
-        }
-        for err in &bundle.errors {
-            let line = serde_json::json!({
-                "type": "error",
-                "data": err
-            })
-            .to_string();
-            out.write_all(line.as_bytes())?;
-            out.write_all(b"\n")?;
-        }
-        let summary = serde_json::json!({
-            "type": "summary",
-            "data": &bundle.stats
-        })
-        .to_string();
-        out.write_all(summary.as_bytes())?;
-        out.write_all(b"\n")?;
+fn run_export(cli: &cli::Cli, args: &cli::ExportArgs) -> Result<()> {
+    let bundle = bundle::load(&args.input)?;
+    let client = match args.to {
+        cli::ExportTarget::Magnets => {
+            let written = output::torrent::write_magnet_lists(&args.out_dir, &bundle)
+                .with_context(|| format!("write magnet lists to {}", args.out_dir.display()))?;
+            info!(path = %args.out_dir.display(), files = written, "wrote magnet lists");
+            return Ok(());
+        }
+        cli::ExportTarget::Aria2 => {
+            let opts = output::aria2::Aria2Options {
+                dir_template: &args.dir_template,
+                out_template: args.out_template.as_deref(),
+            };
+            output::aria2::check_templates(&opts)?;
+            let mut out =
+                output::open_output(args.output.as_deref(), config::Compression::Auto, true)?;
+            let links = output::aria2::write_input_file(&mut out, &bundle, &opts)?;
+            out.finish()?;
+            match &args.output {
+                Some(path) => info!(path = %path.display(), links, "wrote aria2 input file"),
+                None => info!(links, "wrote aria2 input file"),
+            }
+            return Ok(());
+        }
+        cli::ExportTarget::Qbittorrent => output::torrent::Client::Qbittorrent,
+        cli::ExportTarget::Transmission => output::torrent::Client::Transmission,
+    };
+
+    let mut cfg = config::Config::load(cli.config.as_deref())?.export;
+    let overrides = [
+        (&mut cfg.url, &args.url),
+        (&mut cfg.username, &args.username),
+        (&mut cfg.password, &args.password),
+        (&mut cfg.category, &args.category),
+        (&mut cfg.save_path, &args.save_path),
+    ];
+    for (field, value) in overrides {
+        if let Some(value) = value {
+            field.clone_from(value);
+        }
+    }
+    cfg.paused |= args.paused;
+
+    let handoffs = output::torrent::plan(&bundle, &cfg);
+    let magnets: usize = handoffs.iter().map(|h| h.magnets.len()).sum();
+    if args.dry_run {
+        for handoff in &handoffs {
+            println!(
+                "{}\t{}\t{}",
+                handoff.category.as_deref().unwrap_or("-"),
+                handoff.magnets.len(),
+                handoff.title
+            );
+        }
+        info!(releases = handoffs.len(), magnets, "dry run, nothing sent");
         return Ok(());
     }
+    let stats = output::torrent::push(client, &cfg, &handoffs)?;
+    info!(
+        client = ?args.to,
+        added = stats.added,
+        duplicates = stats.duplicates,
+        failed = stats.failed,
+        "handed magnets to torrent client"
+    );
+    if stats.failed > 0 {
+        anyhow::bail!("{} of {magnets} magnets were rejected", stats.failed);
+    }
+    Ok(())
+}
+
+fn run_parse(cli: &cli::Cli, args: &cli::ParseArgs) -> Result<()> {
+    let stats = parse_and_write(cli, args);
+    progress::finish();
+    let stats = stats?;
+    porcelain::record("inputs", stats.input_count);
+    porcelain::record("parsed_ok", stats.parsed_ok);
+    porcelain::record("parsed_err", stats.parsed_err);
+    if args.append {
+        porcelain::record("already_present", stats.already_present);
+    }
+    if let Some(path) = args.output.as_ref().or(args.output_dir.as_ref()) {
+        porcelain::record("output", path.display().to_string());
+    }
+    // Checked only once the output is written, so a failing run still leaves
+    // what did parse behind.
+    let max_rate = match (args.fail_on_error, args.max_error_rate) {
+        (true, _) => 0.0,
+        (false, Some(cli::ErrorRate(rate))) => rate,
+        (false, None) => return Ok(()),
+    };
+    let rate = if stats.input_count == 0 {
+        0.0
+    } else {
+        stats.parsed_err as f64 / stats.input_count as f64
+    };
+    if rate > max_rate {
+        bail!(
+            "{} of {} inputs failed to parse ({:.1}%, allowed {:.1}%)",
+            stats.parsed_err,
+            stats.input_count,
+            rate * 100.0,
+            max_rate * 100.0
+        );
+    }
+    Ok(())
+}
+
+// One line per parsed document, in input order; an empty line when the
+// document has no value, so output stays aligned with the inputs that parsed.
+fn write_field_values(
+    files: &[std::path::PathBuf],
+    cfg: &config::Config,
+    field: &str,
+    filter: Option<&filter::Expr>,
+    redact_links: bool,
+) -> Result<model::Stats> {
+    let mut out = std::io::stdout().lock();
+    let mut found = 0;
+    let (stats, _) = parser::parse_streaming(files, cfg, |mut doc| {
+        if let Some(expr) = filter
+            && !filter::matches(expr, &serde_json::to_value(&doc)?)
+        {
+            return Ok(ControlFlow::Continue(()));
+        }
+        if redact_links {
+            redact::redact_document_links(&mut doc);
+        }
+        let value = serde_json::to_value(&doc)?;
+        let text = output::field_text(&value, field, &cfg.output.list_delimiter);
+        found += usize::from(!text.is_empty());
+        match writeln!(out, "{}", text.replace('\n', " ")) {
+            Ok(()) => Ok(ControlFlow::Continue(())),
+            // The reader went away (`| head`); nothing more would be printed.
+            Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(ControlFlow::Break(())),
+            Err(err) => Err(err).context("write field values"),
+        }
+    })
+    .context("parse inputs")?;
+    if found == 0 && stats.parsed_ok > 0 {
+        warn!(field, "no document has a value for this field");
+    }
+    Ok(stats)
+}
+
+// The bar would end up in captured output or interleaved with JSON logs, so
+// it only shows on an interactive terminal with human-readable logs.
+fn show_progress(cli: &cli::Cli, args: &cli::ParseArgs) -> bool {
+    use std::io::IsTerminal;
+    !args.no_progress
+        && !cli.quiet
+        && !cli.porcelain
+        && !matches!(cli.log_format, cli::LogFormat::Json)
+        && std::io::stdout().is_terminal()
+        && std::io::stderr().is_terminal()
+}
+
+fn parse_and_write(cli: &cli::Cli, args: &cli::ParseArgs) -> Result<model::Stats> {
+    let mut cfg = config::Config::load(cli.config.as_deref())?;
+    cfg.output.explain |= args.explain;
+    if !args.fields.is_empty() {
+        cfg.output.fields = args.fields.clone();
+    }
+    if !args.exclude_fields.is_empty() {
+        cfg.output.exclude_fields = args.exclude_fields.clone();
+    }
+    if let Some(sort) = args.sort {
+        cfg.output.sort = sort.into();
+    }
+    if args.ndjson {
+        cfg.output.format = config::OutputFormat::Ndjson;
+    } else if let Some(format) = args.format {
+        cfg.output.format = format.into();
+    } else if cfg.output.ndjson && cfg.output.format == config::OutputFormat::Json {
+        cfg.output.format = config::OutputFormat::Ndjson;
+    }
+    output::check_format_supported(cfg.output.format)?;
+    cfg.output.records |= args.records;
+    if let Some(delimiter) = &args.list_delimiter {
+        cfg.output.list_delimiter = delimiter.clone();
+    }
+    cfg.output.atomic &= !args.no_atomic;
+    if let Some(compress) = args.compress {
+        cfg.output.compress = compress.into();
+    }
+    if let Some(order) = args.sort_order {
+        cfg.output.sort_order = order.into();
+    }
+    if !args.redact.is_empty() {
+        cfg.output.redact = args.redact.iter().map(|&r| r.into()).collect();
+    }
+    let projection = project::Projection::new(&cfg.output.fields, &cfg.output.exclude_fields);
+    let filter = args
+        .filter
+        .as_deref()
+        .map(filter::parse)
+        .transpose()
+        .context("parse --filter expression")?;
+    let redact_links = cfg.output.redact.contains(&config::RedactTarget::Links);
+    if args.output_dir.is_some() {
+        output::per_input::check_template(&args.output_template)?;
+    }
+    let mut seen = match &args.output {
+        Some(path) if args.append => {
+            if cfg.output.format != config::OutputFormat::Ndjson {
+                anyhow::bail!("--append only works with NDJSON output");
+            }
+            let seen = output::append::SeenDocuments::load(path, cfg.output.compress)
+                .with_context(|| format!("read existing output {}", path.display()))?;
+            info!(path = %path.display(), keys = seen.len(), "appending to existing output");
+            Some(seen)
+        }
+        _ => None,
+    };
+    if args.shard_size.is_some() && cfg.output.format != config::OutputFormat::Ndjson {
+        anyhow::bail!("--shard-size only works with NDJSON output");
+    }
+    let open_output = |path: Option<&std::path::Path>| match (path, args.append) {
+        (Some(path), true) => output::append_output(path, cfg.output.compress),
+        (path, _) => output::open_output(path, cfg.output.compress, cfg.output.atomic),
+    };
+    let open_ndjson = || -> Result<output::shard::NdjsonOutput> {
+        Ok(match (&args.output, args.shard_size) {
+            (Some(path), Some(size)) => {
+                output::shard::NdjsonOutput::Sharded(output::shard::ShardedNdjson::new(
+                    path,
+                    size,
+                    cfg.output.compress,
+                    cfg.output.atomic,
+                ))
+            }
+            // A run that creates the file still closes it with a summary.
+            (Some(path), None) if args.append && path.metadata().is_ok_and(|m| m.len() > 0) => {
+                output::shard::NdjsonOutput::Appended(open_output(Some(path))?)
+            }
+            (path, _) => output::shard::NdjsonOutput::Single(open_output(path.as_deref())?),
+        })
+    };
+    let write_errors = |path: &std::path::Path, errors: &[model::ParseError]| -> Result<()> {
+        output::write_error_stream(path, errors, cfg.output.compress, cfg.output.atomic)
+            .with_context(|| format!("write errors to {}", path.display()))?;
+        info!(path = %path.display(), errors = errors.len(), "wrote parse errors");
+        Ok(())
+    };
+    let files = fs::collect_html_inputs(&args.inputs, args.recursive, args.follow_symlinks)
+        .context("collect inputs")?;
 
-    let json = if pretty_json {
-        serde_json::to_string_pretty(bundle)?
+    if files.is_empty() {
+        warn!("no input HTML files found");
     } else {
-        serde_json::to_string(bundle)?
+        info!(count = files.len(), "collected input HTML files");
+    }
+    if show_progress(cli, args) {
+        progress::start(files.len());
+    }
+    if let Some(field) = &args.get {
+        return write_field_values(&files, &cfg, field, filter.as_ref(), redact_links);
+    }
+
+    // NDJSON is written record by record while parsing unless a pass over the
+    // whole corpus (dedupe, the infohash index, sort) has to see every document
+    // first.
+    if cfg.output.format == config::OutputFormat::Ndjson
+        && args.output_dir.is_none()
+        && cfg.dedupe.by == config::DedupeBy::None
+        && !cfg.dedupe.infohash_index
+        && cfg.output.sort == config::SortBy::None
+    {
+        let mut out = open_ndjson()?;
+        let mut filtered_out = 0;
+        let mut already_present = 0;
+        let (mut stats, errors) = parser::parse_streaming(&files, &cfg, |mut doc| {
+            if let Some(expr) = &filter
+                && !filter::matches(expr, &serde_json::to_value(&doc)?)
+            {
+                filtered_out += 1;
+                return Ok(ControlFlow::Continue(()));
+            }
+            if let Some(seen) = &mut seen
+                && !seen.insert(&doc)
+            {
+                already_present += 1;
+                return Ok(ControlFlow::Continue(()));
+            }
+            if redact_links {
+                redact::redact_document_links(&mut doc);
+            }
+            out.write_record(&output::document_value(
+                &doc,
+                &projection,
+                cfg.output.include_nulls,
+            )?)?;
+            Ok(ControlFlow::Continue(()))
+        })
+        .context("parse inputs")?;
+        stats.filtered_out = filtered_out;
+        stats.already_present = already_present;
+        let errors = match &args.errors_out {
+            Some(path) => {
+                write_errors(path, &errors)?;
+                Vec::new()
+            }
+            None => errors,
+        };
+        out.finish(SCHEMA_VERSION, &errors, &stats)?;
+        if let Some(path) = &args.output
+            && args.shard_size.is_none()
+        {
+            info!(path = %path.display(), format = ?cfg.output.format, "wrote output");
+        }
+        return Ok(stats);
+    }
+
+    let mut bundle = parser::parse_many(&files, &cfg).context("parse inputs")?;
+    if let Some(expr) = &filter {
+        let before = bundle.documents.len();
+        bundle.documents.retain(|doc| {
+            serde_json::to_value(doc).is_ok_and(|value| filter::matches(expr, &value))
+        });
+        bundle.stats.filtered_out = before - bundle.documents.len();
+        info!(
+            kept = bundle.documents.len(),
+            dropped = bundle.stats.filtered_out,
+            "applied filter"
+        );
+    }
+    project::sort_documents(
+        &mut bundle.documents,
+        cfg.output.sort,
+        cfg.output.sort_order,
+        cfg.profile.utc_offset(),
+    );
+    if redact_links {
+        redact::redact_links(&mut bundle);
+    }
+    if let Some(seen) = &mut seen {
+        let before = bundle.documents.len();
+        bundle.documents.retain(|doc| seen.insert(doc));
+        bundle.stats.already_present = before - bundle.documents.len();
+    }
+    if let Some(path) = &args.errors_out {
+        write_errors(path, &bundle.errors)?;
+        bundle.errors.clear();
+    }
+    if let Some(dir) = &args.output_dir {
+        let written = output::per_input::write_per_input(
+            &bundle,
+            &projection,
+            &output::per_input::PerInputOptions {
+                dir,
+                template: &args.output_template,
+                inputs: &args.inputs,
+                pretty_json: args.pretty || cfg.output.pretty_json,
+                include_nulls: cfg.output.include_nulls,
+                compress: cfg.output.compress,
+                atomic: cfg.output.atomic,
+            },
+        )?;
+        info!(path = %dir.display(), files = written, "wrote per-input outputs");
+        return Ok(bundle.stats);
+    }
+    if args.shard_size.is_some() || args.append {
+        let mut out = open_ndjson()?;
+        for doc in &bundle.documents {
+            out.write_record(&output::document_value(
+                doc,
+                &projection,
+                cfg.output.include_nulls,
+            )?)?;
+        }
+        out.finish(SCHEMA_VERSION, &bundle.errors, &bundle.stats)?;
+        return Ok(bundle.stats);
+    }
+    let opts = output::WriteOptions {
+        format: cfg.output.format,
+        pretty_json: args.pretty || cfg.output.pretty_json,
+        records: cfg.output.records,
+        list_delimiter: &cfg.output.list_delimiter,
+        fields: &cfg.output.fields,
+        exclude_fields: &cfg.output.exclude_fields,
+        include_nulls: cfg.output.include_nulls,
     };
-    out.write_all(json.as_bytes())?;
-    out.write_all(b"\n")?;
+
+    write_bundle(
+        args.output.as_deref(),
+        &bundle,
+        &projection,
+        &opts,
+        open_output,
+    )?;
+    Ok(bundle.stats)
+}
+
+fn write_bundle(
+    path: Option<&std::path::Path>,
+    bundle: &model::OutputBundle,
+    projection: &project::Projection,
+    opts: &output::WriteOptions,
+    open_output: impl FnOnce(Option<&std::path::Path>) -> Result<output::Sink>,
+) -> Result<()> {
+    match path {
+        Some(path) if opts.format == config::OutputFormat::Sqlite => {
+            output::sqlite::write_database(path, bundle)?;
+            info!(path = %path.display(), documents = bundle.documents.len(), "wrote sqlite database");
+        }
+        path => {
+            let mut out = open_output(path)?;
+            output::write_output(&mut out, bundle, projection, opts)?;
+            out.finish()?;
+            if let Some(path) = path {
+                info!(path = %path.display(), format = ?opts.format, "wrote output");
+            }
+        }
+    }
     Ok(())
 }
+
+// One link per line, written as each file is parsed so a downloader at the
+// other end of the pipe can start right away. Links repeated across files
+// are printed once.
+fn run_extract(
+    files: &[std::path::PathBuf],
+    cfg: &config::Config,
+    magnets: bool,
+    torrents: bool,
+) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    let mut out = std::io::stdout().lock();
+    for path in files {
+        let (magnet_links, torrent_links) = match parser::extract_download_links(path, cfg) {
+            Ok(found) => found,
+            Err(err) => {
+                warn!(path = %path.display(), error = %format!("{err:#}"), "extract failed");
+                continue;
+            }
+        };
+        let links = magnet_links
+            .into_iter()
+            .filter(|_| magnets)
+            .chain(torrent_links.into_iter().filter(|_| torrents));
+        for link in links {
+            if seen.insert(link.clone())
+                && let Err(err) = writeln!(out, "{link}")
+            {
+                // The reader went away (`| head`); nothing left to do.
+                if err.kind() == std::io::ErrorKind::BrokenPipe {
+                    return Ok(());
+                }
+                return Err(err).context("write links");
+            }
+        }
+        out.flush().context("write links")?;
+    }
+    Ok(())
+}
+
+// `--to` falls back to the output extension, then the configured format.
+fn run_convert(cli: &cli::Cli, args: &cli::ConvertArgs) -> Result<()> {
+    let mut cfg = config::Config::load(cli.config.as_deref())?;
+    if let Some(format) = args
+        .to
+        .map(Into::into)
+        .or_else(|| args.output.as_deref().and_then(bundle::format_for_path))
+    {
+        cfg.output.format = format;
+    }
+    output::check_format_supported(cfg.output.format)?;
+    let bundle = bundle::load_as(&args.input, args.from.map(Into::into))?;
+    info!(
+        path = %args.input.display(),
+        documents = bundle.documents.len(),
+        format = ?cfg.output.format,
+        "converting bundle"
+    );
+
+    write_saved_bundle(&cfg, args.output.as_deref(), args.pretty, &bundle)
+}
+
+// `--index` wins over `[index] dir`.
+fn store_dir(cli: &cli::Cli, index: Option<&std::path::Path>) -> Result<std::path::PathBuf> {
+    Ok(match index {
+        Some(dir) => dir.to_path_buf(),
+        None => config::Config::load(cli.config.as_deref())?.index.dir,
+    })
+}
+
+fn open_store(cli: &cli::Cli, index: Option<&std::path::Path>) -> Result<store::Store> {
+    store::Store::open_existing(&store_dir(cli, index)?)
+}
+
+// Saved bundles are ingested as they are; HTML files and directories are
+// parsed first with the configured extraction settings.
+fn run_index(cli: &cli::Cli, args: &cli::IndexArgs) -> Result<()> {
+    let cfg = config::Config::load(cli.config.as_deref())?;
+    let dir = args.index.clone().unwrap_or_else(|| cfg.index.dir.clone());
+    let mut store = store::Store::open(&dir)?;
+
+    let (pages, bundles): (Vec<_>, Vec<_>) = args
+        .inputs
+        .iter()
+        .cloned()
+        .partition(|p| p.is_dir() || fs::is_html(p));
+    for path in &bundles {
+        let bundle = bundle::load(path)?;
+        store.ingest(&bundle)?;
+        info!(path = %path.display(), documents = bundle.documents.len(), "indexed bundle");
+    }
+    if !pages.is_empty() {
+        let files = fs::collect_html_inputs(&pages, args.recursive, args.follow_symlinks)
+            .context("collect inputs")?;
+        let bundle = parser::parse_many(&files, &cfg).context("parse inputs")?;
+        store.ingest(&bundle)?;
+        info!(
+            files = files.len(),
+            documents = bundle.documents.len(),
+            errors = bundle.errors.len(),
+            "indexed parsed inputs"
+        );
+    }
+    info!(path = %dir.display(), documents = store.document_count()?, "index updated");
+    Ok(())
+}
+
+// The watcher runs on its own thread; the API serves from the catalogue it
+// keeps updated.
+fn run_daemon(cli: &cli::Cli, args: &cli::DaemonArgs) -> Result<()> {
+    let cfg = config::Config::load(cli.config.as_deref())?;
+    let dir = args.index.clone().unwrap_or_else(|| cfg.index.dir.clone());
+    let mut store = store::Store::open(&dir)?;
+    let catalogue = Arc::new(RwLock::new(serve::Catalogue::from_store(&store)?));
+    info!(
+        path = %dir.display(),
+        documents = catalogue.read().map_or(0, |c| c.document_count()),
+        "opened index"
+    );
+
+    let opts = daemon::WatchOptions {
+        inputs: args.inputs.clone(),
+        recursive: args.recursive,
+        follow_symlinks: args.follow_symlinks,
+        interval: std::time::Duration::from_secs(args.interval.max(1)),
+    };
+    let watched = Arc::clone(&catalogue);
+    let watcher = std::thread::Builder::new()
+        .name("watch".into())
+        .spawn(move || daemon::watch(&cfg, &mut store, &watched, &opts))
+        .context("start watcher thread")?;
+    let listen = args.listen.clone();
+    let server = std::thread::Builder::new()
+        .name("serve".into())
+        .spawn(move || serve::serve(&listen, catalogue))
+        .context("start server thread")?;
+
+    // Serving a catalogue that no longer follows the inputs would go unnoticed,
+    // so the daemon stops as soon as either thread does.
+    while !watcher.is_finished() && !server.is_finished() {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+    if server.is_finished() {
+        return server
+            .join()
+            .map_err(|_| anyhow::anyhow!("server thread panicked"))?;
+    }
+    match watcher.join() {
+        Ok(()) => bail!("watcher stopped"),
+        Err(_) => bail!("watcher thread panicked; stopping the daemon"),
+    }
+}
+
+fn run_dedupe(cli: &cli::Cli, args: &cli::DedupeArgs) -> Result<()> {
+    let mut cfg = config::Config::load(cli.config.as_deref())?;
+    if let Some(format) = args
+        .format
+        .map(Into::into)
+        .or_else(|| args.output.as_deref().and_then(bundle::format_for_path))
+    {
+        cfg.output.format = format;
+    }
+    let mut bundle = bundle::load(&args.input)?;
+    let before = bundle.documents.len();
+    let discarded = dedupe::dedupe_documents(
+        &mut bundle.documents,
+        &config::DedupeConfig {
+            by: args.by.into(),
+            ..cfg.dedupe.clone()
+        },
+        cfg.profile.utc_offset(),
+    );
+    info!(
+        path = %args.input.display(),
+        documents = before,
+        kept = bundle.documents.len(),
+        dropped = discarded.len(),
+        "deduplicated bundle"
+    );
+
+    match &args.report {
+        Some(path) => {
+            let json = serde_json::to_string_pretty(&discarded)?;
+            std::fs::write(path, json + "\n")
+                .with_context(|| format!("write report {}", path.display()))?;
+        }
+        None => {
+            let mut err = std::io::stderr().lock();
+            for dup in &discarded {
+                writeln!(
+                    err,
+                    "dropped {} ({}, kept {})",
+                    dup.path, dup.key, dup.kept_path
+                )?;
+            }
+        }
+    }
+    bundle.stats.duplicates_discarded += discarded.len();
+    bundle.stats.discarded_duplicates.extend(discarded);
+    // Recounted from what the bundle now holds, so kept plus dropped documents
+    // add up to `parsed_ok` even when the input's counters did not.
+    let stats = &mut bundle.stats;
+    stats.parsed_ok = bundle.documents.len()
+        + stats.duplicates_discarded
+        + stats.filtered_out
+        + stats.already_present;
+    stats.parsed_err = bundle.errors.len();
+    stats.input_count = stats.parsed_ok + stats.parsed_err;
+    // Both still name the dropped documents.
+    if !bundle.infohash_index.is_empty() {
+        bundle.infohash_index = dedupe::infohash_index(&mut bundle.documents);
+    }
+    if bundle.stats.release_gaps.is_some() {
+        bundle.stats.release_gaps = stats::release_gaps(&bundle.documents);
+    }
+
+    write_saved_bundle(&cfg, args.output.as_deref(), args.pretty, &bundle)
+}
+
+fn run_enrich(cli: &cli::Cli, args: &cli::EnrichArgs) -> Result<()> {
+    let mut cfg = config::Config::load(cli.config.as_deref())?;
+    if let Some(format) = args
+        .format
+        .map(Into::into)
+        .or_else(|| args.output.as_deref().and_then(bundle::format_for_path))
+    {
+        cfg.output.format = format;
+    }
+    if let Some(source) = args.source {
+        cfg.enrich.source = source.into();
+    }
+    if let Some(min_score) = args.min_score {
+        cfg.enrich.min_score = min_score;
+    }
+    if let Some(key) = &args.steam_api_key {
+        cfg.enrich.steam_api_key.clone_from(key);
+    }
+    if let Some(token) = &args.igdb_token {
+        cfg.enrich.igdb_access_token.clone_from(token);
+    }
+    let mut bundle = bundle::load(&args.input)?;
+    let stats = enrich::enrich(&mut bundle.documents, &cfg.enrich)?;
+    info!(
+        source = ?cfg.enrich.source,
+        matched = stats.matched,
+        unmatched = stats.unmatched,
+        failed = stats.failed,
+        "enriched bundle"
+    );
+    write_saved_bundle(&cfg, args.output.as_deref(), args.pretty, &bundle)
+}
+
+// Writes a bundle that was loaded rather than parsed, honouring the
+// `[output]` projection and format settings.
+fn write_saved_bundle(
+    cfg: &config::Config,
+    output: Option<&std::path::Path>,
+    pretty: bool,
+    bundle: &model::OutputBundle,
+) -> Result<()> {
+    let projection = project::Projection::new(&cfg.output.fields, &cfg.output.exclude_fields);
+    let opts = output::WriteOptions {
+        format: cfg.output.format,
+        pretty_json: pretty || cfg.output.pretty_json,
+        records: cfg.output.records,
+        list_delimiter: &cfg.output.list_delimiter,
+        fields: &cfg.output.fields,
+        exclude_fields: &cfg.output.exclude_fields,
+        include_nulls: cfg.output.include_nulls,
+    };
+    write_bundle(output, bundle, &projection, &opts, |path| {
+        output::open_output(path, cfg.output.compress, cfg.output.atomic)
+    })
+}
+
+fn run_merge(cli: &cli::Cli, args: &cli::MergeArgs) -> Result<()> {
+    let mut cfg = config::Config::load(cli.config.as_deref())?;
+    if let Some(format) = args.format {
+        cfg.output.format = format.into();
+    }
+    let mut bundles = Vec::with_capacity(args.inputs.len());
+    for path in &args.inputs {
+        bundles.push(bundle::load(path)?);
+    }
+    let merged = merge::merge(
+        bundles,
+        &merge::MergeOptions {
+            winner: args.on_conflict.into(),
+            prefer_non_null: args.prefer_non_null,
+            utc_offset: cfg.profile.utc_offset(),
+        },
+    )?;
+    info!(
+        inputs = args.inputs.len(),
+        documents = merged.documents.len(),
+        duplicates = merged.stats.duplicates_discarded,
+        "merged bundles"
+    );
+
+    write_saved_bundle(&cfg, args.output.as_deref(), args.pretty, &merged)
+}
@@ -0,0 +1,143 @@
+use crate::dedupe;
+use crate::model::{
+    DiscardedDuplicate, OutputBundle, ParseError, ParsedDocument, SCHEMA_VERSION, Stats, ToolInfo,
+};
+use crate::stats;
+use anyhow::Result;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::debug;
+
+// Which copy of a document wins when several inputs contain the same doc_id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winner {
+    Newest,
+    First,
+    Last,
+}
+
+pub struct MergeOptions {
+    pub winner: Winner,
+    pub prefer_non_null: bool,
+    pub utc_offset: chrono::FixedOffset,
+}
+
+// Documents are keyed by doc_id (content hash when a document has none) and
+// kept in first-seen order; stats, release gaps and the infohash index are
+// recomputed for the merged set.
+pub fn merge(bundles: Vec<OutputBundle>, opts: &MergeOptions) -> Result<OutputBundle> {
+    let mut input_count = 0;
+    let mut parsed_ok = 0;
+    let mut discarded = Vec::new();
+    let mut errors: Vec<ParseError> = Vec::new();
+    let mut error_paths = BTreeSet::new();
+    let mut order: Vec<String> = Vec::new();
+    let mut groups: BTreeMap<String, Vec<ParsedDocument>> = BTreeMap::new();
+
+    for bundle in bundles {
+        input_count += bundle.stats.input_count;
+        parsed_ok += bundle.stats.parsed_ok;
+        discarded.extend(bundle.stats.discarded_duplicates);
+        for err in bundle.errors {
+            if error_paths.insert(err.path.clone()) {
+                errors.push(err);
+            }
+        }
+        for doc in bundle.documents {
+            let key = match &doc.doc_id {
+                Some(id) => format!("doc_id:{id}"),
+                None => format!("sha256:{}", doc.source.sha256),
+            };
+            let group = groups.entry(key.clone()).or_default();
+            if group.is_empty() {
+                order.push(key);
+            }
+            group.push(doc);
+        }
+    }
+
+    let mut documents = Vec::with_capacity(order.len());
+    for key in order {
+        let mut group = groups.remove(&key).unwrap_or_default();
+        // Best candidate first; input order breaks recency ties in favour of
+        // the later input, as with `--on-conflict last`.
+        match opts.winner {
+            Winner::Newest => {
+                let mut ranked: Vec<(usize, ParsedDocument)> =
+                    group.into_iter().enumerate().collect();
+                ranked.sort_by_key(|(i, doc)| Reverse((dedupe::recency(doc, opts.utc_offset), *i)));
+                group = ranked.into_iter().map(|(_, doc)| doc).collect();
+            }
+            Winner::First => {}
+            Winner::Last => group.reverse(),
+        }
+        let mut rest = group.into_iter();
+        let Some(mut kept) = rest.next() else {
+            continue;
+        };
+        let losers: Vec<ParsedDocument> = rest.collect();
+        if opts.prefer_non_null && !losers.is_empty() {
+            let mut value = serde_json::to_value(&kept)?;
+            for loser in &losers {
+                fill_missing(&mut value, &serde_json::to_value(loser)?);
+            }
+            kept = serde_json::from_value(value)?;
+        }
+        for loser in losers {
+            debug!(key = %key, path = %loser.source.path, kept = %kept.source.path, "merged duplicate");
+            discarded.push(DiscardedDuplicate {
+                key: key.clone(),
+                path: loser.source.path,
+                kept_path: kept.source.path.clone(),
+            });
+        }
+        documents.push(kept);
+    }
+
+    let infohash_index = dedupe::infohash_index(&mut documents);
+    let stats = Stats {
+        input_count,
+        parsed_ok,
+        parsed_err: errors.len(),
+        duplicates_discarded: discarded.len(),
+        discarded_duplicates: discarded,
+        filtered_out: 0,
+        already_present: 0,
+        release_gaps: stats::release_gaps(&documents),
+    };
+    Ok(OutputBundle {
+        schema_version: SCHEMA_VERSION,
+        tool: ToolInfo::current(),
+        stats,
+        documents,
+        errors,
+        infohash_index,
+    })
+}
+
+// Copies fields the winner lacks (missing, null or empty) from another copy.
+// `source` stays the winner's so the document still points at one file.
+fn fill_missing(target: &mut Value, other: &Value) {
+    let (Value::Object(target), Value::Object(other)) = (target, other) else {
+        return;
+    };
+    for (key, theirs) in other {
+        if key == "source" {
+            continue;
+        }
+        match target.get_mut(key) {
+            None | Some(Value::Null) => {
+                target.insert(key.clone(), theirs.clone());
+            }
+            Some(Value::Array(ours)) if ours.is_empty() => {
+                target.insert(key.clone(), theirs.clone());
+            }
+            Some(Value::String(ours)) if ours.is_empty() => {
+                target.insert(key.clone(), theirs.clone());
+            }
+            Some(ours @ Value::Object(_)) => fill_missing(ours, theirs),
+            Some(_) => {}
+        }
+    }
+}
@@ -1,38 +1,106 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Bump whenever a field is renamed, removed or changes type; additive changes
+// keep the current version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct OutputBundle {
+    // Bundles written before the field existed load as version 0.
+    #[serde(default)]
+    pub schema_version: u32,
     pub tool: ToolInfo,
     pub stats: Stats,
     pub documents: Vec<ParsedDocument>,
     pub errors: Vec<ParseError>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub infohash_index: BTreeMap<String, Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ToolInfo {
     pub name: String,
     pub version: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ToolInfo {
+    // This build, as recorded in every bundle it writes.
+    pub fn current() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME").to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Stats {
     pub input_count: usize,
     pub parsed_ok: usize,
     pub parsed_err: usize,
+
+    #[serde(skip_serializing_if = "is_zero", default)]
+    pub duplicates_discarded: usize,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub discarded_duplicates: Vec<DiscardedDuplicate>,
+
+    #[serde(skip_serializing_if = "is_zero", default)]
+    pub filtered_out: usize,
+
+    #[serde(skip_serializing_if = "is_zero", default)]
+    pub already_present: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_gaps: Option<ReleaseGaps>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReleaseGaps {
+    pub first: u64,
+    pub last: u64,
+    pub present: usize,
+    pub missing: u64,
+    pub gaps: Vec<GapRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GapRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiscardedDuplicate {
+    pub key: String,
+    pub path: String,
+    pub kept_path: String,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParseError {
     pub path: String,
     pub error: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParsedDocument {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_id: Option<String>,
+
     pub source: SourceInfo,
     pub site: String,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub warnings: Vec<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub page: Option<PageMeta>,
 
@@ -42,15 +110,51 @@ pub struct ParsedDocument {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub release: Option<ReleaseMeta>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<Pricing>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_language: Option<ContentLanguage>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub breadcrumbs: Vec<Breadcrumb>,
+
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub spoiler_sections: Vec<SpoilerSection>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<CommentThread>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<Poll>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub direct_download_links: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_donations: Option<Donations>,
+
     #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
     pub link_domain_counts: BTreeMap<String, u64>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_profile: Option<LinkProfile>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub link_section_counts: BTreeMap<String, u64>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub links: Vec<AnchorLink>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub link_category_counts: BTreeMap<String, u64>,
+
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub download_section_headings: Vec<String>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub download_sections: Vec<DownloadSection>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub torrent_file: Option<bool>,
 
@@ -62,16 +166,81 @@ pub struct ParsedDocument {
 
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub magnet_links: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub infohashes: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub shares_payload_with: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub images: Vec<ImageAsset>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub raw_html: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<Confidence>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redacted: Option<RedactionSummary>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub provenance: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub history: Vec<SaveSnapshot>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enrichment: Option<Enrichment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ContentLanguage {
+    pub code: String,
+    pub name: String,
+    pub script: String,
+    pub confidence: f64,
+    pub reliable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RedactionSummary {
+    pub magnet_links: usize,
+    pub infohashes: usize,
+    pub torrent_file_links: usize,
+    pub direct_download_links: usize,
+    pub mirror_links: usize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Confidence {
+    pub overall: f64,
+    pub fields: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SaveSnapshot {
+    pub path: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SourceInfo {
     pub path: String,
     pub bytes: u64,
     pub sha256: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PageMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -81,25 +250,91 @@ pub struct PageMeta {
 
     #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
     pub meta: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apple_touch_icon: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub feeds: Vec<FeedLink>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortlink: Option<String>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub hreflang: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeedLink {
+    pub kind: String,
+    pub href: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PostMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub post_id: Option<u64>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortlink: Option<String>,
+
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub categories: Vec<String>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub categories_latin: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub genres: Vec<String>,
+
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub wp_tags: Vec<String>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub wp_tags_canonical: Vec<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entry_title: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_title_latin: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entry_datetime: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_datetime_rfc3339: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_datetime_epoch: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_rfc3339: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_epoch: Option<i64>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
 
@@ -107,7 +342,7 @@ pub struct PostMeta {
     pub comments_count: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ReleaseMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub release_number: Option<u64>,
@@ -115,24 +350,305 @@ pub struct ReleaseMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub game_title_line: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_title_line_latin: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_slug: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionInfo>,
+
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub genres_tags: Vec<String>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub genres_tags_canonical: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub genres_tags_latin: Vec<String>,
+
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub companies: Vec<String>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub developers: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub publishers: Vec<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub languages_raw: Option<String>,
 
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub languages: Vec<Language>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_count: Option<u32>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub original_size_raw: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub repack_size_raw: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_size_bytes: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repack_size_bytes_min: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repack_size_bytes_max: Option<u64>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub age_ratings: BTreeMap<String, String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub steam_appids: Vec<u64>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub gog_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub review_scores: Vec<ReviewScore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VersionInfo {
+    pub raw: String,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub components: Vec<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct Language {
+    pub name: String,
+    pub iso639_1: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewScore {
+    pub source: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u32>,
+}
+
+// Metadata attached by `enrich`, one entry per source that matched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct Enrichment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steam: Option<MetadataMatch>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub igdb: Option<MetadataMatch>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hltb: Option<Playtime>,
+}
+
+// HowLongToBeat completion estimates, in hours.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Playtime {
+    pub id: u64,
+    pub canonical_name: String,
+    pub score: f64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_hours: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_hours: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completionist_hours: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MetadataMatch {
+    pub id: u64,
+    pub canonical_name: String,
+    // Title similarity of the match, 1.0 for an exact normalized match.
+    pub score: f64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_year: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Pricing {
+    pub store: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original: Option<f64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_percent: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_raw: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Breadcrumb {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SpoilerSection {
     pub title: String,
     pub text: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DownloadSection {
+    pub heading: String,
+    pub links: Vec<SectionLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SectionLink {
+    pub text: String,
+
+    pub href: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LinkProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_host: Option<String>,
+
+    pub internal: u64,
+    pub external: u64,
+    pub relative: u64,
+    pub nofollow: u64,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub internal_domain_counts: BTreeMap<String, u64>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub external_domain_counts: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AnchorLink {
+    pub text: String,
+
+    pub href: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommentThread {
+    pub count: usize,
+    pub items: Vec<Comment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Comment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Poll {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub question: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_votes: Option<u64>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub options: Vec<PollOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PollOption {
+    pub label: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub votes: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Donations {
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub crypto: Vec<CryptoAddress>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub platforms: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CryptoAddress {
+    pub currency: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ImageAsset {
+    pub src: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<String>,
+}
@@ -0,0 +1,64 @@
+use super::sink::resolve;
+use crate::config::Compression;
+use crate::model::ParsedDocument;
+use anyhow::{Context, Result};
+use flate2::read::MultiGzDecoder;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+// doc_ids and content hashes already present in an NDJSON output, so `--append`
+// only adds records for documents the file does not have yet.
+#[derive(Debug, Default)]
+pub struct SeenDocuments {
+    keys: HashSet<String>,
+}
+
+impl SeenDocuments {
+    pub fn load(path: &Path, compression: Compression) -> Result<Self> {
+        let mut seen = Self::default();
+        if !path.exists() {
+            return Ok(seen);
+        }
+        let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        let reader: Box<dyn Read> = match resolve(compression, Some(path)) {
+            Compression::Gzip => Box::new(MultiGzDecoder::new(file)),
+            Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+            Compression::Auto | Compression::None => Box::new(file),
+        };
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line.with_context(|| format!("read {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: Value = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}: invalid JSON", path.display(), i + 1))?;
+            if record.get("type").is_some() {
+                continue;
+            }
+            if let Some(id) = record.get("doc_id").and_then(Value::as_str) {
+                seen.keys.insert(format!("id:{id}"));
+            }
+            if let Some(sha) = record.pointer("/source/sha256").and_then(Value::as_str) {
+                seen.keys.insert(format!("sha256:{sha}"));
+            }
+        }
+        Ok(seen)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    // Records the document's keys and reports whether any was already known.
+    pub fn insert(&mut self, doc: &ParsedDocument) -> bool {
+        let mut new = true;
+        if let Some(id) = &doc.doc_id {
+            new &= self.keys.insert(format!("id:{id}"));
+        }
+        new &= self.keys.insert(format!("sha256:{}", doc.source.sha256));
+        new
+    }
+}
@@ -0,0 +1,99 @@
+use super::markdown::mirror_links;
+use super::per_input::{check_template, render};
+use crate::model::{OutputBundle, ParsedDocument};
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::io::Write;
+
+// Filled per link, on top of the per-document `--output-template` ones.
+const LINK_PLACEHOLDERS: &[&str] = &["file", "host", "n"];
+
+pub struct Aria2Options<'a> {
+    pub dir_template: &'a str,
+    pub out_template: Option<&'a str>,
+}
+
+pub fn check_templates(opts: &Aria2Options) -> Result<()> {
+    for template in [Some(opts.dir_template), opts.out_template]
+        .into_iter()
+        .flatten()
+    {
+        let mut doc_only = template.to_string();
+        for name in LINK_PLACEHOLDERS {
+            doc_only = doc_only.replace(&format!("{{{name}}}"), "");
+        }
+        check_template(&doc_only).with_context(|| {
+            format!(
+                "check aria2 template `{template}` (per-link placeholders: {})",
+                LINK_PLACEHOLDERS.join(", ")
+            )
+        })?;
+    }
+    Ok(())
+}
+
+// An aria2c `--input-file`: each URI followed by indented per-download
+// options. Magnets and `.torrent` links become torrent downloads, so `out=`
+// only names direct and mirror downloads.
+pub fn write_input_file<W: Write>(
+    out: &mut W,
+    bundle: &OutputBundle,
+    opts: &Aria2Options,
+) -> Result<usize> {
+    let mut written = 0;
+    for doc in &bundle.documents {
+        for (i, (uri, torrent)) in links(doc).into_iter().enumerate() {
+            writeln!(out, "{uri}")?;
+            let dir = fill(opts.dir_template, doc, uri, i + 1);
+            if !dir.is_empty() {
+                writeln!(out, "  dir={dir}")?;
+            }
+            if let Some(template) = opts.out_template
+                && !torrent
+            {
+                writeln!(out, "  out={}", fill(template, doc, uri, i + 1))?;
+            }
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+fn links(doc: &ParsedDocument) -> Vec<(&str, bool)> {
+    let mut seen = BTreeSet::new();
+    let torrents = doc
+        .magnet_links
+        .iter()
+        .chain(&doc.torrent_file_links)
+        .map(|uri| (uri.as_str(), true));
+    let mirrors = doc
+        .direct_download_links
+        .iter()
+        .map(String::as_str)
+        .chain(mirror_links(doc).map(|l| l.href.as_str()))
+        .filter(|uri| uri.starts_with("http://") || uri.starts_with("https://"))
+        .map(|uri| (uri, false));
+    torrents
+        .chain(mirrors)
+        .filter(|(uri, _)| seen.insert(*uri))
+        .collect()
+}
+
+fn fill(template: &str, doc: &ParsedDocument, uri: &str, n: usize) -> String {
+    let url = url::Url::parse(uri).ok();
+    let host = url
+        .as_ref()
+        .and_then(|u| u.host_str())
+        .unwrap_or_default()
+        .to_string();
+    let file = url
+        .as_ref()
+        .and_then(|u| u.path_segments()?.next_back().map(str::to_string))
+        .filter(|f| !f.is_empty())
+        .unwrap_or_else(|| format!("download-{n}"));
+    let with_links = template
+        .replace("{file}", &file.replace(['/', '\\'], "_"))
+        .replace("{host}", &host)
+        .replace("{n}", &n.to_string());
+    render(&with_links, doc)
+}
@@ -0,0 +1,82 @@
+use super::{ColumnKind, Table, render_leaf};
+use anyhow::Result;
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{Array, ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{DataType, Field, Schema};
+use std::io::Write;
+use std::sync::Arc;
+
+pub fn record_batch(table: &Table, list_delimiter: &str) -> Result<RecordBatch> {
+    let mut fields = Vec::with_capacity(table.columns.len());
+    let mut arrays: Vec<ArrayRef> = Vec::with_capacity(table.columns.len());
+    for (i, column) in table.columns.iter().enumerate() {
+        let cells = table.rows.iter().map(|row| row[i].as_slice());
+        let (data_type, array): (DataType, ArrayRef) = match column.kind {
+            ColumnKind::Text => (
+                DataType::Utf8,
+                Arc::new(StringArray::from_iter(cells.map(|cell| {
+                    (!cell.is_empty()).then(|| {
+                        cell.iter()
+                            .map(render_leaf)
+                            .collect::<Vec<_>>()
+                            .join(list_delimiter)
+                    })
+                }))),
+            ),
+            ColumnKind::Int => (
+                DataType::Int64,
+                Arc::new(Int64Array::from_iter(
+                    cells.map(|cell| cell.first().and_then(|v| v.as_i64())),
+                )),
+            ),
+            ColumnKind::Float => (
+                DataType::Float64,
+                Arc::new(Float64Array::from_iter(
+                    cells.map(|cell| cell.first().and_then(|v| v.as_f64())),
+                )),
+            ),
+            ColumnKind::List => {
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for cell in cells {
+                    for leaf in cell {
+                        builder.values().append_value(render_leaf(leaf));
+                    }
+                    builder.append(true);
+                }
+                let array = builder.finish();
+                (array.data_type().clone(), Arc::new(array))
+            }
+        };
+        fields.push(Field::new(&column.name, data_type, true));
+        arrays.push(array);
+    }
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+// The IPC stream format needs no seeking, so it can be piped straight into
+// Polars/DataFusion.
+pub fn write_ipc_stream<W: Write>(out: &mut W, table: &Table, list_delimiter: &str) -> Result<()> {
+    let batch = record_batch(table, list_delimiter)?;
+    let mut writer = StreamWriter::try_new(out, &batch.schema())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+pub fn parquet_bytes(table: &Table, list_delimiter: &str) -> Result<Vec<u8>> {
+    use parquet::arrow::ArrowWriter;
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+
+    let batch = record_batch(table, list_delimiter)?;
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut buf = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(buf)
+}
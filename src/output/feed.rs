@@ -0,0 +1,234 @@
+use super::markdown::{genres, title};
+use super::site::escape;
+use crate::config::{SortBy, SortOrder};
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::project::{entry_timestamp, sort_documents};
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, Utc};
+use std::fmt::Write as _;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedKind {
+    Atom,
+    Rss,
+}
+
+pub struct FeedOptions<'a> {
+    pub kind: FeedKind,
+    pub limit: usize,
+    pub title: &'a str,
+    pub link: Option<&'a str>,
+    pub utc_offset: FixedOffset,
+}
+
+struct Entry<'a> {
+    doc: &'a ParsedDocument,
+    title: String,
+    link: Option<String>,
+    id: String,
+    date: Option<DateTime<Utc>>,
+    description: String,
+}
+
+// The newest `limit` releases by post date; releases without a date sort last.
+pub fn write_feed<W: Write>(out: &mut W, bundle: &OutputBundle, opts: &FeedOptions) -> Result<()> {
+    let mut docs = bundle.documents.clone();
+    sort_documents(
+        &mut docs,
+        SortBy::EntryDatetime,
+        SortOrder::Desc,
+        opts.utc_offset,
+    );
+    let entries: Vec<Entry> = docs
+        .iter()
+        .take(opts.limit)
+        .map(|doc| entry(doc, opts.utc_offset))
+        .collect();
+    let link = opts.link.map(str::to_string).or_else(|| {
+        entries
+            .iter()
+            .find_map(|e| e.link.as_deref().and_then(site_root))
+    });
+    let updated = entries
+        .iter()
+        .filter_map(|e| e.date)
+        .max()
+        .unwrap_or_else(Utc::now);
+
+    match opts.kind {
+        FeedKind::Atom => write_atom(out, &entries, opts.title, link.as_deref(), updated),
+        FeedKind::Rss => write_rss(out, &entries, opts.title, link.as_deref(), updated),
+    }
+}
+
+fn entry(doc: &ParsedDocument, offset: FixedOffset) -> Entry<'_> {
+    let link = doc.page.as_ref().and_then(|p| p.canonical_url.clone());
+    let id = match (&doc.doc_id, &link) {
+        (Some(id), _) => format!("urn:uuid:{id}"),
+        (None, Some(url)) => url.clone(),
+        (None, None) => format!("urn:sha256:{}", doc.source.sha256),
+    };
+    Entry {
+        doc,
+        title: title(doc),
+        link,
+        id,
+        date: entry_timestamp(doc, offset).and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        description: description(doc),
+    }
+}
+
+// An HTML fragment; feed readers render it as the entry body.
+fn description(doc: &ParsedDocument) -> String {
+    let release = doc.release.as_ref();
+    let mut items: Vec<(&str, String)> = Vec::new();
+    if let Some(n) = release.and_then(|r| r.release_number) {
+        items.push(("Release", format!("#{n}")));
+    }
+    if let Some(group) = release.and_then(|r| r.group.as_deref()) {
+        items.push(("Group", group.to_string()));
+    }
+    if let Some(size) = release.and_then(|r| r.original_size_raw.as_deref()) {
+        items.push(("Original size", size.to_string()));
+    }
+    if let Some(size) = release.and_then(|r| r.repack_size_raw.as_deref()) {
+        items.push(("Repack size", size.to_string()));
+    }
+    let genres = genres(doc);
+    if !genres.is_empty() {
+        items.push(("Genres", genres.join(", ")));
+    }
+    let mut html = String::from("<ul>");
+    for (label, value) in items {
+        let _ = write!(html, "<li><b>{label}:</b> {}</li>", escape(&value));
+    }
+    html.push_str("</ul>");
+    html
+}
+
+fn write_atom<W: Write>(
+    out: &mut W,
+    entries: &[Entry],
+    title: &str,
+    link: Option<&str>,
+    updated: DateTime<Utc>,
+) -> Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    writeln!(out, r#"<feed xmlns="http://www.w3.org/2005/Atom">"#)?;
+    writeln!(out, "  <title>{}</title>", escape(title))?;
+    let feed_id = link.map_or_else(|| "urn:game-scraper:feed".to_string(), str::to_string);
+    writeln!(out, "  <id>{}</id>", escape(&feed_id))?;
+    if let Some(link) = link {
+        writeln!(out, r#"  <link href="{}"/>"#, escape(link))?;
+    }
+    writeln!(out, "  <updated>{}</updated>", atom_date(updated))?;
+    writeln!(
+        out,
+        r#"  <generator version="{}">{}</generator>"#,
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_NAME")
+    )?;
+    for e in entries {
+        writeln!(out, "  <entry>")?;
+        writeln!(out, "    <title>{}</title>", escape(&e.title))?;
+        writeln!(out, "    <id>{}</id>", escape(&e.id))?;
+        if let Some(link) = &e.link {
+            writeln!(out, r#"    <link href="{}"/>"#, escape(link))?;
+        }
+        // Atom requires an author per entry unless the feed has one.
+        let author = e
+            .doc
+            .post
+            .as_ref()
+            .and_then(|p| p.author.as_deref())
+            .unwrap_or(&e.doc.site);
+        writeln!(out, "    <author><name>{}</name></author>", escape(author))?;
+        writeln!(
+            out,
+            "    <updated>{}</updated>",
+            atom_date(e.date.unwrap_or(updated))
+        )?;
+        if let Some(date) = e.date {
+            writeln!(out, "    <published>{}</published>", atom_date(date))?;
+        }
+        for genre in genres(e.doc) {
+            writeln!(out, r#"    <category term="{}"/>"#, escape(&genre))?;
+        }
+        writeln!(
+            out,
+            r#"    <summary type="html">{}</summary>"#,
+            escape(&e.description)
+        )?;
+        writeln!(out, "  </entry>")?;
+    }
+    writeln!(out, "</feed>")?;
+    Ok(())
+}
+
+fn write_rss<W: Write>(
+    out: &mut W,
+    entries: &[Entry],
+    title: &str,
+    link: Option<&str>,
+    updated: DateTime<Utc>,
+) -> Result<()> {
+    writeln!(out, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+    writeln!(out, r#"<rss version="2.0">"#)?;
+    writeln!(out, "  <channel>")?;
+    writeln!(out, "    <title>{}</title>", escape(title))?;
+    writeln!(out, "    <link>{}</link>", escape(link.unwrap_or_default()))?;
+    writeln!(
+        out,
+        "    <description>{}</description>",
+        escape(&format!("{} newest parsed releases", entries.len()))
+    )?;
+    writeln!(
+        out,
+        "    <lastBuildDate>{}</lastBuildDate>",
+        updated.to_rfc2822()
+    )?;
+    writeln!(
+        out,
+        "    <generator>{} {}</generator>",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    )?;
+    for e in entries {
+        writeln!(out, "    <item>")?;
+        writeln!(out, "      <title>{}</title>", escape(&e.title))?;
+        if let Some(link) = &e.link {
+            writeln!(out, "      <link>{}</link>", escape(link))?;
+        }
+        let permalink = e.link.as_deref() == Some(e.id.as_str());
+        writeln!(
+            out,
+            r#"      <guid isPermaLink="{permalink}">{}</guid>"#,
+            escape(&e.id)
+        )?;
+        if let Some(date) = e.date {
+            writeln!(out, "      <pubDate>{}</pubDate>", date.to_rfc2822())?;
+        }
+        for genre in genres(e.doc) {
+            writeln!(out, "      <category>{}</category>", escape(&genre))?;
+        }
+        writeln!(
+            out,
+            "      <description>{}</description>",
+            escape(&e.description)
+        )?;
+        writeln!(out, "    </item>")?;
+    }
+    writeln!(out, "  </channel>")?;
+    writeln!(out, "</rss>")?;
+    Ok(())
+}
+
+fn atom_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn site_root(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    Some(format!("{}://{}/", url.scheme(), url.host_str()?))
+}
@@ -0,0 +1,123 @@
+use crate::model::{OutputBundle, ParsedDocument, SectionLink};
+use anyhow::Result;
+use std::collections::BTreeSet;
+use std::io::Write;
+
+pub fn write_catalogue<W: Write>(out: &mut W, bundle: &OutputBundle) -> Result<()> {
+    writeln!(out, "# Release catalogue")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} releases from {} inputs ({} failed to parse).",
+        bundle.documents.len(),
+        bundle.stats.input_count,
+        bundle.stats.parsed_err
+    )?;
+
+    for doc in &bundle.documents {
+        writeln!(out)?;
+        writeln!(out, "## {}", escape(&title(doc)))?;
+        writeln!(out)?;
+
+        let release = doc.release.as_ref();
+        let mut rows: Vec<(&str, String)> = Vec::new();
+        if let Some(n) = release.and_then(|r| r.release_number) {
+            rows.push(("Release", format!("#{n}")));
+        }
+        if let Some(group) = release.and_then(|r| r.group.as_deref()) {
+            rows.push(("Group", escape(group)));
+        }
+        if let Some(version) = release.and_then(|r| r.version.as_ref()) {
+            rows.push(("Version", escape(&version.raw)));
+        }
+        if let Some(date) = doc.post.as_ref().and_then(|p| {
+            p.entry_datetime_rfc3339
+                .as_deref()
+                .or(p.entry_datetime.as_deref())
+        }) {
+            rows.push(("Posted", escape(date)));
+        }
+        if let Some(size) = release.and_then(|r| r.original_size_raw.as_deref()) {
+            rows.push(("Original size", escape(size)));
+        }
+        if let Some(size) = release.and_then(|r| r.repack_size_raw.as_deref()) {
+            rows.push(("Repack size", escape(size)));
+        }
+        let genres = genres(doc);
+        if !genres.is_empty() {
+            rows.push(("Genres", escape(&genres.join(", "))));
+        }
+        if let Some(companies) = release
+            .map(|r| r.companies.join(", "))
+            .filter(|c| !c.is_empty())
+        {
+            rows.push(("Companies", escape(&companies)));
+        }
+        let mirrors = mirror_domains(doc);
+        if !mirrors.is_empty() {
+            let hosts: Vec<String> = mirrors.iter().map(|h| format!("`{h}`")).collect();
+            rows.push(("Mirrors", hosts.join(", ")));
+        }
+        if let Some(url) = doc.page.as_ref().and_then(|p| p.canonical_url.as_deref()) {
+            rows.push(("Page", format!("<{url}>")));
+        }
+        rows.push(("Source", format!("`{}`", doc.source.path)));
+
+        for (label, value) in rows {
+            writeln!(out, "- **{label}:** {value}")?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn title(doc: &ParsedDocument) -> String {
+    let release = doc.release.as_ref();
+    let post = doc.post.as_ref();
+    release
+        .and_then(|r| r.game_title_line.clone())
+        .or_else(|| post.and_then(|p| p.entry_title.clone()))
+        .or_else(|| doc.page.as_ref().and_then(|p| p.title.clone()))
+        .unwrap_or_else(|| doc.source.path.clone())
+}
+
+pub(crate) fn genres(doc: &ParsedDocument) -> Vec<String> {
+    match doc.release.as_ref() {
+        Some(r) if !r.genres_tags.is_empty() => r.genres_tags.clone(),
+        _ => doc
+            .post
+            .as_ref()
+            .map(|p| p.genres.clone())
+            .unwrap_or_default(),
+    }
+}
+
+// Links the release can be fetched from: links the host map classified, plus
+// anything under a download/mirror heading.
+pub(crate) fn mirror_links(doc: &ParsedDocument) -> impl Iterator<Item = &SectionLink> {
+    doc.download_sections.iter().flat_map(|section| {
+        let heading = section.heading.to_lowercase();
+        let download_section = heading.contains("download") || heading.contains("mirror");
+        section
+            .links
+            .iter()
+            .filter(move |l| download_section || l.category.is_some())
+    })
+}
+
+pub(crate) fn mirror_domains(doc: &ParsedDocument) -> BTreeSet<String> {
+    mirror_links(doc).filter_map(|l| l.host.clone()).collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,519 @@
+use crate::config::OutputFormat;
+use crate::model::{OutputBundle, ParseError, ParsedDocument, Stats};
+use crate::project::Projection;
+use anyhow::{Result, bail};
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde_json::{Map, Value};
+use std::io::Write;
+
+pub mod append;
+pub mod aria2;
+#[cfg(feature = "arrow")]
+mod columnar;
+pub mod feed;
+mod markdown;
+pub mod nfo;
+pub mod per_input;
+pub mod shard;
+mod sink;
+pub mod site;
+pub mod sqlite;
+mod terminal;
+pub mod torrent;
+mod xlsx;
+
+pub(crate) use markdown::{genres, mirror_domains, mirror_links, title};
+pub(crate) use sink::resolve as resolve_compression;
+pub use sink::{Sink, append_output, open_output};
+pub(crate) use terminal::human_size;
+
+// Column set for tabular formats when no `fields` projection is given. Fixed
+// so the header (and the Parquet/Arrow schema) does not depend on which
+// documents happened to be parsed.
+const TABLE_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("doc_id", ColumnKind::Text),
+    ("source.path", ColumnKind::Text),
+    ("site", ColumnKind::Text),
+    ("page.title", ColumnKind::Text),
+    ("page.canonical_url", ColumnKind::Text),
+    ("post.post_id", ColumnKind::Int),
+    ("post.slug", ColumnKind::Text),
+    ("post.entry_title", ColumnKind::Text),
+    ("post.entry_datetime_rfc3339", ColumnKind::Text),
+    ("post.modified_rfc3339", ColumnKind::Text),
+    ("post.author", ColumnKind::Text),
+    ("post.categories", ColumnKind::List),
+    ("post.genres", ColumnKind::List),
+    ("post.wp_tags", ColumnKind::List),
+    ("post.comments_count", ColumnKind::Int),
+    ("release.release_number", ColumnKind::Int),
+    ("release.game_title_line", ColumnKind::Text),
+    ("release.group", ColumnKind::Text),
+    ("release.version.raw", ColumnKind::Text),
+    ("release.genres_tags", ColumnKind::List),
+    ("release.companies", ColumnKind::List),
+    ("release.developers", ColumnKind::List),
+    ("release.publishers", ColumnKind::List),
+    ("release.languages.iso639_1", ColumnKind::List),
+    ("release.original_size_bytes", ColumnKind::Int),
+    ("release.repack_size_bytes_min", ColumnKind::Int),
+    ("release.repack_size_bytes_max", ColumnKind::Int),
+    ("pricing.store", ColumnKind::Text),
+    ("pricing.current", ColumnKind::Float),
+    ("pricing.currency", ColumnKind::Text),
+    ("content_language.code", ColumnKind::Text),
+    ("torrent_file_links", ColumnKind::List),
+    ("magnet_links", ColumnKind::List),
+    ("infohashes", ColumnKind::List),
+    ("direct_download_links", ColumnKind::List),
+    ("confidence.overall", ColumnKind::Float),
+    ("warnings", ColumnKind::List),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Text,
+    Int,
+    Float,
+    List,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+    // CSV/TSV render every cell as text; xlsx, Parquet and Arrow use the type.
+    pub kind: ColumnKind,
+}
+
+// Each cell holds the scalar leaves found under the column path; arrays along
+// the path are flattened, so list columns can hold any number of leaves.
+pub struct Table {
+    pub columns: Vec<Column>,
+    pub rows: Vec<Vec<Vec<Value>>>,
+}
+
+pub struct WriteOptions<'a> {
+    pub format: OutputFormat,
+    pub pretty_json: bool,
+    pub records: bool,
+    pub list_delimiter: &'a str,
+    pub fields: &'a [String],
+    pub exclude_fields: &'a [String],
+    pub include_nulls: bool,
+}
+
+static BUNDLE_SCHEMA: Lazy<Value> = Lazy::new(schema_value::<OutputBundle>);
+static DOCUMENT_SCHEMA: Lazy<Value> = Lazy::new(schema_value::<ParsedDocument>);
+
+pub fn schema_value<T: JsonSchema>() -> Value {
+    schemars::schema_for!(T).to_value()
+}
+
+// A document as written: `[output] include_nulls` puts back the empty fields
+// the model omits, then the projection applies.
+pub fn document_value(
+    doc: &ParsedDocument,
+    projection: &Projection,
+    include_nulls: bool,
+) -> Result<Value> {
+    let mut value = serde_json::to_value(doc)?;
+    if include_nulls {
+        fill_nulls(&mut value, &DOCUMENT_SCHEMA);
+    }
+    Ok(projection.apply(value))
+}
+
+// Adds every property of `root` (a JSON schema from `schema_value`) missing
+// from `value` as null, [], {}, 0 or "", in schema order, so values of one
+// type all have the same shape.
+pub fn fill_nulls(value: &mut Value, root: &Value) {
+    fill(value, root, root);
+}
+
+fn fill(value: &mut Value, schema: &Value, root: &Value) {
+    let schema = resolve(schema, root);
+    match value {
+        Value::Object(fields) => {
+            let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+                return;
+            };
+            let mut rest = std::mem::take(fields);
+            for (key, property) in properties {
+                match rest.remove(key) {
+                    Some(mut field) => {
+                        fill(&mut field, property, root);
+                        fields.insert(key.clone(), field);
+                    }
+                    None => {
+                        if let Some(empty) = empty_value(property) {
+                            fields.insert(key.clone(), empty);
+                        }
+                    }
+                }
+            }
+            fields.extend(rest);
+        }
+        Value::Array(items) => {
+            if let Some(item) = schema.get("items") {
+                for value in items {
+                    fill(value, item, root);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// `$ref`s point into the root's `$defs`; `Option<T>` of a struct is an
+// `anyOf` of the struct and null.
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix("#/$defs/"))
+    {
+        return &root["$defs"][name];
+    }
+    if let Some(branch) = schema
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .and_then(|branches| branches.iter().find(|b| b.get("$ref").is_some()))
+    {
+        return resolve(branch, root);
+    }
+    schema
+}
+
+fn empty_value(property: &Value) -> Option<Value> {
+    let types: Vec<&str> = match property.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => property
+            .get("anyOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|b| b.get("type").and_then(Value::as_str))
+            .collect(),
+    };
+    if types.contains(&"null") {
+        return Some(Value::Null);
+    }
+    match types.first() {
+        Some(&"array") => Some(Value::Array(Vec::new())),
+        Some(&"object") => Some(Value::Object(Map::new())),
+        Some(&"integer") => Some(Value::from(0)),
+        Some(&"string") => Some(Value::String(String::new())),
+        _ => None,
+    }
+}
+
+pub fn write_output<W: Write>(
+    out: &mut W,
+    bundle: &OutputBundle,
+    projection: &Projection,
+    opts: &WriteOptions,
+) -> Result<()> {
+    match opts.format {
+        OutputFormat::Json => write_bundle(out, bundle, projection, opts),
+        OutputFormat::Ndjson => write_ndjson(out, bundle, projection, opts.include_nulls),
+        OutputFormat::Csv => write_table(out, bundle, opts, b','),
+        OutputFormat::Tsv => write_table(out, bundle, opts, b'\t'),
+        OutputFormat::Markdown => markdown::write_catalogue(out, bundle),
+        OutputFormat::Table => terminal::write_quick_view(out, bundle),
+        OutputFormat::Msgpack | OutputFormat::Cbor => write_binary(out, bundle, projection, opts),
+        OutputFormat::Xlsx => {
+            let table = build_table(bundle, opts)?;
+            out.write_all(&xlsx::workbook_bytes(&table, bundle, opts.list_delimiter)?)?;
+            Ok(())
+        }
+        OutputFormat::Parquet => write_parquet(out, bundle, opts),
+        OutputFormat::Arrow => write_arrow(out, bundle, opts),
+        OutputFormat::Sqlite => bail!("sqlite output needs a database path (--output <PATH>)"),
+    }
+}
+
+fn write_bundle<W: Write>(
+    out: &mut W,
+    bundle: &OutputBundle,
+    projection: &Projection,
+    opts: &WriteOptions,
+) -> Result<()> {
+    let value = bundle_value(bundle, projection, opts.include_nulls)?;
+    let json = if opts.pretty_json {
+        serde_json::to_string_pretty(&value)?
+    } else {
+        serde_json::to_string(&value)?
+    };
+    out.write_all(json.as_bytes())?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+fn bundle_value(
+    bundle: &OutputBundle,
+    projection: &Projection,
+    include_nulls: bool,
+) -> Result<Value> {
+    let mut value = serde_json::to_value(bundle)?;
+    if include_nulls {
+        fill_nulls(&mut value, &BUNDLE_SCHEMA);
+    }
+    if !projection.is_empty()
+        && let Some(Value::Array(docs)) = value.get_mut("documents")
+    {
+        for doc in docs.iter_mut() {
+            *doc = projection.apply(doc.take());
+        }
+    }
+    Ok(value)
+}
+
+fn write_ndjson<W: Write>(
+    out: &mut W,
+    bundle: &OutputBundle,
+    projection: &Projection,
+    include_nulls: bool,
+) -> Result<()> {
+    for record in records(bundle, projection, include_nulls)? {
+        write_ndjson_record(out, &record)?;
+    }
+    Ok(())
+}
+
+pub fn write_ndjson_record<W: Write + ?Sized>(out: &mut W, record: &Value) -> Result<()> {
+    serde_json::to_writer(&mut *out, record)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+// `--errors-out`: bare ParseError objects, one per line, so the main output
+// carries documents only.
+pub fn write_error_stream(
+    path: &std::path::Path,
+    errors: &[ParseError],
+    compression: crate::config::Compression,
+    atomic: bool,
+) -> Result<()> {
+    let mut out = open_output(Some(path), compression, atomic)?;
+    for err in errors {
+        write_ndjson_record(&mut out, &serde_json::to_value(err)?)?;
+    }
+    out.finish()
+}
+
+pub fn error_record(err: &ParseError) -> Value {
+    serde_json::json!({
+        "type": "error",
+        "data": err
+    })
+}
+
+pub fn summary_record(schema_version: u32, stats: &Stats) -> Value {
+    serde_json::json!({
+        "type": "summary",
+        "schema_version": schema_version,
+        "data": stats
+    })
+}
+
+// The NDJSON record sequence: one projected document per record, then one
+// `error` record per failed input, then a closing `summary` record.
+fn records(
+    bundle: &OutputBundle,
+    projection: &Projection,
+    include_nulls: bool,
+) -> Result<Vec<Value>> {
+    let mut records = Vec::with_capacity(bundle.documents.len() + bundle.errors.len() + 1);
+    for doc in &bundle.documents {
+        records.push(document_value(doc, projection, include_nulls)?);
+    }
+    records.extend(bundle.errors.iter().map(error_record));
+    records.push(summary_record(bundle.schema_version, &bundle.stats));
+    Ok(records)
+}
+
+// MessagePack and CBOR values are self-delimiting, so the record stream is
+// just the encoded records back to back.
+fn write_binary<W: Write>(
+    out: &mut W,
+    bundle: &OutputBundle,
+    projection: &Projection,
+    opts: &WriteOptions,
+) -> Result<()> {
+    let values = if opts.records {
+        records(bundle, projection, opts.include_nulls)?
+    } else {
+        vec![bundle_value(bundle, projection, opts.include_nulls)?]
+    };
+    for value in &values {
+        match opts.format {
+            OutputFormat::Cbor => ciborium::into_writer(value, &mut *out)?,
+            _ => rmp_serde::encode::write_named(out, value)?,
+        }
+    }
+    Ok(())
+}
+
+// Parquet and Arrow writers are behind cargo features; callers check before
+// parsing so a build without them fails fast.
+pub fn check_format_supported(format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Parquet if !cfg!(feature = "parquet") => {
+            bail!("parquet output needs a build with `--features parquet`")
+        }
+        OutputFormat::Arrow if !cfg!(feature = "arrow") => {
+            bail!("arrow output needs a build with `--features arrow`")
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "parquet")]
+fn write_parquet<W: Write>(out: &mut W, bundle: &OutputBundle, opts: &WriteOptions) -> Result<()> {
+    let table = build_table(bundle, opts)?;
+    out.write_all(&columnar::parquet_bytes(&table, opts.list_delimiter)?)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_parquet<W: Write>(_: &mut W, _: &OutputBundle, _: &WriteOptions) -> Result<()> {
+    check_format_supported(OutputFormat::Parquet)
+}
+
+#[cfg(feature = "arrow")]
+fn write_arrow<W: Write>(out: &mut W, bundle: &OutputBundle, opts: &WriteOptions) -> Result<()> {
+    let table = build_table(bundle, opts)?;
+    columnar::write_ipc_stream(out, &table, opts.list_delimiter)
+}
+
+#[cfg(not(feature = "arrow"))]
+fn write_arrow<W: Write>(_: &mut W, _: &OutputBundle, _: &WriteOptions) -> Result<()> {
+    check_format_supported(OutputFormat::Arrow)
+}
+
+// One row per document; list cells are joined with `list_delimiter` and
+// leftover objects are written as compact JSON.
+fn write_table<W: Write>(
+    out: &mut W,
+    bundle: &OutputBundle,
+    opts: &WriteOptions,
+    delimiter: u8,
+) -> Result<()> {
+    let table = build_table(bundle, opts)?;
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(out);
+    writer.write_record(table.columns.iter().map(|c| c.name.as_str()))?;
+    for row in &table.rows {
+        writer.write_record(row.iter().map(|cell| {
+            cell.iter()
+                .map(render_leaf)
+                .collect::<Vec<_>>()
+                .join(opts.list_delimiter)
+        }))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// Columns are the `fields` projection (or TABLE_COLUMNS) minus
+// `exclude_fields`. Types of user-chosen columns are inferred from the data.
+pub fn build_table(bundle: &OutputBundle, opts: &WriteOptions) -> Result<Table> {
+    let paths: Vec<(String, Option<ColumnKind>)> = if opts.fields.is_empty() {
+        TABLE_COLUMNS
+            .iter()
+            .map(|&(name, kind)| (name.to_string(), Some(kind)))
+            .collect()
+    } else {
+        opts.fields
+            .iter()
+            .map(|f| (f.trim().to_string(), None))
+            .collect()
+    };
+    let paths: Vec<(String, Option<ColumnKind>)> = paths
+        .into_iter()
+        .filter(|(name, _)| {
+            !name.is_empty() && !opts.exclude_fields.iter().any(|e| e.trim() == name)
+        })
+        .collect();
+
+    let docs = bundle
+        .documents
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    let mut rows: Vec<Vec<Vec<Value>>> = vec![Vec::with_capacity(paths.len()); docs.len()];
+    let mut columns = Vec::with_capacity(paths.len());
+    for (name, kind) in paths {
+        let path: Vec<&str> = name.split('.').collect();
+        let mut listy = false;
+        for (doc, row) in docs.iter().zip(rows.iter_mut()) {
+            let mut cell = Vec::new();
+            listy |= collect_leaves(doc, &path, &mut cell);
+            row.push(cell);
+        }
+        let kind = kind.unwrap_or_else(|| {
+            let column = rows
+                .iter()
+                .map(|row| row.last().map(Vec::as_slice).unwrap_or_default());
+            infer_kind(column, listy)
+        });
+        columns.push(Column { name, kind });
+    }
+    Ok(Table { columns, rows })
+}
+
+fn infer_kind<'a>(cells: impl Iterator<Item = &'a [Value]> + Clone, listy: bool) -> ColumnKind {
+    if listy {
+        return ColumnKind::List;
+    }
+    if cells.clone().all(<[Value]>::is_empty) || !cells.clone().flatten().all(Value::is_number) {
+        ColumnKind::Text
+    } else if cells.flatten().all(Value::is_i64) {
+        ColumnKind::Int
+    } else {
+        ColumnKind::Float
+    }
+}
+
+// Returns true when the path crossed an array, i.e. the column is a list.
+fn collect_leaves(value: &Value, path: &[&str], out: &mut Vec<Value>) -> bool {
+    match (value, path.split_first()) {
+        (Value::Array(items), _) => {
+            for item in items {
+                collect_leaves(item, path, out);
+            }
+            true
+        }
+        (Value::Object(fields), Some((head, rest))) => fields
+            .get(*head)
+            .is_some_and(|child| collect_leaves(child, rest, out)),
+        (_, Some(_)) | (Value::Null, None) => false,
+        (leaf, None) => {
+            out.push(leaf.clone());
+            false
+        }
+    }
+}
+
+// `parse --get`: one field as a line of plain text. Paths cross arrays like
+// table columns do; several values are joined with the list delimiter.
+pub fn field_text(doc: &Value, path: &str, list_delimiter: &str) -> String {
+    let path: Vec<&str> = path.split('.').collect();
+    let mut leaves = Vec::new();
+    collect_leaves(doc, &path, &mut leaves);
+    leaves
+        .iter()
+        .map(render_leaf)
+        .collect::<Vec<_>>()
+        .join(list_delimiter)
+}
+
+pub fn render_leaf(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,125 @@
+use super::markdown::{genres, title};
+use super::site::{escape, unique_file_name};
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::project::entry_timestamp;
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+// One Kodi-style `.nfo` per release. Media centers only read NFO roots for
+// library types they know, so the root element is configurable: `movie` gets
+// releases into a Jellyfin/Kodi movie or home-video library.
+pub fn write_nfo_files(
+    dir: &Path,
+    bundle: &OutputBundle,
+    root: &str,
+    offset: FixedOffset,
+) -> Result<usize> {
+    std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    let mut used = BTreeSet::new();
+    for doc in &bundle.documents {
+        let file = unique_file_name(doc, "nfo", &mut used);
+        std::fs::write(dir.join(&file), render(doc, root, offset))
+            .with_context(|| format!("write {file}"))?;
+    }
+    Ok(used.len())
+}
+
+fn render(doc: &ParsedDocument, root: &str, offset: FixedOffset) -> String {
+    let release = doc.release.as_ref();
+    let mut xml = String::new();
+    let _ = writeln!(
+        xml,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>"
+    );
+    let _ = writeln!(xml, "<{root}>");
+    let mut field = |name: &str, value: &str| {
+        let _ = writeln!(xml, "  <{name}>{}</{name}>", escape(value));
+    };
+
+    let title = title(doc);
+    field("title", &title);
+    if let Some(latin) = release.and_then(|r| r.game_title_line_latin.as_deref())
+        && latin != title
+    {
+        field("originaltitle", latin);
+    }
+    if let Some(slug) = release.and_then(|r| r.title_slug.as_deref()) {
+        field("sorttitle", slug);
+    }
+    if let Some(plot) = plot(doc) {
+        field("plot", &plot);
+    }
+    if let Some(date) = entry_timestamp(doc, offset).and_then(|ts| DateTime::from_timestamp(ts, 0))
+    {
+        field("premiered", &date.format("%Y-%m-%d").to_string());
+        field("releasedate", &date.format("%Y-%m-%d").to_string());
+        field("year", &date.format("%Y").to_string());
+    }
+    for genre in genres(doc) {
+        field("genre", &genre);
+    }
+    if let Some(r) = release {
+        for studio in r.developers.iter().chain(&r.publishers) {
+            field("studio", studio);
+        }
+        if r.developers.is_empty() && r.publishers.is_empty() {
+            for company in &r.companies {
+                field("studio", company);
+            }
+        }
+        if let Some(rating) = r
+            .age_ratings
+            .get("esrb")
+            .or_else(|| r.age_ratings.values().next())
+        {
+            field("mpaa", rating);
+        }
+        if let Some(version) = &r.version {
+            field("tagline", &version.raw);
+        }
+    }
+    if let Some(cover) = doc.images.first() {
+        let _ = writeln!(
+            xml,
+            "  <thumb aspect=\"poster\">{}</thumb>",
+            escape(&cover.src)
+        );
+    }
+    if let Some(n) = release.and_then(|r| r.release_number) {
+        let _ = writeln!(xml, "  <uniqueid type=\"release\">{n}</uniqueid>");
+    }
+    for appid in release
+        .map(|r| r.steam_appids.as_slice())
+        .unwrap_or_default()
+    {
+        let _ = writeln!(xml, "  <uniqueid type=\"steam\">{appid}</uniqueid>");
+    }
+    if let Some(id) = &doc.doc_id {
+        let _ = writeln!(
+            xml,
+            "  <uniqueid type=\"game-scraper\" default=\"true\">{}</uniqueid>",
+            escape(id)
+        );
+    }
+    let _ = writeln!(xml, "</{root}>");
+    xml
+}
+
+// The site's description spoiler when present, else the page's meta
+// description.
+fn plot(doc: &ParsedDocument) -> Option<String> {
+    doc.spoiler_sections
+        .iter()
+        .find(|s| s.title.to_lowercase().contains("description"))
+        .map(|s| s.text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .or_else(|| {
+            let meta = &doc.page.as_ref()?.meta;
+            meta.get("description")
+                .or_else(|| meta.get("og:description"))
+                .cloned()
+        })
+}
@@ -0,0 +1,157 @@
+use super::open_output;
+use crate::config::Compression;
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::project::Projection;
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const PLACEHOLDERS: &[&str] = &[
+    "stem",
+    "post_id",
+    "doc_id",
+    "release_number",
+    "slug",
+    "sha256",
+];
+
+pub struct PerInputOptions<'a> {
+    pub dir: &'a Path,
+    pub template: &'a str,
+    pub inputs: &'a [PathBuf],
+    pub pretty_json: bool,
+    pub include_nulls: bool,
+    pub compress: Compression,
+    pub atomic: bool,
+}
+
+pub fn check_template(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            bail!("unclosed `{{` in output template `{template}`");
+        };
+        let name = &rest[start + 1..start + len];
+        if !PLACEHOLDERS.contains(&name) {
+            bail!(
+                "unknown placeholder `{{{name}}}` in output template (expected one of {})",
+                PLACEHOLDERS.join(", ")
+            );
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+// One JSON document per input, placed under the input's directory relative to
+// the input root it was found in, so the output tree mirrors the source tree.
+pub fn write_per_input(
+    bundle: &OutputBundle,
+    projection: &Projection,
+    opts: &PerInputOptions,
+) -> Result<usize> {
+    let mut used = BTreeSet::new();
+    for doc in &bundle.documents {
+        let source = Path::new(&doc.source.path);
+        let mut path = opts
+            .dir
+            .join(relative_parent(source, opts.inputs))
+            .join(render(opts.template, doc));
+        if !used.insert(path.clone()) {
+            let unique = (2..)
+                .map(|n| with_suffix(&path, n))
+                .find(|p| !used.contains(p))
+                .unwrap_or_default();
+            warn!(
+                path = %path.display(),
+                renamed = %unique.display(),
+                "output template produced a duplicate path"
+            );
+            used.insert(unique.clone());
+            path = unique;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("create {}", parent.display()))?;
+        }
+
+        let value = super::document_value(doc, projection, opts.include_nulls)?;
+        let mut out = open_output(Some(&path), opts.compress, opts.atomic)?;
+        if opts.pretty_json {
+            serde_json::to_writer_pretty(&mut out, &value)?;
+        } else {
+            serde_json::to_writer(&mut out, &value)?;
+        }
+        out.write_all(b"\n")?;
+        out.finish()
+            .with_context(|| format!("write {}", path.display()))?;
+    }
+    Ok(used.len())
+}
+
+fn relative_parent(source: &Path, inputs: &[PathBuf]) -> PathBuf {
+    inputs
+        .iter()
+        .filter(|root| root.is_dir())
+        .filter_map(|root| source.strip_prefix(root).ok())
+        .min_by_key(|rel| rel.components().count())
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+// Placeholders without a value fall back to the input file stem.
+pub(super) fn render(template: &str, doc: &ParsedDocument) -> String {
+    let stem = Path::new(&doc.source.path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| doc.source.sha256.clone());
+    let post = doc.post.as_ref();
+    let release = doc.release.as_ref();
+    let mut out = template.to_string();
+    for name in PLACEHOLDERS {
+        let key = format!("{{{name}}}");
+        if !out.contains(&key) {
+            continue;
+        }
+        let value = match *name {
+            "stem" => Some(stem.clone()),
+            "post_id" => post.and_then(|p| p.post_id).map(|n| n.to_string()),
+            "doc_id" => doc.doc_id.clone(),
+            "release_number" => release
+                .and_then(|r| r.release_number)
+                .map(|n| n.to_string()),
+            "slug" => post.and_then(|p| p.slug.clone()),
+            _ => Some(doc.source.sha256.clone()),
+        };
+        out = out.replace(&key, &sanitize(&value.unwrap_or_else(|| stem.clone())));
+    }
+    out
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | ':' | '\0') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn with_suffix(path: &Path, n: usize) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (stem, ext) = match name.find('.') {
+        Some(i) => name.split_at(i),
+        None => (name.as_str(), ""),
+    };
+    path.with_file_name(format!("{stem}-{n}{ext}"))
+}
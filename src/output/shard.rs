@@ -0,0 +1,213 @@
+use super::{open_output, sink::Sink, write_ndjson_record};
+use crate::config::Compression;
+use crate::model::{ParseError, Stats};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::info;
+
+// `--shard-size`: a bare number is a record count, a number with a byte unit
+// (`64MB`, `1GiB`; binary multiples, as for release sizes) caps the
+// uncompressed bytes per shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardSize {
+    Records(u64),
+    Bytes(u64),
+}
+
+impl FromStr for ShardSize {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let raw = raw.trim();
+        let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+        let (number, unit) = raw.split_at(split);
+        let n: u64 = number
+            .parse()
+            .map_err(|_| format!("invalid shard size `{raw}`"))?;
+        let bytes = |scale: u64| {
+            n.checked_mul(scale)
+                .map(Self::Bytes)
+                .ok_or_else(|| format!("shard size `{raw}` is too large"))
+        };
+        let size = match unit.trim().to_ascii_lowercase().as_str() {
+            "" => Self::Records(n),
+            "b" => Self::Bytes(n),
+            "k" | "kb" | "kib" => bytes(1 << 10)?,
+            "m" | "mb" | "mib" => bytes(1 << 20)?,
+            "g" | "gb" | "gib" => bytes(1 << 30)?,
+            other => return Err(format!("unknown size unit `{other}` in `{raw}`")),
+        };
+        match size {
+            Self::Records(0) | Self::Bytes(0) => Err("shard size must be positive".into()),
+            size => Ok(size),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ShardEntry {
+    path: String,
+    records: u64,
+    bytes: u64,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    schema_version: u32,
+    shard_size: ShardSize,
+    shards: &'a [ShardEntry],
+    errors: &'a [ParseError],
+    stats: &'a Stats,
+}
+
+// Document records split across `out-0001.ndjson`, `out-0002.ndjson`, … next
+// to `--output`. Errors and the summary go to `out.manifest.json` instead of
+// the shards, so every shard holds only documents and can be loaded on its own.
+pub struct ShardedNdjson {
+    base: PathBuf,
+    size: ShardSize,
+    compression: Compression,
+    atomic: bool,
+    current: Option<Sink>,
+    shards: Vec<ShardEntry>,
+}
+
+impl ShardedNdjson {
+    pub fn new(base: &Path, size: ShardSize, compression: Compression, atomic: bool) -> Self {
+        Self {
+            base: base.to_path_buf(),
+            size,
+            compression,
+            atomic,
+            current: None,
+            shards: Vec::new(),
+        }
+    }
+
+    pub fn write_record(&mut self, record: &Value) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+        let full = self.shards.last().is_some_and(|shard| match self.size {
+            ShardSize::Records(max) => shard.records >= max,
+            ShardSize::Bytes(max) => shard.records > 0 && shard.bytes + len > max,
+        });
+        if self.current.is_none() || full {
+            self.rotate()?;
+        }
+        let out = self.current.as_mut().expect("shard opened by rotate");
+        out.write_all(&line)?;
+        let shard = self.shards.last_mut().expect("shard opened by rotate");
+        shard.records += 1;
+        shard.bytes += len;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        if let Some(out) = self.current.take() {
+            out.finish()?;
+        }
+        let path = with_suffix(&self.base, &format!("-{:04}", self.shards.len() + 1));
+        self.current = Some(open_output(Some(&path), self.compression, self.atomic)?);
+        self.shards.push(ShardEntry {
+            path: file_name(&path),
+            records: 0,
+            bytes: 0,
+        });
+        Ok(())
+    }
+
+    pub fn finish(
+        mut self,
+        schema_version: u32,
+        errors: &[ParseError],
+        stats: &Stats,
+    ) -> Result<PathBuf> {
+        if let Some(out) = self.current.take() {
+            out.finish()?;
+        }
+        let manifest = Manifest {
+            schema_version,
+            shard_size: self.size,
+            shards: &self.shards,
+            errors,
+            stats,
+        };
+        let path = self.manifest_path();
+        let mut out = open_output(Some(&path), Compression::None, self.atomic)?;
+        serde_json::to_writer_pretty(&mut out, &manifest)?;
+        out.write_all(b"\n")?;
+        out.finish()
+            .with_context(|| format!("write {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        let name = file_name(&self.base);
+        let stem = name.split('.').next().unwrap_or_default();
+        self.base.with_file_name(format!("{stem}.manifest.json"))
+    }
+}
+
+// Either a single NDJSON stream or a sharded one; both take document records
+// and close with the parse errors and run stats. An appended stream gets
+// documents only, so the error and summary records of earlier runs are not
+// followed by another set in the middle of the file.
+pub enum NdjsonOutput {
+    Single(Sink),
+    Appended(Sink),
+    Sharded(ShardedNdjson),
+}
+
+impl NdjsonOutput {
+    pub fn write_record(&mut self, record: &Value) -> Result<()> {
+        match self {
+            Self::Single(out) | Self::Appended(out) => write_ndjson_record(out, record),
+            Self::Sharded(shards) => shards.write_record(record),
+        }
+    }
+
+    pub fn finish(self, schema_version: u32, errors: &[ParseError], stats: &Stats) -> Result<()> {
+        match self {
+            Self::Single(mut out) => {
+                for err in errors {
+                    write_ndjson_record(&mut out, &super::error_record(err))?;
+                }
+                write_ndjson_record(&mut out, &super::summary_record(schema_version, stats))?;
+                out.finish()
+            }
+            Self::Appended(out) => out.finish(),
+            Self::Sharded(shards) => {
+                let count = shards.shard_count();
+                let manifest = shards.finish(schema_version, errors, stats)?;
+                info!(manifest = %manifest.display(), shards = count, "wrote shards");
+                Ok(())
+            }
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// Inserts before the first extension so `out.ndjson.gz` keeps its codec suffix.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let name = file_name(path);
+    let (stem, ext) = match name.find('.') {
+        Some(i) => name.split_at(i),
+        None => (name.as_str(), ""),
+    };
+    path.with_file_name(format!("{stem}{suffix}{ext}"))
+}
@@ -0,0 +1,157 @@
+use crate::config::Compression;
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+type Inner = BufWriter<Box<dyn Write>>;
+
+// Where `parse` output goes. Compressed sinks must be finished explicitly so
+// the trailer is written and errors surface instead of being lost on drop.
+pub struct Sink {
+    encoder: Encoder,
+    staged: Option<Staged>,
+}
+
+enum Encoder {
+    Plain(Inner),
+    Gzip(GzEncoder<Inner>),
+    Zstd(zstd::Encoder<'static, Inner>),
+}
+
+// Atomic writes go to a temporary file next to the destination, which is
+// renamed over it in `finish`; if the run dies first, the old file is intact
+// and the temporary file is removed on drop.
+struct Staged {
+    file: File,
+    tmp: PathBuf,
+    dest: PathBuf,
+    committed: bool,
+}
+
+impl Drop for Staged {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.tmp);
+        }
+    }
+}
+
+pub fn open_output(path: Option<&Path>, compression: Compression, atomic: bool) -> Result<Sink> {
+    let Some(path) = path else {
+        return wrap(Box::new(std::io::stdout().lock()), compression, None);
+    };
+    // Pipes and device files cannot be replaced by a rename.
+    let special = std::fs::metadata(path).is_ok_and(|m| !m.is_file());
+    if !atomic || special {
+        let file =
+            File::create(path).with_context(|| format!("create output {}", path.display()))?;
+        return wrap(Box::new(file), resolve(compression, Some(path)), None);
+    }
+
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{name}.tmp-{}", std::process::id()));
+    let file = File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?;
+    let staged = Staged {
+        file: file.try_clone()?,
+        tmp,
+        dest: path.to_path_buf(),
+        committed: false,
+    };
+    wrap(
+        Box::new(file),
+        resolve(compression, Some(path)),
+        Some(staged),
+    )
+}
+
+// Appending to a compressed file starts a new gzip member / zstd frame; both
+// formats decode concatenated streams as one.
+pub fn append_output(path: &Path, compression: Compression) -> Result<Sink> {
+    let file = File::options()
+        .append(true)
+        .create(true)
+        .open(path)
+        .with_context(|| format!("open {} for append", path.display()))?;
+    wrap(Box::new(file), resolve(compression, Some(path)), None)
+}
+
+fn wrap(inner: Box<dyn Write>, compression: Compression, staged: Option<Staged>) -> Result<Sink> {
+    let inner = BufWriter::new(inner);
+    let encoder = match compression {
+        Compression::Gzip => Encoder::Gzip(GzEncoder::new(inner, flate2::Compression::default())),
+        Compression::Zstd => Encoder::Zstd(
+            zstd::Encoder::new(inner, zstd::DEFAULT_COMPRESSION_LEVEL)
+                .context("start zstd encoder")?,
+        ),
+        Compression::Auto | Compression::None => Encoder::Plain(inner),
+    };
+    Ok(Sink { encoder, staged })
+}
+
+// `auto` picks the codec from the output extension; stdout stays plain.
+pub(crate) fn resolve(compression: Compression, path: Option<&Path>) -> Compression {
+    if compression != Compression::Auto {
+        return compression;
+    }
+    match path
+        .and_then(|p| p.extension())
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("gz") => Compression::Gzip,
+        Some("zst") | Some("zstd") => Compression::Zstd,
+        _ => Compression::None,
+    }
+}
+
+impl Sink {
+    pub fn finish(self) -> Result<()> {
+        let mut inner = match self.encoder {
+            Encoder::Plain(inner) => inner,
+            Encoder::Gzip(encoder) => encoder.finish().context("finish gzip stream")?,
+            Encoder::Zstd(encoder) => encoder.finish().context("finish zstd stream")?,
+        };
+        inner.flush().context("flush output")?;
+        drop(inner);
+
+        if let Some(mut staged) = self.staged {
+            staged
+                .file
+                .sync_all()
+                .with_context(|| format!("sync {}", staged.tmp.display()))?;
+            std::fs::rename(&staged.tmp, &staged.dest).with_context(|| {
+                format!(
+                    "rename {} to {}",
+                    staged.tmp.display(),
+                    staged.dest.display()
+                )
+            })?;
+            staged.committed = true;
+        }
+        Ok(())
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.encoder {
+            Encoder::Plain(w) => w.write(buf),
+            Encoder::Gzip(w) => w.write(buf),
+            Encoder::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.encoder {
+            Encoder::Plain(w) => w.flush(),
+            Encoder::Gzip(w) => w.flush(),
+            Encoder::Zstd(w) => w.flush(),
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use super::markdown::{genres, mirror_domains, title};
+use crate::model::{OutputBundle, ParsedDocument};
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; padding: 0 1rem; color: #222; }
+a { color: #0b5cad; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: .35rem .5rem; border-bottom: 1px solid #ddd; vertical-align: top; }
+th { background: #f4f4f4; }
+.controls { display: flex; gap: .5rem; margin: 1rem 0; }
+.controls input { flex: 1; padding: .4rem; }
+.muted { color: #777; }
+dl { display: grid; grid-template-columns: max-content auto; gap: .25rem 1rem; }
+dt { font-weight: 600; }
+code { word-break: break-all; }
+img { max-width: 100%; }
+"#;
+
+// Hides index rows that do not match the search text and genre selection;
+// without JavaScript the full table is still readable.
+const SCRIPT: &str = r#"
+const q = document.getElementById('q');
+const genre = document.getElementById('genre');
+const count = document.getElementById('count');
+function apply() {
+  const needle = q.value.trim().toLowerCase();
+  const g = genre.value;
+  let shown = 0;
+  for (const row of document.querySelectorAll('tbody tr')) {
+    const ok = (!needle || row.dataset.search.includes(needle))
+      && (!g || row.dataset.genres.includes('|' + g + '|'));
+    row.hidden = !ok;
+    if (ok) shown++;
+  }
+  count.textContent = shown;
+}
+q.addEventListener('input', apply);
+genre.addEventListener('change', apply);
+"#;
+
+pub fn write_site(dir: &Path, bundle: &OutputBundle) -> Result<()> {
+    let pages_dir = dir.join("releases");
+    std::fs::create_dir_all(&pages_dir)
+        .with_context(|| format!("create {}", pages_dir.display()))?;
+
+    let mut used = BTreeSet::new();
+    let mut rows = String::new();
+    let mut all_genres = BTreeSet::new();
+    for doc in &bundle.documents {
+        let file = unique_file_name(doc, "html", &mut used);
+        let page = render_release(doc);
+        std::fs::write(pages_dir.join(&file), page)
+            .with_context(|| format!("write releases/{file}"))?;
+
+        let release = doc.release.as_ref();
+        let genres = genres(doc);
+        all_genres.extend(genres.iter().cloned());
+        let title = title(doc);
+        let number = release
+            .and_then(|r| r.release_number)
+            .map(|n| n.to_string())
+            .unwrap_or_default();
+        let group = release.and_then(|r| r.group.clone()).unwrap_or_default();
+        let size = release
+            .and_then(|r| r.repack_size_raw.clone())
+            .unwrap_or_default();
+        let posted = posted(doc).unwrap_or_default();
+        let search = format!("{title} {group} {} {number}", genres.join(" ")).to_lowercase();
+        let _ = writeln!(
+            rows,
+            "<tr data-search=\"{}\" data-genres=\"|{}|\"><td>{}</td><td><a href=\"releases/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&search),
+            escape(&genres.join("|")),
+            escape(&number),
+            escape(&file),
+            escape(&title),
+            escape(&group),
+            escape(&genres.join(", ")),
+            escape(&size),
+            escape(posted.get(..10).unwrap_or(posted.as_str())),
+        );
+    }
+
+    let options: String = all_genres
+        .iter()
+        .map(|g| format!("<option>{}</option>", escape(g)))
+        .collect();
+    let index = format!(
+        "<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Release catalogue</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>Release catalogue</h1>\n<p class=\"muted\"><span id=\"count\">{count}</span> of {count} releases shown &middot; {inputs} inputs, {errors} failed to parse</p>\n<div class=\"controls\"><input id=\"q\" type=\"search\" placeholder=\"Search title, group, genre&hellip;\"><select id=\"genre\"><option value=\"\">All genres</option>{options}</select></div>\n<table>\n<thead><tr><th>#</th><th>Title</th><th>Group</th><th>Genres</th><th>Size</th><th>Posted</th></tr></thead>\n<tbody>\n{rows}</tbody>\n</table>\n<script>{SCRIPT}</script>\n</body>\n</html>\n",
+        count = bundle.documents.len(),
+        inputs = bundle.stats.input_count,
+        errors = bundle.stats.parsed_err,
+    );
+    std::fs::write(dir.join("index.html"), index).context("write index.html")?;
+    Ok(())
+}
+
+fn render_release(doc: &ParsedDocument) -> String {
+    let release = doc.release.as_ref();
+    let title = title(doc);
+    let mut body = String::new();
+    let _ = writeln!(
+        body,
+        "<p><a href=\"../index.html\">&larr; All releases</a></p>"
+    );
+    let _ = writeln!(body, "<h1>{}</h1>", escape(&title));
+
+    let mut facts: Vec<(&str, String)> = Vec::new();
+    if let Some(n) = release.and_then(|r| r.release_number) {
+        facts.push(("Release", format!("#{n}")));
+    }
+    if let Some(group) = release.and_then(|r| r.group.as_deref()) {
+        facts.push(("Group", escape(group)));
+    }
+    if let Some(version) = release.and_then(|r| r.version.as_ref()) {
+        facts.push(("Version", escape(&version.raw)));
+    }
+    if let Some(date) = posted(doc) {
+        facts.push(("Posted", escape(&date)));
+    }
+    if let Some(size) = release.and_then(|r| r.original_size_raw.as_deref()) {
+        facts.push(("Original size", escape(size)));
+    }
+    if let Some(size) = release.and_then(|r| r.repack_size_raw.as_deref()) {
+        facts.push(("Repack size", escape(size)));
+    }
+    let genres = genres(doc);
+    if !genres.is_empty() {
+        facts.push(("Genres", escape(&genres.join(", "))));
+    }
+    if let Some(r) = release.filter(|r| !r.companies.is_empty()) {
+        facts.push(("Companies", escape(&r.companies.join(", "))));
+    }
+    if let Some(r) = release.filter(|r| !r.languages.is_empty()) {
+        let names: Vec<&str> = r.languages.iter().map(|l| l.name.as_str()).collect();
+        facts.push(("Languages", escape(&names.join(", "))));
+    }
+    let mirrors = mirror_domains(doc);
+    if !mirrors.is_empty() {
+        let hosts: Vec<&str> = mirrors.iter().map(String::as_str).collect();
+        facts.push(("Mirrors", escape(&hosts.join(", "))));
+    }
+    if let Some(url) = doc.page.as_ref().and_then(|p| p.canonical_url.as_deref()) {
+        facts.push(("Page", link(url, url)));
+    }
+    facts.push((
+        "Source",
+        format!("<code>{}</code>", escape(&doc.source.path)),
+    ));
+    let _ = writeln!(body, "<dl>");
+    for (label, value) in facts {
+        let _ = writeln!(body, "<dt>{label}</dt><dd>{value}</dd>");
+    }
+    let _ = writeln!(body, "</dl>");
+
+    if let Some(cover) = doc.images.first() {
+        let _ = writeln!(
+            body,
+            "<p><img src=\"{}\" alt=\"{}\" loading=\"lazy\"></p>",
+            escape(&cover.src),
+            escape(cover.alt.as_deref().unwrap_or(&title))
+        );
+    }
+
+    for section in doc.download_sections.iter().filter(|s| !s.links.is_empty()) {
+        let _ = writeln!(body, "<h2>{}</h2>\n<ul>", escape(&section.heading));
+        for l in &section.links {
+            let text = if l.text.is_empty() { &l.href } else { &l.text };
+            let item = link(&l.href, text);
+            let host = l
+                .host
+                .as_deref()
+                .map(|h| format!(" <span class=\"muted\">{}</span>", escape(h)))
+                .unwrap_or_default();
+            let _ = writeln!(body, "<li>{item}{host}</li>");
+        }
+        let _ = writeln!(body, "</ul>");
+    }
+
+    let downloads: Vec<&String> = doc
+        .magnet_links
+        .iter()
+        .chain(&doc.torrent_file_links)
+        .collect();
+    if !downloads.is_empty() {
+        let _ = writeln!(body, "<h2>Magnets and torrents</h2>\n<ul>");
+        for href in downloads {
+            let _ = writeln!(body, "<li><code>{}</code></li>", link(href, href));
+        }
+        let _ = writeln!(body, "</ul>");
+    }
+
+    for spoiler in &doc.spoiler_sections {
+        let _ = writeln!(
+            body,
+            "<details><summary>{}</summary><p>{}</p></details>",
+            escape(&spoiler.title),
+            escape(&spoiler.text).replace('\n', "<br>")
+        );
+    }
+
+    format!(
+        "<!doctype html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape(&title)
+    )
+}
+
+fn posted(doc: &ParsedDocument) -> Option<String> {
+    let post = doc.post.as_ref()?;
+    post.entry_datetime_rfc3339
+        .clone()
+        .or_else(|| post.entry_datetime.clone())
+}
+
+pub(super) fn unique_file_name(
+    doc: &ParsedDocument,
+    ext: &str,
+    used: &mut BTreeSet<String>,
+) -> String {
+    let release = doc.release.as_ref();
+    let stem = match (
+        release.and_then(|r| r.release_number),
+        release.and_then(|r| r.title_slug.as_deref()),
+    ) {
+        (Some(n), Some(slug)) => format!("{n}-{slug}"),
+        (Some(n), None) => n.to_string(),
+        (None, Some(slug)) => slug.to_string(),
+        (None, None) => doc
+            .doc_id
+            .clone()
+            .unwrap_or_else(|| doc.source.sha256.chars().take(16).collect()),
+    };
+    let stem: String = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let mut name = format!("{stem}.{ext}");
+    let mut n = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{stem}-{n}.{ext}");
+        n += 1;
+    }
+    name
+}
+
+// Scraped hrefs can carry any scheme; only web and magnet links become
+// clickable, anything else (`javascript:`, `data:`, empty) stays plain text.
+fn link(href: &str, text: &str) -> String {
+    let scheme = href
+        .split_once(':')
+        .map(|(s, _)| s.trim().to_ascii_lowercase());
+    if !matches!(scheme.as_deref(), Some("http" | "https" | "magnet")) {
+        return escape(text);
+    }
+    format!(
+        "<a href=\"{}\" rel=\"noreferrer\">{}</a>",
+        escape(href),
+        escape(text)
+    )
+}
+
+pub(super) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
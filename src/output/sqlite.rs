@@ -0,0 +1,218 @@
+use crate::dedupe;
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::parser::links::{host_of, magnet_infohashes};
+use anyhow::{Context, Result};
+use rusqlite::{Connection, Transaction, params};
+use std::path::Path;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS documents (
+    doc_id TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    site TEXT NOT NULL,
+    canonical_url TEXT,
+    post_id INTEGER,
+    entry_title TEXT,
+    entry_datetime TEXT,
+    release_number INTEGER,
+    game_title TEXT,
+    release_group TEXT,
+    version TEXT,
+    original_size_bytes INTEGER,
+    repack_size_bytes_min INTEGER,
+    repack_size_bytes_max INTEGER,
+    content_language TEXT,
+    data TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS tags (
+    doc_id TEXT NOT NULL REFERENCES documents(doc_id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (doc_id, kind, tag)
+);
+CREATE TABLE IF NOT EXISTS links (
+    doc_id TEXT NOT NULL REFERENCES documents(doc_id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    section TEXT,
+    text TEXT,
+    href TEXT NOT NULL,
+    host TEXT,
+    category TEXT
+);
+CREATE TABLE IF NOT EXISTS spoilers (
+    doc_id TEXT NOT NULL REFERENCES documents(doc_id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    text TEXT NOT NULL,
+    PRIMARY KEY (doc_id, position)
+);
+CREATE TABLE IF NOT EXISTS magnets (
+    doc_id TEXT NOT NULL REFERENCES documents(doc_id) ON DELETE CASCADE,
+    uri TEXT NOT NULL,
+    infohash TEXT,
+    PRIMARY KEY (doc_id, uri)
+);
+CREATE INDEX IF NOT EXISTS tags_tag ON tags(tag);
+CREATE INDEX IF NOT EXISTS links_doc ON links(doc_id);
+CREATE INDEX IF NOT EXISTS links_host ON links(host);
+CREATE INDEX IF NOT EXISTS magnets_infohash ON magnets(infohash);
+"#;
+
+// Documents are upserted on doc_id and their child rows replaced, so running
+// the same inputs again refreshes rows instead of duplicating them. `data`
+// always holds the whole document so the database can be read back.
+pub fn write_database(path: &Path, bundle: &OutputBundle) -> Result<()> {
+    let mut conn = open_database(path)?;
+
+    let updated_at = chrono::Utc::now().to_rfc3339();
+    let tx = conn.transaction()?;
+    for doc in &bundle.documents {
+        let doc_id = doc.doc_id.clone().unwrap_or_else(|| dedupe::doc_id(doc));
+        upsert_document(&tx, &doc_id, doc, &updated_at)
+            .with_context(|| format!("write {}", doc.source.path))?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// Opens (creating if needed) a database with the catalogue tables in place.
+pub fn open_database(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path).with_context(|| format!("open sqlite {}", path.display()))?;
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    conn.execute_batch(SCHEMA).context("create sqlite schema")?;
+    Ok(conn)
+}
+
+fn upsert_document(
+    tx: &Transaction,
+    doc_id: &str,
+    doc: &ParsedDocument,
+    updated_at: &str,
+) -> Result<()> {
+    let data = serde_json::to_string(doc)?;
+    let post = doc.post.as_ref();
+    let release = doc.release.as_ref();
+    tx.execute(
+        "INSERT INTO documents (doc_id, path, sha256, site, canonical_url, post_id, entry_title,
+             entry_datetime, release_number, game_title, release_group, version,
+             original_size_bytes, repack_size_bytes_min, repack_size_bytes_max,
+             content_language, data, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+         ON CONFLICT(doc_id) DO UPDATE SET
+             path = excluded.path, sha256 = excluded.sha256, site = excluded.site,
+             canonical_url = excluded.canonical_url, post_id = excluded.post_id,
+             entry_title = excluded.entry_title, entry_datetime = excluded.entry_datetime,
+             release_number = excluded.release_number, game_title = excluded.game_title,
+             release_group = excluded.release_group, version = excluded.version,
+             original_size_bytes = excluded.original_size_bytes,
+             repack_size_bytes_min = excluded.repack_size_bytes_min,
+             repack_size_bytes_max = excluded.repack_size_bytes_max,
+             content_language = excluded.content_language, data = excluded.data,
+             updated_at = excluded.updated_at",
+        params![
+            doc_id,
+            doc.source.path,
+            doc.source.sha256,
+            doc.site,
+            doc.page.as_ref().and_then(|p| p.canonical_url.as_deref()),
+            sql_int(post.and_then(|p| p.post_id)),
+            post.and_then(|p| p.entry_title.as_deref()),
+            post.and_then(|p| p.entry_datetime_rfc3339.as_deref()),
+            sql_int(release.and_then(|r| r.release_number)),
+            release.and_then(|r| r.game_title_line.as_deref()),
+            release.and_then(|r| r.group.as_deref()),
+            release
+                .and_then(|r| r.version.as_ref())
+                .map(|v| v.raw.as_str()),
+            sql_int(release.and_then(|r| r.original_size_bytes)),
+            sql_int(release.and_then(|r| r.repack_size_bytes_min)),
+            sql_int(release.and_then(|r| r.repack_size_bytes_max)),
+            doc.content_language.as_ref().map(|l| l.code.as_str()),
+            data,
+            updated_at,
+        ],
+    )?;
+
+    for table in ["tags", "links", "spoilers", "magnets"] {
+        tx.execute(&format!("DELETE FROM {table} WHERE doc_id = ?1"), [doc_id])?;
+    }
+
+    let mut tag = tx.prepare_cached("INSERT OR IGNORE INTO tags VALUES (?1, ?2, ?3)")?;
+    let tag_lists: [(&str, &[String]); 3] = [
+        (
+            "genre_tag",
+            release
+                .map(|r| r.genres_tags.as_slice())
+                .unwrap_or_default(),
+        ),
+        (
+            "category",
+            post.map(|p| p.categories.as_slice()).unwrap_or_default(),
+        ),
+        (
+            "wp_tag",
+            post.map(|p| p.wp_tags.as_slice()).unwrap_or_default(),
+        ),
+    ];
+    for (kind, tags) in tag_lists {
+        for t in tags {
+            tag.execute(params![doc_id, kind, t])?;
+        }
+    }
+
+    let mut link = tx.prepare_cached(
+        "INSERT INTO links (doc_id, kind, section, text, href, host, category)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    for section in &doc.download_sections {
+        for l in section.links.iter().filter(|l| !l.href.is_empty()) {
+            link.execute(params![
+                doc_id,
+                "mirror",
+                section.heading,
+                l.text,
+                l.href,
+                l.host,
+                l.category
+            ])?;
+        }
+    }
+    for (kind, hrefs) in [
+        ("torrent", &doc.torrent_file_links),
+        ("direct", &doc.direct_download_links),
+    ] {
+        for href in hrefs {
+            let host = host_of(href);
+            link.execute(params![
+                doc_id,
+                kind,
+                None::<&str>,
+                None::<&str>,
+                href,
+                host,
+                None::<&str>
+            ])?;
+        }
+    }
+
+    let mut spoiler = tx.prepare_cached("INSERT INTO spoilers VALUES (?1, ?2, ?3, ?4)")?;
+    for (position, s) in doc.spoiler_sections.iter().enumerate() {
+        spoiler.execute(params![doc_id, position as i64, s.title, s.text])?;
+    }
+
+    let mut magnet = tx.prepare_cached("INSERT OR IGNORE INTO magnets VALUES (?1, ?2, ?3)")?;
+    for uri in &doc.magnet_links {
+        let infohash = magnet_infohashes(std::slice::from_ref(uri))
+            .into_iter()
+            .next();
+        magnet.execute(params![doc_id, uri, infohash])?;
+    }
+    Ok(())
+}
+
+// SQLite integers are signed 64-bit; ids and byte sizes never get near the limit.
+fn sql_int(v: Option<u64>) -> Option<i64> {
+    v.and_then(|n| i64::try_from(n).ok())
+}
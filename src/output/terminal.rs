@@ -0,0 +1,134 @@
+use super::markdown::title;
+use crate::model::{OutputBundle, ParsedDocument};
+use anyhow::Result;
+use std::io::Write;
+
+// Titles are cut to fit `$COLUMNS` (or this width when unset) so each release
+// stays on one line.
+const DEFAULT_TITLE_WIDTH: usize = 60;
+const MIN_TITLE_WIDTH: usize = 20;
+
+const HEADERS: [&str; 5] = ["#", "TITLE", "DATE", "REPACK", "MAGNETS"];
+// Right-aligned columns.
+const NUMERIC: [bool; 5] = [true, false, false, true, true];
+
+pub fn write_quick_view<W: Write>(out: &mut W, bundle: &OutputBundle) -> Result<()> {
+    let mut rows: Vec<[String; 5]> = bundle.documents.iter().map(row).collect();
+    let mut widths = HEADERS.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let others: usize = widths
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != 1)
+        .map(|(_, w)| w + 2)
+        .sum();
+    let max_title = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .map_or(DEFAULT_TITLE_WIDTH, |cols| {
+            cols.saturating_sub(others).max(MIN_TITLE_WIDTH)
+        });
+    if widths[1] > max_title {
+        widths[1] = max_title;
+        for row in &mut rows {
+            row[1] = truncate(&row[1], max_title);
+        }
+    }
+
+    let header = HEADERS.map(str::to_string);
+    write_row(out, &header, &widths)?;
+    let rule = widths.map(|w| "-".repeat(w));
+    write_row(out, &rule, &widths)?;
+    for row in &rows {
+        write_row(out, row, &widths)?;
+    }
+    writeln!(
+        out,
+        "\n{} releases from {} inputs ({} failed to parse)",
+        bundle.documents.len(),
+        bundle.stats.input_count,
+        bundle.stats.parsed_err
+    )?;
+    Ok(())
+}
+
+fn row(doc: &ParsedDocument) -> [String; 5] {
+    let release = doc.release.as_ref();
+    let number = release
+        .and_then(|r| r.release_number)
+        .map(|n| n.to_string())
+        .unwrap_or_default();
+    let date = doc
+        .post
+        .as_ref()
+        .and_then(|p| {
+            p.entry_datetime_rfc3339
+                .as_deref()
+                .or(p.entry_datetime.as_deref())
+        })
+        .map(|d| d.chars().take(10).collect())
+        .unwrap_or_default();
+    let size = match release.map(|r| (r.repack_size_bytes_min, r.repack_size_bytes_max)) {
+        Some((Some(min), Some(max))) if min != max => {
+            format!("{}-{}", human_size(min), human_size(max))
+        }
+        Some((Some(bytes), _)) | Some((None, Some(bytes))) => human_size(bytes),
+        _ => String::new(),
+    };
+    [
+        number,
+        title(doc),
+        date,
+        size,
+        doc.magnet_links.len().to_string(),
+    ]
+}
+
+fn write_row<W: Write>(out: &mut W, cells: &[String; 5], widths: &[usize; 5]) -> Result<()> {
+    let mut line = String::new();
+    for (i, cell) in cells.iter().enumerate() {
+        if i > 0 {
+            line.push_str("  ");
+        }
+        let pad = " ".repeat(widths[i].saturating_sub(cell.chars().count()));
+        if NUMERIC[i] {
+            line.push_str(&pad);
+            line.push_str(cell);
+        } else {
+            line.push_str(cell);
+            line.push_str(&pad);
+        }
+    }
+    writeln!(out, "{}", line.trim_end())?;
+    Ok(())
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+// Binary multiples with the unit labels release posts use ("21.3 GB").
+pub(crate) fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
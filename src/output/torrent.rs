@@ -0,0 +1,276 @@
+use super::markdown::{genres, title};
+use super::site::unique_file_name;
+use crate::config::ExportConfig;
+use crate::model::{OutputBundle, ParsedDocument};
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{debug, warn};
+use ureq::Agent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Client {
+    Qbittorrent,
+    Transmission,
+}
+
+impl Client {
+    fn default_url(self) -> &'static str {
+        match self {
+            Self::Qbittorrent => "http://localhost:8080",
+            Self::Transmission => "http://localhost:9091/transmission/rpc",
+        }
+    }
+}
+
+// The magnets of one release and the category/label it is filed under.
+#[derive(Debug)]
+pub struct Handoff {
+    pub title: String,
+    pub magnets: Vec<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct PushStats {
+    pub added: usize,
+    pub duplicates: usize,
+    pub failed: usize,
+}
+
+pub fn plan(bundle: &OutputBundle, cfg: &ExportConfig) -> Vec<Handoff> {
+    bundle
+        .documents
+        .iter()
+        .filter(|doc| !doc.magnet_links.is_empty())
+        .map(|doc| Handoff {
+            title: title(doc),
+            magnets: doc.magnet_links.clone(),
+            category: category(doc, cfg),
+        })
+        .collect()
+}
+
+fn category(doc: &ParsedDocument, cfg: &ExportConfig) -> Option<String> {
+    genres(doc)
+        .iter()
+        .find_map(|genre| {
+            cfg.genre_categories
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(genre))
+                .map(|(_, category)| category.clone())
+        })
+        .or_else(|| Some(cfg.category.clone()))
+        .filter(|c| !c.is_empty())
+}
+
+// One `.magnets` file per release (one magnet URI per line), named like the
+// `site` release pages.
+pub fn write_magnet_lists(dir: &Path, bundle: &OutputBundle) -> Result<usize> {
+    std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    let mut used = BTreeSet::new();
+    for doc in bundle
+        .documents
+        .iter()
+        .filter(|d| !d.magnet_links.is_empty())
+    {
+        let file = unique_file_name(doc, "magnets", &mut used);
+        let mut text = doc.magnet_links.join("\n");
+        text.push('\n');
+        std::fs::write(dir.join(&file), text).with_context(|| format!("write {file}"))?;
+    }
+    Ok(used.len())
+}
+
+pub fn push(client: Client, cfg: &ExportConfig, handoffs: &[Handoff]) -> Result<PushStats> {
+    let url = if cfg.url.is_empty() {
+        client.default_url()
+    } else {
+        cfg.url.trim_end_matches('/')
+    };
+    // Status codes are checked per call; both APIs use them for control flow.
+    let agent: Agent = Agent::config_builder()
+        .http_status_as_error(false)
+        .timeout_global(Some(Duration::from_secs(30)))
+        .build()
+        .into();
+    match client {
+        Client::Qbittorrent => push_qbittorrent(&agent, url, cfg, handoffs),
+        Client::Transmission => push_transmission(&agent, url, cfg, handoffs),
+    }
+}
+
+// qBittorrent Web API v2: cookie login, then one `torrents/add` per release.
+fn push_qbittorrent(
+    agent: &Agent,
+    url: &str,
+    cfg: &ExportConfig,
+    handoffs: &[Handoff],
+) -> Result<PushStats> {
+    let mut cookie = None;
+    if !cfg.username.is_empty() {
+        let mut resp = agent
+            .post(format!("{url}/api/v2/auth/login"))
+            .header("Referer", url)
+            .send_form([
+                ("username", cfg.username.as_str()),
+                ("password", cfg.password.as_str()),
+            ])
+            .with_context(|| format!("connect to qBittorrent at {url}"))?;
+        let body = resp.body_mut().read_to_string().unwrap_or_default();
+        if !resp.status().is_success() || body.trim() != "Ok." {
+            bail!(
+                "qBittorrent login failed ({}): {}",
+                resp.status(),
+                body.trim()
+            );
+        }
+        cookie = resp
+            .headers()
+            .get("set-cookie")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(str::to_string);
+    }
+    let post = |path: &str| {
+        let req = agent
+            .post(format!("{url}/api/v2/{path}"))
+            .header("Referer", url);
+        match &cookie {
+            Some(cookie) => req.header("Cookie", cookie),
+            None => req,
+        }
+    };
+
+    // Adding to a category that does not exist fails, so create them first;
+    // 409 means it is already there.
+    let categories: BTreeSet<&str> = handoffs
+        .iter()
+        .filter_map(|h| h.category.as_deref())
+        .collect();
+    for category in categories {
+        let resp = post("torrents/createCategory")
+            .send_form([("category", category), ("savePath", "")])
+            .context("create qBittorrent category")?;
+        if !resp.status().is_success() && resp.status().as_u16() != 409 {
+            bail!(
+                "qBittorrent rejected category `{category}` ({})",
+                resp.status()
+            );
+        }
+    }
+
+    let paused = if cfg.paused { "true" } else { "false" };
+    let mut stats = PushStats::default();
+    for handoff in handoffs {
+        let urls = handoff.magnets.join("\n");
+        let mut form = vec![
+            ("urls", urls.as_str()),
+            ("paused", paused),
+            ("stopped", paused),
+        ];
+        if let Some(category) = &handoff.category {
+            form.push(("category", category));
+        }
+        if !cfg.save_path.is_empty() {
+            form.push(("savepath", &cfg.save_path));
+        }
+        let mut resp = post("torrents/add")
+            .send_form(form)
+            .context("add torrents to qBittorrent")?;
+        let body = resp.body_mut().read_to_string().unwrap_or_default();
+        match resp.status().as_u16() {
+            200 if body.trim() != "Fails." => {
+                debug!(title = %handoff.title, magnets = handoff.magnets.len(), "added to qBittorrent");
+                stats.added += handoff.magnets.len();
+            }
+            // qBittorrent answers "Fails." (409 on 5.x) when every URL was
+            // already present or unparseable.
+            200 | 409 => stats.duplicates += handoff.magnets.len(),
+            403 => bail!("qBittorrent refused the request (403); check credentials"),
+            status => {
+                warn!(title = %handoff.title, status, body = %body.trim(), "qBittorrent rejected torrents");
+                stats.failed += handoff.magnets.len();
+            }
+        }
+    }
+    Ok(stats)
+}
+
+// Transmission RPC: every call needs the session id from a 409 handshake.
+fn push_transmission(
+    agent: &Agent,
+    url: &str,
+    cfg: &ExportConfig,
+    handoffs: &[Handoff],
+) -> Result<PushStats> {
+    let auth = (!cfg.username.is_empty()).then(|| {
+        format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{}", cfg.username, cfg.password))
+        )
+    });
+    let mut session = String::new();
+    let mut call = |body: &Value| -> Result<Value> {
+        for _ in 0..2 {
+            let mut req = agent
+                .post(url)
+                .header("X-Transmission-Session-Id", session.as_str());
+            if let Some(auth) = &auth {
+                req = req.header("Authorization", auth.as_str());
+            }
+            let mut resp = req
+                .send_json(body)
+                .with_context(|| format!("connect to Transmission at {url}"))?;
+            match resp.status().as_u16() {
+                409 => {
+                    session = resp
+                        .headers()
+                        .get("X-Transmission-Session-Id")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                }
+                401 => bail!("Transmission refused the request (401); check credentials"),
+                _ if resp.status().is_success() => {
+                    return resp
+                        .body_mut()
+                        .read_json()
+                        .context("decode Transmission response");
+                }
+                _ => bail!("Transmission RPC failed ({})", resp.status()),
+            }
+        }
+        bail!("Transmission did not accept the RPC session id")
+    };
+
+    let mut stats = PushStats::default();
+    for handoff in handoffs {
+        for magnet in &handoff.magnets {
+            let mut arguments = json!({ "filename": magnet, "paused": cfg.paused });
+            if let Some(category) = &handoff.category {
+                arguments["labels"] = json!([category]);
+            }
+            if !cfg.save_path.is_empty() {
+                arguments["download-dir"] = json!(cfg.save_path);
+            }
+            let reply = call(&json!({ "method": "torrent-add", "arguments": arguments }))?;
+            let args = &reply["arguments"];
+            match reply["result"].as_str() {
+                Some("success") if args.get("torrent-duplicate").is_some() => {
+                    stats.duplicates += 1;
+                }
+                Some("success") => stats.added += 1,
+                result => {
+                    warn!(title = %handoff.title, result = result.unwrap_or_default(), "Transmission rejected torrent");
+                    stats.failed += 1;
+                }
+            }
+        }
+    }
+    Ok(stats)
+}
@@ -0,0 +1,117 @@
+use super::{ColumnKind, Table, render_leaf};
+use crate::model::OutputBundle;
+use anyhow::Result;
+use rust_xlsxwriter::{Format, Workbook, Worksheet};
+use serde_json::Value;
+
+// Excel rejects longer cell strings.
+const MAX_CELL_CHARS: usize = 32_767;
+
+pub fn workbook_bytes(
+    table: &Table,
+    bundle: &OutputBundle,
+    list_delimiter: &str,
+) -> Result<Vec<u8>> {
+    let header = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+
+    let sheet = workbook.add_worksheet().set_name("documents")?;
+    for (col, column) in table.columns.iter().enumerate() {
+        sheet.write_with_format(0, col as u16, &column.name, &header)?;
+    }
+    for (i, row) in table.rows.iter().enumerate() {
+        let r = i as u32 + 1;
+        for (col, (column, cell)) in table.columns.iter().zip(row).enumerate() {
+            let col = col as u16;
+            let number = match column.kind {
+                ColumnKind::Int | ColumnKind::Float => cell.first().and_then(Value::as_f64),
+                ColumnKind::Text | ColumnKind::List => None,
+            };
+            match number {
+                Some(n) => {
+                    sheet.write_number(r, col, n)?;
+                }
+                None if !cell.is_empty() => {
+                    let text: Vec<String> = cell.iter().map(render_leaf).collect();
+                    write_text(sheet, r, col, &text.join(list_delimiter))?;
+                }
+                None => {}
+            }
+        }
+    }
+    finish_sheet(sheet, table.rows.len(), table.columns.len())?;
+
+    let sheet = workbook.add_worksheet().set_name("errors")?;
+    sheet.write_with_format(0, 0, "path", &header)?;
+    sheet.write_with_format(0, 1, "error", &header)?;
+    for (i, err) in bundle.errors.iter().enumerate() {
+        write_text(sheet, i as u32 + 1, 0, &err.path)?;
+        write_text(sheet, i as u32 + 1, 1, &err.error)?;
+    }
+    finish_sheet(sheet, bundle.errors.len(), 2)?;
+
+    let sheet = workbook.add_worksheet().set_name("stats")?;
+    sheet.write_with_format(0, 0, "stat", &header)?;
+    sheet.write_with_format(0, 1, "value", &header)?;
+    let mut stats = vec![
+        (
+            "schema_version".to_string(),
+            Value::from(bundle.schema_version),
+        ),
+        ("tool".to_string(), Value::from(bundle.tool.name.as_str())),
+        (
+            "tool_version".to_string(),
+            Value::from(bundle.tool.version.as_str()),
+        ),
+    ];
+    flatten("", &serde_json::to_value(&bundle.stats)?, &mut stats);
+    for (i, (key, value)) in stats.iter().enumerate() {
+        let r = i as u32 + 1;
+        write_text(sheet, r, 0, key)?;
+        match value {
+            Value::Number(n) => {
+                sheet.write_number(r, 1, n.as_f64().unwrap_or_default())?;
+            }
+            other => write_text(sheet, r, 1, &render_leaf(other))?,
+        }
+    }
+    finish_sheet(sheet, stats.len(), 2)?;
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+// Nested stats objects become dotted keys; arrays stay as compact JSON.
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(fields) => {
+            for (key, child) in fields {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(&key, child, out);
+            }
+        }
+        Value::Array(_) => out.push((prefix.to_string(), Value::from(value.to_string()))),
+        other => out.push((prefix.to_string(), other.clone())),
+    }
+}
+
+fn write_text(sheet: &mut Worksheet, row: u32, col: u16, text: &str) -> Result<()> {
+    let text = match text.char_indices().nth(MAX_CELL_CHARS) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    };
+    sheet.write_string(row, col, text)?;
+    Ok(())
+}
+
+fn finish_sheet(sheet: &mut Worksheet, rows: usize, cols: usize) -> Result<()> {
+    sheet.set_freeze_panes(1, 0)?;
+    if cols > 0 {
+        sheet.autofilter(0, 0, rows as u32, cols as u16 - 1)?;
+    }
+    sheet.autofit();
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::model::{Confidence, ParsedDocument, ReleaseMeta};
+use crate::parser::normalize;
+use std::collections::BTreeMap;
+
+pub fn score(doc: &ParsedDocument) -> Option<Confidence> {
+    let mut fields: BTreeMap<String, f64> = BTreeMap::new();
+
+    if let Some(release) = doc.release.as_ref() {
+        score_release(release, &mut fields);
+    }
+
+    if !doc.torrent_file_links.is_empty() {
+        let total: f64 = doc
+            .torrent_file_links
+            .iter()
+            .map(|href| torrent_link_score(href))
+            .sum();
+        fields.insert(
+            "torrent_file_links".to_string(),
+            total / doc.torrent_file_links.len() as f64,
+        );
+    }
+
+    if fields.is_empty() {
+        return None;
+    }
+
+    let overall = fields.values().sum::<f64>() / fields.len() as f64;
+    for value in fields.values_mut() {
+        *value = round2(*value);
+    }
+    Some(Confidence {
+        overall: round2(overall),
+        fields,
+    })
+}
+
+fn score_release(release: &ReleaseMeta, fields: &mut BTreeMap<String, f64>) {
+    if let Some(number) = release.release_number {
+        let marker = format!("#{number}");
+        let line = release.game_title_line.as_deref().map(str::trim_start);
+        let value = match line {
+            Some(l) if l.starts_with(&marker) => 0.95,
+            Some(l) if l.contains(&marker) => 0.7,
+            Some(_) => 0.5,
+            None => 0.6,
+        };
+        fields.insert("release_number".to_string(), value);
+    }
+
+    if !release.companies.is_empty() {
+        fields.insert(
+            "companies".to_string(),
+            label_capture_score(&release.companies.join(", ")),
+        );
+    }
+
+    if let Some(raw) = release.languages_raw.as_deref() {
+        let (languages, _) = normalize::parse_languages(raw);
+        let tokens = raw
+            .split([',', '/', '&', ';'])
+            .map(str::trim)
+            .filter(|t| !t.is_empty() && !t.to_ascii_lowercase().starts_with("multi"))
+            .count()
+            .max(1);
+        let recognized = (languages.len() as f64 / tokens as f64).min(1.0);
+        fields.insert(
+            "languages".to_string(),
+            label_capture_score(raw).min(0.5 + 0.45 * recognized),
+        );
+    }
+
+    for (name, raw) in [
+        ("original_size", release.original_size_raw.as_deref()),
+        ("repack_size", release.repack_size_raw.as_deref()),
+    ] {
+        if let Some(raw) = raw {
+            let value = match normalize::parse_size_range(raw) {
+                Some(_) => label_capture_score(raw).min(0.95),
+                None => 0.4,
+            };
+            fields.insert(name.to_string(), value);
+        }
+    }
+}
+
+// Label captures run until the next known label; a value that still contains
+// a colon or runs long probably swallowed text from an unexpected label.
+fn label_capture_score(value: &str) -> f64 {
+    if value.contains(':') {
+        0.4
+    } else if value.chars().count() > 120 {
+        0.5
+    } else {
+        0.9
+    }
+}
+
+fn torrent_link_score(href: &str) -> f64 {
+    let path = href.split(['?', '#']).next().unwrap_or(href);
+    if path.to_ascii_lowercase().ends_with(".torrent") {
+        0.95
+    } else if href.to_ascii_lowercase().contains(".torrent") {
+        0.8
+    } else {
+        0.6
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
@@ -0,0 +1,659 @@
+use crate::model::{
+    AnchorLink, CryptoAddress, Donations, DownloadSection, LinkProfile, ParsedDocument,
+    ReviewScore, SectionLink,
+};
+use crate::parser::util::{bump_domain_count, normalize_ws, select_attr};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::{BTreeMap, BTreeSet};
+use url::Url;
+
+static RE_STEAM_APP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:store\.steampowered\.com|steamcommunity\.com|steamdb\.info)/app/(\d+)")
+        .expect("valid regex")
+});
+static RE_REVIEW_SCORE_TEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(metacritic|opencritic)\s*(?:score|rating|metascore)?\s*[:\-]?\s*(\d{1,3})\b",
+    )
+    .expect("valid regex")
+});
+static RE_SCORE_NUMBER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d{1,3})(?:\s*/\s*100)?\b").expect("valid regex"));
+static RE_CRYPTO_ADDRESSES: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    [
+        ("btc", r"\b(bc1[ac-hj-np-z02-9]{25,87})\b"),
+        ("btc", r"\b([13][a-km-zA-HJ-NP-Z1-9]{25,34})\b"),
+        ("eth", r"\b(0x[a-fA-F0-9]{40})\b"),
+        ("ltc", r"\b(ltc1[ac-hj-np-z02-9]{25,87})\b"),
+        ("xmr", r"\b(4[0-9AB][1-9A-HJ-NP-Za-km-z]{93})\b"),
+    ]
+    .into_iter()
+    .map(|(currency, re)| (currency, Regex::new(re).expect("valid regex")))
+    .collect()
+});
+static RE_GOG_PRODUCT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(?:gog\.com/(?:[a-z]{2}(?:-[a-z]{2})?/)?game/([a-z0-9_]+)|(?:gog\.com|gogdb\.org)/(?:[a-z/]*/)?product/(\d+))",
+    )
+    .expect("valid regex")
+});
+
+pub fn document_base_url(doc: &Html) -> Option<Url> {
+    let canonical = select_attr(doc, "link[rel='canonical']", "href")
+        .or_else(|| select_attr(doc, "meta[property='og:url']", "content"))
+        .and_then(|u| Url::parse(u.trim()).ok());
+    let base_href = select_attr(doc, "base[href]", "href");
+
+    match (base_href, canonical) {
+        (Some(base), Some(canonical)) => canonical.join(base.trim()).ok().or(Some(canonical)),
+        (Some(base), None) => Url::parse(base.trim()).ok(),
+        (None, canonical) => canonical,
+    }
+}
+
+pub fn resolve_href(base: Option<&Url>, href: &str) -> Option<String> {
+    let href_l = href.to_ascii_lowercase();
+    if href_l.starts_with("http://") || href_l.starts_with("https://") {
+        return Some(href.to_string());
+    }
+    if href.is_empty() || href.starts_with('#') || !is_relative(&href_l) {
+        return None;
+    }
+    if let Some(rest) = href.strip_prefix("//") {
+        let scheme = base.map(Url::scheme).unwrap_or("https");
+        return Some(format!("{scheme}://{rest}"));
+    }
+    base?.join(href).ok().map(|u| u.to_string())
+}
+
+fn is_relative(href_l: &str) -> bool {
+    !href_l.contains(':') || href_l.split(':').next().is_some_and(|s| s.contains('/'))
+}
+
+pub fn extract_download_sections(
+    doc: &Html,
+    root_selector: &str,
+    base: Option<&Url>,
+) -> Vec<DownloadSection> {
+    let root_sel = match Selector::parse(root_selector) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+    let root = match doc.select(&root_sel).next() {
+        Some(r) => r,
+        None => return vec![],
+    };
+
+    let mut out: Vec<DownloadSection> = Vec::new();
+
+    for node in root.descendants() {
+        let el = match ElementRef::wrap(node) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        match el.value().name() {
+            "h3" => {
+                let heading = element_text(&el);
+                if !heading.is_empty() {
+                    out.push(DownloadSection {
+                        heading,
+                        links: vec![],
+                    });
+                }
+            }
+            "a" => {
+                let section = match out.last_mut() {
+                    Some(s) => s,
+                    None => continue,
+                };
+                let href = match el.value().attr("href").map(str::trim) {
+                    Some(h) if !h.is_empty() && !h.starts_with('#') => h,
+                    _ => continue,
+                };
+                let href = resolve_href(base, href).unwrap_or_else(|| href.to_string());
+                section.links.push(SectionLink {
+                    text: element_text(&el),
+                    host: host_of(&href),
+                    href,
+                    category: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    out.retain(|s| !s.links.is_empty());
+    out
+}
+
+pub fn extract_anchor_inventory(doc: &Html, base: Option<&Url>) -> Vec<AnchorLink> {
+    let mut out: Vec<AnchorLink> = Vec::new();
+    let mut section: Option<String> = None;
+
+    for node in doc.root_element().descendants() {
+        let el = match ElementRef::wrap(node) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        match el.value().name() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let heading = element_text(&el);
+                if !heading.is_empty() {
+                    section = Some(heading);
+                }
+            }
+            "a" => {
+                let href = match el.value().attr("href").map(str::trim) {
+                    Some(h) if !h.is_empty() => h,
+                    _ => continue,
+                };
+                out.push(AnchorLink {
+                    text: element_text(&el),
+                    href: resolve_href(base, href).unwrap_or_else(|| href.to_string()),
+                    rel: el
+                        .value()
+                        .attr("rel")
+                        .map(normalize_ws)
+                        .filter(|s| !s.is_empty()),
+                    section: section.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+pub fn extract_link_profile(
+    doc: &Html,
+    site_host: Option<String>,
+    base: Option<&Url>,
+    resolve_relative: bool,
+    ignore_magnet: bool,
+) -> LinkProfile {
+    let mut out = LinkProfile {
+        site_host,
+        internal: 0,
+        external: 0,
+        relative: 0,
+        nofollow: 0,
+        internal_domain_counts: BTreeMap::new(),
+        external_domain_counts: BTreeMap::new(),
+    };
+    let selector = match Selector::parse("a[href]") {
+        Ok(s) => s,
+        Err(_) => return out,
+    };
+
+    for a in doc.select(&selector) {
+        let href = a.value().attr("href").unwrap_or_default().trim();
+        let href_l = href.to_ascii_lowercase();
+        if href.is_empty() || href.starts_with('#') {
+            continue;
+        }
+        if ignore_magnet && href_l.starts_with("magnet:") {
+            continue;
+        }
+
+        if a.value().attr("rel").is_some_and(|rel| {
+            rel.split_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("nofollow"))
+        }) {
+            out.nofollow += 1;
+        }
+
+        let absolute = href_l.starts_with("http://") || href_l.starts_with("https://");
+        // Protocol-relative links are only followed to their host when
+        // resolving is on; otherwise they are left uncounted.
+        if href.starts_with("//") && !resolve_relative {
+            continue;
+        }
+        let relative = !absolute && is_relative(&href_l) && !href.starts_with("//");
+        if relative {
+            out.relative += 1;
+        }
+
+        match resolve_href(base, href).and_then(|h| host_of(&h)) {
+            Some(host) => {
+                // Without a canonical host, a relative link resolved against
+                // `<base href>` still points at this site.
+                let internal = match out.site_host.as_deref() {
+                    Some(site) => same_site(&host, site),
+                    None => relative,
+                };
+                if internal {
+                    out.internal += 1;
+                    bump_domain_count(&mut out.internal_domain_counts, &host);
+                } else {
+                    out.external += 1;
+                    bump_domain_count(&mut out.external_domain_counts, &host);
+                }
+            }
+            // Relative links with no base to resolve against still point at this site.
+            None if relative => {
+                out.internal += 1;
+            }
+            None => {}
+        }
+    }
+
+    out
+}
+
+fn same_site(host: &str, site: &str) -> bool {
+    let strip = |h: &str| h.strip_prefix("www.").unwrap_or(h).to_string();
+    strip(host) == strip(site)
+}
+
+const PAGE_SECTIONS: &[(&str, &str)] = &[
+    (
+        "comments",
+        "#comments, .comments-area, ol.commentlist, ol.comment-list, .tolstoycomments-feed",
+    ),
+    ("content", "div.entry-content"),
+    ("sidebar", "#secondary, aside, .sidebar, .widget-area"),
+    ("footer", "footer, #colophon, .site-footer"),
+    // `<header class="entry-header">` inside the post is not the page header.
+    (
+        "header",
+        "header:not(article header), #masthead, .site-header, nav:not(article nav)",
+    ),
+];
+
+pub fn extract_section_link_counts(doc: &Html) -> BTreeMap<String, u64> {
+    let mut out = BTreeMap::new();
+    let selector = match Selector::parse("a[href]") {
+        Ok(s) => s,
+        Err(_) => return out,
+    };
+    let sections: Vec<(&str, Selector)> = PAGE_SECTIONS
+        .iter()
+        .filter_map(|(name, raw)| Selector::parse(raw).ok().map(|sel| (*name, sel)))
+        .collect();
+
+    for a in doc.select(&selector) {
+        let section = a
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find_map(|el| {
+                sections
+                    .iter()
+                    .find(|(_, sel)| sel.matches(&el))
+                    .map(|(name, _)| *name)
+            })
+            .unwrap_or("other");
+        bump_domain_count(&mut out, section);
+    }
+
+    out
+}
+
+pub fn extract_host_category_counts(
+    doc: &Html,
+    base: Option<&Url>,
+    categories: &BTreeMap<String, String>,
+) -> BTreeMap<String, u64> {
+    let mut out = BTreeMap::new();
+    let selector = match Selector::parse("a[href]") {
+        Ok(s) => s,
+        Err(_) => return out,
+    };
+
+    for a in doc.select(&selector) {
+        let host = match a
+            .value()
+            .attr("href")
+            .and_then(|h| resolve_href(base, h.trim()))
+            .and_then(|h| host_of(&h))
+        {
+            Some(h) => h,
+            None => continue,
+        };
+        let category = classify_host(&host, categories);
+        bump_domain_count(&mut out, category.as_deref().unwrap_or("unclassified"));
+    }
+
+    out
+}
+
+// Looks up `host` in the category map, also matching subdomains of a listed
+// host.
+pub fn classify_host(host: &str, categories: &BTreeMap<String, String>) -> Option<String> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let mut candidate = host.as_str();
+
+    loop {
+        if let Some(category) = categories.get(candidate) {
+            return Some(category.clone());
+        }
+        match candidate.split_once('.') {
+            Some((_, rest)) if rest.contains('.') => candidate = rest,
+            _ => return None,
+        }
+    }
+}
+
+pub fn extract_steam_appids(doc: &Html, anchor_selector: &str) -> Vec<u64> {
+    let selector = match Selector::parse(anchor_selector) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    let mut out: Vec<u64> = doc
+        .select(&selector)
+        .filter_map(|a| a.value().attr("href"))
+        .filter_map(steam_appid_from_url)
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+pub fn steam_appid_from_url(href: &str) -> Option<u64> {
+    RE_STEAM_APP
+        .captures(href)
+        .and_then(|cap| cap.get(1))
+        .and_then(|m| m.as_str().parse::<u64>().ok())
+}
+
+pub fn extract_gog_ids(doc: &Html, anchor_selector: &str) -> Vec<String> {
+    let selector = match Selector::parse(anchor_selector) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    let mut out: Vec<String> = doc
+        .select(&selector)
+        .filter_map(|a| a.value().attr("href"))
+        .filter_map(gog_id_from_url)
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+pub fn gog_id_from_url(href: &str) -> Option<String> {
+    let cap = RE_GOG_PRODUCT.captures(href)?;
+    cap.get(1)
+        .or_else(|| cap.get(2))
+        .map(|m| m.as_str().to_ascii_lowercase())
+}
+
+const REVIEW_AGGREGATORS: &[(&str, &str)] = &[
+    ("metacritic.com", "metacritic"),
+    ("opencritic.com", "opencritic"),
+];
+
+pub fn extract_review_scores(doc: &Html, root_selector: &str) -> Vec<ReviewScore> {
+    let mut out: Vec<ReviewScore> = Vec::new();
+    let anchor_sel = match Selector::parse(&format!("{root_selector} a[href]")) {
+        Ok(s) => s,
+        Err(_) => return out,
+    };
+    let img_sel = Selector::parse("img").ok();
+
+    for a in doc.select(&anchor_sel) {
+        let href = a.value().attr("href").unwrap_or_default().trim();
+        let source = match host_of(href).and_then(|h| review_source(&h)) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let mut hint = element_text(&a);
+        if let Some(sel) = &img_sel {
+            for img in a.select(sel) {
+                for attr in ["alt", "title"] {
+                    if let Some(v) = img.value().attr(attr) {
+                        hint.push(' ');
+                        hint.push_str(v);
+                    }
+                }
+            }
+        }
+        let score = score_from_text(&hint);
+
+        match out.iter_mut().find(|r| r.source == source) {
+            Some(existing) => {
+                existing.score = existing.score.or(score);
+            }
+            None => out.push(ReviewScore {
+                source: source.to_string(),
+                url: Some(href.to_string()),
+                score,
+            }),
+        }
+    }
+
+    if let Ok(root_sel) = Selector::parse(root_selector)
+        && let Some(root) = doc.select(&root_sel).next()
+    {
+        let text = element_text(&root);
+        for cap in RE_REVIEW_SCORE_TEXT.captures_iter(&text) {
+            let source = cap[1].to_ascii_lowercase();
+            let score = cap[2].parse::<u32>().ok().filter(|s| *s <= 100);
+            match out.iter_mut().find(|r| r.source == source) {
+                Some(existing) => existing.score = existing.score.or(score),
+                None => out.push(ReviewScore {
+                    source,
+                    url: None,
+                    score,
+                }),
+            }
+        }
+    }
+
+    out
+}
+
+fn review_source(host: &str) -> Option<&'static str> {
+    REVIEW_AGGREGATORS
+        .iter()
+        .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{domain}")))
+        .map(|(_, source)| *source)
+}
+
+fn score_from_text(text: &str) -> Option<u32> {
+    RE_SCORE_NUMBER
+        .captures_iter(text)
+        .filter_map(|cap| cap[1].parse::<u32>().ok())
+        .find(|s| *s <= 100)
+}
+
+const DONATION_PLATFORMS: &[&str] = &[
+    "patreon.com",
+    "ko-fi.com",
+    "buymeacoffee.com",
+    "paypal.me",
+    "boosty.to",
+    "liberapay.com",
+    "opencollective.com",
+    "donationalerts.com",
+];
+
+pub fn extract_donations(doc: &Html) -> Option<Donations> {
+    let mut out = Donations {
+        crypto: vec![],
+        platforms: vec![],
+    };
+
+    let text = visible_text(doc);
+    for (currency, re) in RE_CRYPTO_ADDRESSES.iter() {
+        for cap in re.captures_iter(&text) {
+            let found = CryptoAddress {
+                currency: currency.to_string(),
+                address: cap[1].to_string(),
+            };
+            if !out.crypto.contains(&found) {
+                out.crypto.push(found);
+            }
+        }
+    }
+
+    if let Ok(selector) = Selector::parse("a[href]") {
+        for a in doc.select(&selector) {
+            let href = a.value().attr("href").unwrap_or_default().trim();
+            let is_platform = host_of(href).is_some_and(|host| {
+                DONATION_PLATFORMS
+                    .iter()
+                    .any(|p| host == *p || host.ends_with(&format!(".{p}")))
+            });
+            if is_platform && !out.platforms.iter().any(|seen| seen == href) {
+                out.platforms.push(href.to_string());
+            }
+        }
+    }
+
+    if out.crypto.is_empty() && out.platforms.is_empty() {
+        None
+    } else {
+        Some(out)
+    }
+}
+
+pub fn strip_tracking_links(doc: &mut ParsedDocument, params: &[String]) {
+    for href in doc
+        .torrent_file_links
+        .iter_mut()
+        .chain(doc.direct_download_links.iter_mut())
+        .chain(doc.links.iter_mut().map(|l| &mut l.href))
+        .chain(
+            doc.download_sections
+                .iter_mut()
+                .flat_map(|s| s.links.iter_mut().map(|l| &mut l.href)),
+        )
+    {
+        *href = strip_tracking_params(href, params);
+    }
+
+    dedupe_in_order(&mut doc.torrent_file_links);
+    dedupe_in_order(&mut doc.direct_download_links);
+    for section in &mut doc.download_sections {
+        let mut seen = BTreeSet::new();
+        section.links.retain(|l| seen.insert(l.href.clone()));
+    }
+}
+
+pub fn strip_tracking_params(href: &str, params: &[String]) -> String {
+    let mut url = match Url::parse(href) {
+        Ok(u) if u.query().is_some() => u,
+        _ => return href.to_string(),
+    };
+
+    // Filter the raw `&` segments so kept parameters keep their exact encoding.
+    let query = url.query().unwrap_or_default().to_string();
+    let segments: Vec<&str> = query.split('&').filter(|s| !s.is_empty()).collect();
+    let kept: Vec<&str> = segments
+        .iter()
+        .copied()
+        .filter(|seg| !is_tracking_param(seg.split('=').next().unwrap_or(seg), params))
+        .collect();
+
+    if kept.len() == segments.len() {
+        return href.to_string();
+    }
+
+    if kept.is_empty() {
+        url.set_query(None);
+    } else {
+        url.set_query(Some(&kept.join("&")));
+    }
+    url.to_string()
+}
+
+fn is_tracking_param(key: &str, params: &[String]) -> bool {
+    let key = key.to_ascii_lowercase();
+    params.iter().any(|p| {
+        let p = p.to_ascii_lowercase();
+        match p.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == p,
+        }
+    })
+}
+
+fn dedupe_in_order(items: &mut Vec<String>) {
+    let mut seen = BTreeSet::new();
+    items.retain(|item| seen.insert(item.clone()));
+}
+
+pub fn magnet_infohashes(magnets: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = magnets
+        .iter()
+        .filter_map(|m| Url::parse(m).ok())
+        .flat_map(|url| {
+            url.query_pairs()
+                .filter(|(key, _)| key == "xt")
+                .filter_map(|(_, value)| infohash_from_xt(&value))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    out.sort();
+    out.dedup();
+    out
+}
+
+// Accepts the hex (40 chars) and base32 (32 chars) btih forms and returns
+// lowercase hex so both spellings of the same torrent compare equal.
+fn infohash_from_xt(xt: &str) -> Option<String> {
+    let hash = xt
+        .get(..9)
+        .filter(|p| p.eq_ignore_ascii_case("urn:btih:"))
+        .map(|_| &xt[9..])?;
+    match hash.len() {
+        40 if hash.chars().all(|c| c.is_ascii_hexdigit()) => Some(hash.to_ascii_lowercase()),
+        32 => decode_base32(hash).map(hex::encode),
+        _ => None,
+    }
+}
+
+fn decode_base32(raw: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(raw.len() * 5 / 8);
+    let mut buffer: u64 = 0;
+    let mut bits = 0;
+    for c in raw.chars() {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+pub fn host_of(href: &str) -> Option<String> {
+    Url::parse(href)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_ascii_lowercase()))
+}
+
+pub fn visible_text(doc: &Html) -> String {
+    let parts: Vec<&str> = doc
+        .root_element()
+        .descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let parent = node.parent().and_then(ElementRef::wrap)?;
+            match parent.value().name() {
+                "script" | "style" | "noscript" => None,
+                _ => Some(&**text),
+            }
+        })
+        .collect();
+    normalize_ws(&parts.join(" "))
+}
+
+fn element_text(el: &ElementRef) -> String {
+    normalize_ws(&el.text().collect::<Vec<_>>().join(" "))
+}
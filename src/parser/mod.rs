@@ -1,10 +1,19 @@
+pub mod confidence;
+pub mod links;
+pub mod normalize;
 pub mod release_page;
+pub mod store;
 pub mod util;
 
 use crate::config::Config;
-use crate::model::{OutputBundle, ParseError, ParsedDocument, Stats, ToolInfo};
+use crate::model::{OutputBundle, ParseError, ParsedDocument, SCHEMA_VERSION, Stats, ToolInfo};
+use crate::{dedupe, progress, stats};
 use anyhow::{Context, Result};
+use scraper::Html;
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use tracing::{info, instrument, warn};
 
 #[instrument(level = "info", skip_all, fields(file_count = files.len()))]
@@ -13,63 +22,192 @@ pub fn parse_many(files: &[PathBuf], cfg: &Config) -> Result<OutputBundle> {
     let mut errs: Vec<ParseError> = Vec::new();
 
     for p in files {
-        match parse_one(p, cfg) {
+        progress::file_started(p);
+        let parsed = parse_file(p, cfg);
+        progress::file_done(parsed.is_err());
+        match parsed {
             Ok(doc) => docs.push(doc),
-            Err(err) => {
-                warn!(path = %p.display(), error = %format!("{err:#}"), "parse failed");
-                errs.push(ParseError {
-                    path: p.display().to_string(),
-                    error: format!("{err:#}"),
-                });
-            }
+            Err(err) => errs.push(err),
         }
     }
 
+    let parsed_ok = docs.len();
+    let discarded = dedupe::dedupe_documents(&mut docs, &cfg.dedupe, cfg.profile.utc_offset());
+    let infohash_index = if cfg.dedupe.infohash_index {
+        dedupe::infohash_index(&mut docs)
+    } else {
+        BTreeMap::new()
+    };
+
     let stats = Stats {
         input_count: files.len(),
-        parsed_ok: docs.len(),
+        parsed_ok,
         parsed_err: errs.len(),
+        duplicates_discarded: discarded.len(),
+        discarded_duplicates: discarded,
+        filtered_out: 0,
+        already_present: 0,
+        release_gaps: if cfg.scrape.release_number_gaps {
+            stats::release_gaps(&docs)
+        } else {
+            None
+        },
     };
 
     info!(?stats, "parse summary");
 
     Ok(OutputBundle {
-        tool: ToolInfo {
-            name: env!("CARGO_PKG_NAME").to_string(),
-            version: env!("CARGO_PKG_VERSION").to_string(),
-        },
+        schema_version: SCHEMA_VERSION,
+        tool: ToolInfo::current(),
         stats,
         documents: docs,
         errors: errs,
+        infohash_index,
+    })
+}
+
+// Hands each document to `emit` as soon as it is parsed instead of building a
+// bundle. Cross-document passes (dedupe, infohash index) are not run, so the
+// caller must only stream when those are not needed.
+#[instrument(level = "info", skip_all, fields(file_count = files.len()))]
+pub fn parse_streaming(
+    files: &[PathBuf],
+    cfg: &Config,
+    mut emit: impl FnMut(ParsedDocument) -> Result<ControlFlow<()>>,
+) -> Result<(Stats, Vec<ParseError>)> {
+    let mut errs: Vec<ParseError> = Vec::new();
+    let mut parsed_ok = 0;
+    let mut release_numbers = Vec::new();
+
+    // `emit` breaks to stop early; the stats then cover the inputs read so far.
+    for p in files {
+        progress::file_started(p);
+        let parsed = parse_file(p, cfg);
+        progress::file_done(parsed.is_err());
+        match parsed {
+            Ok(doc) => {
+                parsed_ok += 1;
+                release_numbers.extend(doc.release.as_ref().and_then(|r| r.release_number));
+                if emit(doc)?.is_break() {
+                    break;
+                }
+            }
+            Err(err) => errs.push(err),
+        }
+    }
+
+    let stats = Stats {
+        input_count: parsed_ok + errs.len(),
+        parsed_ok,
+        parsed_err: errs.len(),
+        duplicates_discarded: 0,
+        discarded_duplicates: vec![],
+        filtered_out: 0,
+        already_present: 0,
+        release_gaps: if cfg.scrape.release_number_gaps {
+            stats::release_number_gaps(release_numbers)
+        } else {
+            None
+        },
+    };
+    info!(?stats, "parse summary");
+    Ok((stats, errs))
+}
+
+pub fn parse_file(path: &PathBuf, cfg: &Config) -> std::result::Result<ParsedDocument, ParseError> {
+    parse_one(path, cfg).map_err(|err| {
+        warn!(path = %path.display(), error = %format!("{err:#}"), "parse failed");
+        ParseError {
+            path: path.display().to_string(),
+            error: format!("{err:#}"),
+        }
     })
 }
 
+// Time spent in each stage of `parse_one`, summed over calls; `bench`
+// reports it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StageTimings {
+    pub read: Duration,
+    pub hash: Duration,
+    pub dom: Duration,
+    pub extract: Duration,
+}
+
+// Magnet URIs and torrent URLs of one file; see `release_page::download_links`.
 #[instrument(level = "debug", skip_all, fields(path = %path.display()))]
-fn parse_one(path: &PathBuf, cfg: &Config) -> Result<ParsedDocument> {
+pub fn extract_download_links(path: &PathBuf, cfg: &Config) -> Result<(Vec<String>, Vec<String>)> {
+    let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    let html = String::from_utf8(bytes).context("input is not valid UTF-8")?;
+    Ok(release_page::download_links(
+        &Html::parse_document(&html),
+        cfg,
+    ))
+}
+
+pub fn parse_one(path: &PathBuf, cfg: &Config) -> Result<ParsedDocument> {
+    parse_timed(path, cfg, &mut StageTimings::default())
+}
+
+pub fn parse_timed(
+    path: &PathBuf,
+    cfg: &Config,
+    timings: &mut StageTimings,
+) -> Result<ParsedDocument> {
+    let started = Instant::now();
     let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
     let bytes_len = bytes.len() as u64;
+    let mtime = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    let hashing = Instant::now();
+    timings.read += hashing - started;
     let sha256 = util::sha256_hex(&bytes);
+    let decoding = Instant::now();
+    timings.hash += decoding - hashing;
 
     let html = String::from_utf8(bytes).context("input is not valid UTF-8")?;
 
     let is_wp_release = cfg.profile.wordpress_release_layout
         && html.contains("article id=\"post-")
         && html.contains("entry-content");
+    let dom = Html::parse_document(&html);
+    let extracting = Instant::now();
+    timings.dom += extracting - decoding;
 
     let mut doc = if is_wp_release {
-        release_page::parse_wordpress_release(&html, cfg).context("wordpress-release parse")?
+        release_page::parse_wordpress_release(&dom, cfg).context("wordpress-release parse")?
     } else {
-        release_page::parse_generic(&html, cfg).context("generic parse")?
+        release_page::parse_generic(&dom, cfg).context("generic parse")?
     };
 
     doc.source.path = path.display().to_string();
     doc.source.bytes = bytes_len;
     doc.source.sha256 = sha256;
+    doc.source.mtime = mtime;
     doc.site = if is_wp_release {
         "wordpress_release".to_string()
     } else {
         "generic".to_string()
     };
+    if cfg.links.strip_tracking_params {
+        links::strip_tracking_links(&mut doc, &cfg.links.tracking_params);
+    }
+    if cfg.normalize.enabled {
+        doc = normalize::normalize_document(&doc, &cfg.normalize).context("normalize text")?;
+    }
+    if cfg.normalize.transliterate_cyrillic {
+        normalize::add_latin_transliterations(&mut doc);
+    }
+    if cfg.scrape.doc_id {
+        doc.doc_id = Some(dedupe::doc_id(&doc));
+    }
+    if cfg.scrape.confidence {
+        doc.confidence = confidence::score(&doc);
+    }
+    timings.extract += extracting.elapsed();
 
     Ok(doc)
 }
@@ -0,0 +1,586 @@
+use crate::config::NormalizeConfig;
+use crate::model::{ContentLanguage, Language, ParsedDocument, VersionInfo};
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+static RE_MULTI: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^multi\s*-?\s*(\d+)?$").expect("valid regex"));
+static RE_LANGUAGE_SPLIT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\s*(?:[/,;+&|()\[\]]|\band\b)\s*").expect("valid regex"));
+
+const LANGUAGES: &[(&str, &str, &[&str])] = &[
+    ("English", "en", &["eng", "en", "english"]),
+    ("Russian", "ru", &["rus", "ru", "russian"]),
+    ("German", "de", &["ger", "deu", "de", "german"]),
+    ("French", "fr", &["fre", "fra", "fr", "french"]),
+    ("Spanish", "es", &["spa", "esp", "es", "spanish"]),
+    ("Italian", "it", &["ita", "it", "italian"]),
+    (
+        "Portuguese",
+        "pt",
+        &["por", "bra", "pt", "br", "ptbr", "portuguese", "brazilian"],
+    ),
+    ("Polish", "pl", &["pol", "pl", "polish"]),
+    ("Japanese", "ja", &["jap", "jpn", "ja", "jp", "japanese"]),
+    ("Korean", "ko", &["kor", "ko", "korean"]),
+    (
+        "Chinese",
+        "zh",
+        &["chi", "chn", "zho", "zh", "cn", "chs", "cht", "chinese"],
+    ),
+    ("Turkish", "tr", &["tur", "tr", "turkish"]),
+    ("Ukrainian", "uk", &["ukr", "ua", "ukrainian"]),
+    ("Czech", "cs", &["cze", "ces", "cz", "czech"]),
+    ("Hungarian", "hu", &["hun", "hu", "hungarian"]),
+    ("Dutch", "nl", &["dut", "nld", "nl", "dutch"]),
+    ("Swedish", "sv", &["swe", "sv", "swedish"]),
+    ("Norwegian", "no", &["nor", "norwegian"]),
+    ("Danish", "da", &["dan", "da", "danish"]),
+    ("Finnish", "fi", &["fin", "fi", "finnish"]),
+    ("Arabic", "ar", &["ara", "ar", "arabic"]),
+    ("Thai", "th", &["tha", "th", "thai"]),
+    ("Greek", "el", &["gre", "ell", "el", "greek"]),
+    ("Romanian", "ro", &["rom", "ron", "ro", "romanian"]),
+    ("Vietnamese", "vi", &["vie", "vi", "vietnamese"]),
+    ("Indonesian", "id", &["ind", "indonesian"]),
+    ("Hebrew", "he", &["heb", "he", "hebrew"]),
+];
+
+static RE_RELEASE_PREFIX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*#\s*\d+\s*").expect("valid regex"));
+static RE_NAME_TAIL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:\s+[–—-]\s+|,\s*v\s*\d|\s+v\s*\d|\s+build\s+\d|\s+\+\s+|\s*[\[(]).*$")
+        .expect("valid regex")
+});
+static RE_EDITION_MARKERS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(?:game\s+of\s+the\s+year|goty|deluxe|digital\s+deluxe|complete|definitive|ultimate|gold|premium|collector'?s|enhanced|anniversary|legendary|standard)(?:\s+edition)?\b|\bedition\b",
+    )
+    .expect("valid regex")
+});
+
+static RE_VERSION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\bv(?:er(?:sion)?)?\.?\s*(\d+(?:\.\d+)*)([a-z]?)\b(?:\s*[-_ ]?\s*(hotfix\s*\d*|beta\s*\d*|alpha\s*\d*|rc\s*\d*|patch\s*\d*)\b)?",
+    )
+    .expect("valid regex")
+});
+static RE_BUILD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bbuild\s*#?\s*(\d+)\b").expect("valid regex"));
+
+static RE_SIZE_TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+(?:[.,]\d+)*)\s*(tib|gib|mib|kib|tb|gb|mb|kb|bytes|b|тб|гб|мб|кб)?\b")
+        .expect("valid regex")
+});
+
+pub fn map_genres(categories: &[String], genre_map: &BTreeMap<String, String>) -> Vec<String> {
+    let lookup: BTreeMap<String, &String> = genre_map
+        .iter()
+        .map(|(raw, genre)| (raw.trim().to_lowercase(), genre))
+        .collect();
+
+    let mut out: Vec<String> = categories
+        .iter()
+        .filter_map(|category| {
+            let key = category.trim().to_lowercase();
+            if let Some(genre) = lookup.get(&key) {
+                return Some((*genre).clone());
+            }
+            let stem = key
+                .strip_suffix(" games")
+                .or_else(|| key.strip_suffix(" game"))?;
+            lookup
+                .get(stem)
+                .map(|g| (*g).clone())
+                .or_else(|| Some(title_case(stem)))
+        })
+        .collect();
+
+    out.sort();
+    out.dedup();
+    out
+}
+
+pub fn canonicalize_tags(tags: &[String], aliases: &BTreeMap<String, String>) -> Vec<String> {
+    let lookup: BTreeMap<String, &String> = aliases
+        .iter()
+        .map(|(alias, canonical)| (tag_key(alias), canonical))
+        .collect();
+
+    let mut out: Vec<String> = tags
+        .iter()
+        .map(|tag| {
+            lookup
+                .get(&tag_key(tag))
+                .map(|c| (*c).clone())
+                .unwrap_or_else(|| tag.trim().to_string())
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    out.sort();
+    out.dedup();
+    out
+}
+
+// "Open-World", "open_world" and "open world" all look up the same alias.
+fn tag_key(tag: &str) -> String {
+    tag.to_lowercase()
+        .replace(['-', '_'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn game_name(title_line: &str) -> String {
+    let without_number = RE_RELEASE_PREFIX.replace(title_line, "");
+    let name = RE_NAME_TAIL.replace(&without_number, "");
+    name.trim().to_string()
+}
+
+pub fn title_slug(name: &str) -> String {
+    let folded: String = name
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase();
+    let stripped = RE_EDITION_MARKERS.replace_all(&folded, " ");
+
+    stripped
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+pub fn parse_version(text: &str) -> Option<VersionInfo> {
+    let build = RE_BUILD
+        .captures(text)
+        .and_then(|cap| cap[1].parse::<u64>().ok());
+
+    let best = RE_VERSION
+        .captures_iter(text)
+        .map(|cap| {
+            let mut suffix: Vec<String> = Vec::new();
+            if let Some(letter) = cap.get(2).filter(|m| !m.as_str().is_empty()) {
+                suffix.push(letter.as_str().to_ascii_lowercase());
+            }
+            if let Some(tag) = cap.get(3) {
+                suffix.push(
+                    tag.as_str()
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .to_lowercase(),
+                );
+            }
+            VersionInfo {
+                raw: cap[0].trim().to_string(),
+                components: cap[1]
+                    .split('.')
+                    .filter_map(|part| part.parse::<u64>().ok())
+                    .collect(),
+                build,
+                suffix: if suffix.is_empty() {
+                    None
+                } else {
+                    Some(suffix.join(" "))
+                },
+            }
+        })
+        .max_by(compare_versions);
+
+    best.or_else(|| {
+        let cap = RE_BUILD.captures(text)?;
+        Some(VersionInfo {
+            raw: cap[0].trim().to_string(),
+            components: vec![],
+            build,
+            suffix: None,
+        })
+    })
+}
+
+pub fn compare_versions(a: &VersionInfo, b: &VersionInfo) -> Ordering {
+    let len = a.components.len().max(b.components.len());
+    for idx in 0..len {
+        let x = a.components.get(idx).copied().unwrap_or(0);
+        let y = b.components.get(idx).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+
+    a.build
+        .cmp(&b.build)
+        .then_with(|| suffix_rank(a.suffix.as_deref()).cmp(&suffix_rank(b.suffix.as_deref())))
+}
+
+fn suffix_rank(suffix: Option<&str>) -> (u8, String) {
+    match suffix {
+        None => (2, String::new()),
+        Some(s) if s.starts_with("alpha") => (0, s.to_string()),
+        Some(s) if s.starts_with("beta") || s.starts_with("rc") => (1, s.to_string()),
+        Some(s) => (3, s.to_string()),
+    }
+}
+
+// Detection on the first few thousand characters is as accurate as on the
+// whole post and keeps large comment sections from dominating.
+const LANGUAGE_SAMPLE_CHARS: usize = 4000;
+
+pub fn detect_language(text: &str) -> Option<ContentLanguage> {
+    let sample: String = text.chars().take(LANGUAGE_SAMPLE_CHARS).collect();
+    let info = whatlang::detect(&sample)?;
+    Some(ContentLanguage {
+        code: info.lang().code().to_string(),
+        name: info.lang().eng_name().to_string(),
+        script: info.script().name().to_string(),
+        confidence: (info.confidence() * 100.0).round() / 100.0,
+        reliable: info.is_reliable(),
+    })
+}
+
+pub fn parse_languages(raw: &str) -> (Vec<Language>, Option<u32>) {
+    let mut languages: Vec<Language> = Vec::new();
+    let mut multi_count = None;
+
+    for token in RE_LANGUAGE_SPLIT.split(raw) {
+        let token = token.trim().trim_matches('.');
+        if token.is_empty() {
+            continue;
+        }
+
+        if let Some(cap) = RE_MULTI.captures(token) {
+            multi_count = cap.get(1).and_then(|m| m.as_str().parse::<u32>().ok());
+            continue;
+        }
+
+        let key = token.to_lowercase().replace(['-', ' '], "");
+        let found = LANGUAGES
+            .iter()
+            .find(|(_, _, aliases)| aliases.contains(&key.as_str()));
+        if let Some((name, iso, _)) = found {
+            let lang = Language {
+                name: name.to_string(),
+                iso639_1: iso.to_string(),
+            };
+            if !languages.contains(&lang) {
+                languages.push(lang);
+            }
+        }
+    }
+
+    (languages, multi_count)
+}
+
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%d %H:%M:%S%z",
+    "%a, %d %b %Y %H:%M:%S %z",
+];
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d.%m.%Y %H:%M",
+    "%B %d, %Y %I:%M %p",
+];
+const NAIVE_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%B %d, %Y", "%b %d, %Y", "%d.%m.%Y", "%d/%m/%Y"];
+
+// `+HH:MM`, `-HH:MM`, `HH:MM`, `Z` or `UTC`; None for anything else.
+pub fn parse_utc_offset(raw: &str) -> Option<FixedOffset> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("z") || raw.eq_ignore_ascii_case("utc") || raw.is_empty() {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, rest) = if let Some(rest) = raw.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = raw.strip_prefix('-') {
+        (-1, rest)
+    } else {
+        (1, raw)
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let digits = |s: &str| !s.is_empty() && s.len() <= 2 && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(hours) || !digits(minutes) {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+pub fn parse_datetime(raw: &str, default_offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    let raw = raw.trim();
+
+    if let Ok(ts) = DateTime::parse_from_rfc3339(raw) {
+        return Some(ts);
+    }
+    if let Some(ts) = DATETIME_FORMATS
+        .iter()
+        .find_map(|fmt| DateTime::parse_from_str(raw, fmt).ok())
+    {
+        return Some(ts);
+    }
+
+    let naive = NAIVE_DATETIME_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(raw, fmt).ok())
+        .or_else(|| {
+            NAIVE_DATE_FORMATS
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(raw, fmt).ok())
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })?;
+    default_offset.from_local_datetime(&naive).single()
+}
+
+pub fn parse_size_range(raw: &str) -> Option<(u64, u64)> {
+    let mut sizes: Vec<u64> = Vec::new();
+    let mut pending: Vec<f64> = Vec::new();
+
+    for cap in RE_SIZE_TOKEN.captures_iter(raw) {
+        let value = match parse_decimal(&cap[1]) {
+            Some(v) => v,
+            None => continue,
+        };
+        match cap.get(2).and_then(|m| unit_multiplier(m.as_str())) {
+            Some(mult) => {
+                for v in pending.drain(..) {
+                    sizes.push((v * mult).round() as u64);
+                }
+                sizes.push((value * mult).round() as u64);
+            }
+            None => pending.push(value),
+        }
+    }
+
+    let min = *sizes.iter().min()?;
+    let max = *sizes.iter().max()?;
+    Some((min, max))
+}
+
+fn unit_multiplier(unit: &str) -> Option<f64> {
+    let mult = match unit.to_lowercase().as_str() {
+        "b" | "bytes" => 1.0,
+        "kb" | "kib" | "кб" => 1024.0,
+        "mb" | "mib" | "мб" => 1024.0 * 1024.0,
+        "gb" | "gib" | "гб" => 1024.0 * 1024.0 * 1024.0,
+        "tb" | "tib" | "тб" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(mult)
+}
+
+fn parse_decimal(raw: &str) -> Option<f64> {
+    let thousands_grouped = !raw.contains('.')
+        && raw.contains(',')
+        && raw.split(',').skip(1).all(|group| group.len() == 3);
+
+    let normalized = match raw.rfind([',', '.']) {
+        Some(_) if thousands_grouped => raw.replace(',', ""),
+        Some(pos) => format!("{}.{}", raw[..pos].replace([',', '.'], ""), &raw[pos + 1..]),
+        None => raw.to_string(),
+    };
+    normalized.parse::<f64>().ok()
+}
+
+fn title_case(s: &str) -> String {
+    s.split_whitespace()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn normalize_text(s: &str, cfg: &NormalizeConfig) -> String {
+    let composed: String = if cfg.nfc {
+        s.nfc().collect()
+    } else {
+        s.to_string()
+    };
+
+    let mut out = String::with_capacity(composed.len());
+    for c in composed.chars() {
+        if cfg.strip_zero_width
+            && matches!(
+                c,
+                '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'
+            )
+        {
+            continue;
+        }
+        if cfg.ascii_punctuation {
+            match c {
+                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => out.push('\''),
+                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}'
+                | '\u{00BB}' => out.push('"'),
+                '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
+                | '\u{2212}' => out.push('-'),
+                '\u{2026}' => out.push_str("..."),
+                '\u{00A0}' | '\u{2007}' | '\u{2009}' | '\u{202F}' => out.push(' '),
+                _ => out.push(c),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// Skipped at any depth: raw input, and URLs, hashes and addresses that have to
+// stay byte-for-byte what the page had.
+const NORMALIZE_SKIPPED_FIELDS: &[&str] = &[
+    "source",
+    "raw_html",
+    "provenance",
+    "href",
+    "url",
+    "src",
+    "canonical_url",
+    "cover_url",
+    "shortlink",
+    "favicon",
+    "apple_touch_icon",
+    "hreflang",
+    "links",
+    "magnet_links",
+    "torrent_file_links",
+    "direct_download_links",
+    "infohashes",
+    "shares_payload_with",
+    "address",
+];
+
+pub fn normalize_document(doc: &ParsedDocument, cfg: &NormalizeConfig) -> Result<ParsedDocument> {
+    let mut value = serde_json::to_value(doc)?;
+    normalize_value(&mut value, cfg);
+    Ok(serde_json::from_value(value)?)
+}
+
+fn normalize_value(value: &mut Value, cfg: &NormalizeConfig) {
+    match value {
+        Value::String(s) => *s = normalize_text(s, cfg),
+        Value::Array(items) => items.iter_mut().for_each(|v| normalize_value(v, cfg)),
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                if !NORMALIZE_SKIPPED_FIELDS.contains(&key.as_str()) {
+                    normalize_value(field, cfg);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn add_latin_transliterations(doc: &mut ParsedDocument) {
+    if let Some(post) = doc.post.as_mut() {
+        post.entry_title_latin = post.entry_title.as_deref().and_then(transliterate_cyrillic);
+        post.categories_latin = transliterate_list(&post.categories);
+    }
+    if let Some(release) = doc.release.as_mut() {
+        release.game_title_line_latin = release
+            .game_title_line
+            .as_deref()
+            .and_then(transliterate_cyrillic);
+        release.genres_tags_latin = transliterate_list(&release.genres_tags);
+    }
+}
+
+fn transliterate_list(items: &[String]) -> Vec<String> {
+    if !items.iter().any(|s| has_cyrillic(s)) {
+        return vec![];
+    }
+    items
+        .iter()
+        .map(|s| transliterate_cyrillic(s).unwrap_or_else(|| s.clone()))
+        .collect()
+}
+
+fn has_cyrillic(s: &str) -> bool {
+    s.chars().any(|c| matches!(c, '\u{0400}'..='\u{04FF}'))
+}
+
+// Returns None when there is nothing to transliterate, so `*_latin` fields
+// only appear for Cyrillic input.
+pub fn transliterate_cyrillic(s: &str) -> Option<String> {
+    if !has_cyrillic(s) {
+        return None;
+    }
+
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        let latin = match lower {
+            'а' => "a",
+            'б' => "b",
+            'в' => "v",
+            'г' | 'ґ' => "g",
+            'д' => "d",
+            'е' => "e",
+            'ё' => "yo",
+            'є' => "ye",
+            'ж' => "zh",
+            'з' => "z",
+            'и' => "i",
+            'і' => "i",
+            'ї' => "yi",
+            'й' => "y",
+            'к' => "k",
+            'л' => "l",
+            'м' => "m",
+            'н' => "n",
+            'о' => "o",
+            'п' => "p",
+            'р' => "r",
+            'с' => "s",
+            'т' => "t",
+            'у' => "u",
+            'ф' => "f",
+            'х' => "kh",
+            'ц' => "ts",
+            'ч' => "ch",
+            'ш' => "sh",
+            'щ' => "shch",
+            'ъ' | 'ь' => "",
+            'ы' => "y",
+            'э' => "e",
+            'ю' => "yu",
+            'я' => "ya",
+            _ => {
+                out.push(c);
+                continue;
+            }
+        };
+        if c != lower {
+            let mut chars = latin.chars();
+            if let Some(first) = chars.next() {
+                out.extend(first.to_uppercase());
+                out.push_str(chars.as_str());
+            }
+        } else {
+            out.push_str(latin);
+        }
+    }
+    Some(out)
+}
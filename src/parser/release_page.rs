@@ -1,11 +1,18 @@
 use crate::config::Config;
-use crate::model::{PageMeta, ParsedDocument, PostMeta, ReleaseMeta, SourceInfo, SpoilerSection};
-use crate::parser::util::{bump_domain_count, normalize_ws};
+use crate::model::{
+    Breadcrumb, Comment, CommentThread, FeedLink, ImageAsset, PageMeta, ParsedDocument, Poll,
+    PollOption, PostMeta, ReleaseMeta, SourceInfo, SpoilerSection,
+};
+use crate::parser::util::{
+    bump_domain_count, find_json_ld_type, json_ld_values, normalize_ws, select_all_text,
+    select_attr, select_text, select_text_lines,
+};
+use crate::parser::{links, normalize, store};
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use scraper::{Html, Selector};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use tracing::{debug, instrument, warn};
 use url::Url;
 
@@ -13,62 +20,156 @@ static RE_POST_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"post-(\d+)").expect("
 static RE_RELEASE_NO: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"#\s*(\d{1,6})").expect("valid regex"));
 static RE_FIRST_INT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)").expect("valid regex"));
+static RE_AGE_RATING_TEXT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(ESRB|PEGI|USK|CERO|ACB)\s*(?:rating)?\s*[:\-]?\s*(Everyone\s*10\+|Everyone|Teen|Mature\s*17\+|Mature|Adults\s*Only|E10\+|EC|AO|RP|[ETM]|[A-DZ]|R18\+|MA15\+|\d{1,2}\+?)(?:\b|$)")
+        .expect("valid regex")
+});
+static RE_AGE_RATING_ASSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(esrb|pegi|usk)[_\-/ ]?(?:rating[_\-/]?)?(e10|ec|ao|rp|[etm]|\d{1,2})\b")
+        .expect("valid regex")
+});
+static RE_COMMENT_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"comment-(\d+)").expect("valid regex"));
+static RE_VOTED_TOTAL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d[\d,\s]*)\s*(?:users?|people|visitors?)?\s*(?:have\s+)?voted")
+        .expect("valid regex")
+});
+static RE_TOTAL_VOTERS: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)total\s+vot(?:es|ers)\s*:?\s*(\d[\d,]*)").expect("valid regex"));
+static RE_OPTION_VOTES: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:(\d+(?:[.,]\d+)?)\s*%)?[,\s]*(?:(\d[\d,]*)\s*votes?)?")
+        .expect("valid regex")
+});
+static RE_WP_THEME_PATH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"/wp-content/themes/([A-Za-z0-9_.-]+)/").expect("valid regex"));
 
 #[instrument(level = "debug", skip_all)]
-pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocument> {
-    let doc = Html::parse_document(html);
+pub fn parse_wordpress_release(doc: &Html, cfg: &Config) -> Result<ParsedDocument> {
+    let base = if cfg.links.resolve_relative {
+        links::document_base_url(doc)
+    } else {
+        None
+    };
 
     let mut out = ParsedDocument {
+        doc_id: None,
         source: SourceInfo {
             path: String::new(),
             bytes: 0,
             sha256: String::new(),
+            mtime: None,
         },
         site: "wordpress_release".to_string(),
+        warnings: vec![],
         page: None,
         post: None,
         release: None,
+        pricing: None,
+        content_language: None,
+        breadcrumbs: vec![],
         spoiler_sections: vec![],
+        comments: None,
+        poll: None,
+        direct_download_links: vec![],
+        site_donations: None,
         link_domain_counts: BTreeMap::new(),
+        link_profile: None,
+        link_section_counts: BTreeMap::new(),
+        links: vec![],
+        link_category_counts: BTreeMap::new(),
         download_section_headings: vec![],
+        download_sections: vec![],
         torrent_file: None,
         torrent_file_names: vec![],
         torrent_file_links: vec![],
         magnet_links: vec![],
+        infohashes: vec![],
+        shares_payload_with: vec![],
+        images: vec![],
+        raw_html: BTreeMap::new(),
+        confidence: None,
+        redacted: None,
+        provenance: BTreeMap::new(),
+        history: vec![],
+        enrichment: None,
     };
 
-    if cfg.scrape.page_title || cfg.scrape.canonical_url || cfg.scrape.meta_tags {
+    if cfg.scrape.page_title
+        || cfg.scrape.canonical_url
+        || cfg.scrape.meta_tags
+        || cfg.scrape.head_links
+        || cfg.scrape.hreflang
+        || cfg.scrape.generator
+    {
         let mut page = PageMeta {
             title: None,
             canonical_url: None,
             meta: BTreeMap::new(),
+            favicon: None,
+            apple_touch_icon: None,
+            feeds: vec![],
+            shortlink: None,
+            hreflang: BTreeMap::new(),
+            generator: None,
+            theme: None,
         };
 
         if cfg.scrape.page_title {
-            page.title = select_text(&doc, "head > title");
+            page.title = select_text(doc, "head > title");
         }
         if cfg.scrape.canonical_url {
-            page.canonical_url = select_attr(&doc, "link[rel='canonical']", "href");
+            page.canonical_url = select_attr(doc, "link[rel='canonical']", "href");
         }
         if cfg.scrape.meta_tags {
-            page.meta = extract_meta_tags(&doc);
+            page.meta = extract_meta_tags(doc);
+        }
+        if cfg.scrape.head_links {
+            page.favicon = select_attr(doc, "head link[rel~='icon']", "href");
+            page.apple_touch_icon = select_attr(doc, "head link[rel~='apple-touch-icon']", "href");
+            page.feeds = extract_feed_links(doc);
+            page.shortlink = select_attr(doc, "head link[rel~='shortlink']", "href");
+        }
+        if cfg.scrape.hreflang {
+            page.hreflang = extract_hreflang(doc);
+        }
+        if cfg.scrape.generator {
+            page.generator = select_attr(doc, "meta[name='generator']", "content")
+                .map(|s| normalize_ws(&s))
+                .filter(|s| !s.is_empty());
+            page.theme = detect_wp_theme(doc);
         }
 
         out.page = Some(page);
     }
 
+    if cfg.scrape.breadcrumbs {
+        out.breadcrumbs = extract_breadcrumbs(doc);
+    }
+
     let mut post = PostMeta {
         post_id: None,
+        slug: None,
+        shortlink: None,
         categories: vec![],
+        categories_latin: vec![],
+        genres: vec![],
         wp_tags: vec![],
+        wp_tags_canonical: vec![],
         entry_title: None,
+        entry_title_latin: None,
         entry_datetime: None,
+        entry_datetime_rfc3339: None,
+        entry_datetime_epoch: None,
+        published: None,
+        modified: None,
+        modified_rfc3339: None,
+        modified_epoch: None,
         author: None,
         comments_count: None,
     };
 
     if cfg.scrape.post_id || cfg.scrape.wp_tags {
-        if let Some(article) = select_attr(&doc, "article[id^='post-']", "id") {
+        if let Some(article) = select_attr(doc, "article[id^='post-']", "id") {
             if cfg.scrape.post_id {
                 if let Some(cap) = RE_POST_ID.captures(&article) {
                     post.post_id = cap.get(1).and_then(|m| m.as_str().parse::<u64>().ok());
@@ -77,7 +178,7 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
         }
 
         if cfg.scrape.wp_tags {
-            if let Some(class_attr) = select_attr(&doc, "article[id^='post-']", "class") {
+            if let Some(class_attr) = select_attr(doc, "article[id^='post-']", "class") {
                 for tok in class_attr.split_whitespace() {
                     if let Some(tag) = tok.strip_prefix("tag-") {
                         post.wp_tags.push(tag.to_string());
@@ -89,21 +190,87 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
         }
     }
 
-    if cfg.scrape.categories {
-        post.categories = select_all_text(&doc, "span.cat-links a");
+    if cfg.scrape.slug {
+        post.slug = select_attr(doc, "link[rel='canonical']", "href")
+            .as_deref()
+            .and_then(slug_from_url);
+        post.shortlink = select_attr(doc, "link[rel~='shortlink']", "href");
+    }
+
+    if cfg.scrape.categories || cfg.scrape.genre_taxonomy {
+        let categories = select_all_text(doc, "span.cat-links a");
+        if cfg.scrape.genre_taxonomy {
+            post.genres = normalize::map_genres(&categories, &cfg.taxonomy.genres);
+        }
+        if cfg.scrape.categories {
+            post.categories = categories;
+        }
     }
     if cfg.scrape.entry_title {
-        post.entry_title = select_text(&doc, "h1.entry-title");
+        post.entry_title = select_text(doc, "h1.entry-title");
     }
     if cfg.scrape.entry_datetime {
-        post.entry_datetime = select_attr(&doc, "time.entry-date", "datetime")
-            .or_else(|| select_text(&doc, "time.entry-date"));
+        let entry_datetime = select_attr(doc, "time.entry-date", "datetime")
+            .map(|v| (v, "css:time.entry-date@datetime"))
+            .or_else(|| select_text(doc, "time.entry-date").map(|v| (v, "css:time.entry-date")));
+        if let Some((value, source)) = entry_datetime {
+            post.entry_datetime = Some(value);
+            explain(&mut out, cfg, "post.entry_datetime", source);
+        }
+        if let Some((value, source)) = select_first_attr(
+            doc,
+            &[
+                ("time.entry-date[datetime]", "datetime"),
+                ("meta[property='article:published_time']", "content"),
+            ],
+        ) {
+            post.published = Some(value);
+            explain(&mut out, cfg, "post.published", &source);
+        }
+    }
+    if cfg.scrape.entry_modified
+        && let Some((value, source)) = select_first_attr(
+            doc,
+            &[
+                ("meta[property='article:modified_time']", "content"),
+                ("time.updated[datetime]", "datetime"),
+                ("meta[property='og:updated_time']", "content"),
+            ],
+        )
+    {
+        post.modified = Some(value);
+        explain(&mut out, cfg, "post.modified", &source);
+    }
+    if cfg.scrape.datetime_normalize {
+        let offset = cfg.profile.utc_offset();
+        if let Some(raw) = post.entry_datetime.as_deref() {
+            match normalize::parse_datetime(raw, offset) {
+                Some(ts) => {
+                    post.entry_datetime_rfc3339 = Some(ts.to_rfc3339());
+                    post.entry_datetime_epoch = Some(ts.timestamp());
+                }
+                None => out
+                    .warnings
+                    .push(format!("could not normalize entry datetime {raw:?}")),
+            }
+        }
+        if let Some(raw) = post.modified.as_deref() {
+            match normalize::parse_datetime(raw, offset) {
+                Some(ts) => {
+                    post.modified_rfc3339 = Some(ts.to_rfc3339());
+                    post.modified_epoch = Some(ts.timestamp());
+                }
+                None => out
+                    .warnings
+                    .push(format!("could not normalize modified time {raw:?}")),
+            }
+        }
     }
     if cfg.scrape.author {
-        post.author = select_text(&doc, "span.author a");
+        post.author = select_text(doc, "span.author a");
     }
     if cfg.scrape.comments_count {
-        let raw = select_text(&doc, "span.tolstoycomments-cc");
+        let raw = select_text(doc, "span.tolstoycomments-cc");
         post.comments_count = raw
             .as_deref()
             .and_then(|s| RE_FIRST_INT.captures(s))
@@ -111,20 +278,42 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
             .and_then(|m| m.as_str().parse::<u64>().ok());
     }
 
+    if cfg.scrape.tag_aliases {
+        post.wp_tags_canonical =
+            normalize::canonicalize_tags(&post.wp_tags, &cfg.taxonomy.tag_aliases);
+    }
+
     out.post = Some(post);
 
     let mut release = ReleaseMeta {
         release_number: None,
         game_title_line: None,
+        game_title_line_latin: None,
+        group: None,
+        title_slug: None,
+        version: None,
         genres_tags: vec![],
+        genres_tags_canonical: vec![],
+        genres_tags_latin: vec![],
         companies: vec![],
+        developers: vec![],
+        publishers: vec![],
         languages_raw: None,
+        languages: vec![],
+        multi_count: None,
         original_size_raw: None,
         repack_size_raw: None,
+        original_size_bytes: None,
+        repack_size_bytes_min: None,
+        repack_size_bytes_max: None,
+        age_ratings: BTreeMap::new(),
+        steam_appids: vec![],
+        gog_ids: vec![],
+        review_scores: vec![],
     };
 
     if cfg.scrape.game_title_line || cfg.scrape.release_number {
-        let h3 = select_text(&doc, "div.entry-content > h3");
+        let h3 = select_text(doc, "div.entry-content > h3");
         if cfg.scrape.game_title_line {
             release.game_title_line = h3.clone();
         }
@@ -138,6 +327,65 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
         }
     }
 
+    let title_line = select_text(doc, "div.entry-content > h3")
+        .map(|line| (line, "css:div.entry-content > h3"))
+        .or_else(|| select_text(doc, "h1.entry-title").map(|line| (line, "css:h1.entry-title")));
+
+    if cfg.scrape.title_slug
+        && let Some((line, source)) = &title_line
+    {
+        release.title_slug = Some(normalize::title_slug(&normalize::game_name(line)))
+            .filter(|slug| !slug.is_empty());
+        if release.title_slug.is_some() {
+            explain(&mut out, cfg, "release.title_slug", source);
+        }
+    }
+
+    if cfg.scrape.version
+        && let Some((line, source)) = &title_line
+    {
+        release.version = normalize::parse_version(line);
+        if release.version.is_some() {
+            explain(&mut out, cfg, "release.version", source);
+        }
+    }
+
+    if cfg.scrape.release_group {
+        let candidates = [
+            (
+                select_text(doc, "div.entry-content > h3"),
+                "css:div.entry-content > h3",
+            ),
+            (select_text(doc, "h1.entry-title"), "css:h1.entry-title"),
+            (
+                select_attr(doc, "meta[property='og:site_name']", "content"),
+                "css:meta[property='og:site_name']@content",
+            ),
+            (select_text(doc, "head > title"), "css:head > title"),
+            (
+                select_attr(doc, "link[rel='canonical']", "href"),
+                "css:link[rel='canonical']@href",
+            ),
+            (
+                select_text(doc, "footer, #colophon, .site-info"),
+                "css:footer, #colophon, .site-info",
+            ),
+        ];
+        if let Some((group, source)) = candidates.iter().find_map(|(text, source)| {
+            text.as_deref()
+                .and_then(|t| detect_release_group(t, &cfg.profile.release_groups))
+                .map(|group| (group, *source))
+        }) {
+            release.group = Some(group);
+            explain(
+                &mut out,
+                cfg,
+                "release.group",
+                &format!("{source} + profile:release_groups"),
+            );
+        }
+    }
+
     if cfg.scrape.genres_tags
         || cfg.scrape.companies
         || cfg.scrape.languages
@@ -145,7 +393,7 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
         || cfg.scrape.repack_size
     {
         if let Some(p_html) =
-            find_first_paragraph_html_containing(&doc, "div.entry-content p", "Genres/Tags:")
+            find_first_paragraph_html_containing(doc, "div.entry-content p", "Genres/Tags:")
         {
             if cfg.scrape.genres_tags {
                 release.genres_tags =
@@ -178,24 +426,154 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
             }
         } else {
             warn!("could not find Genres/Tags paragraph; release metadata may be partial");
+            out.warnings.push(
+                "could not find Genres/Tags paragraph; release metadata may be partial".to_string(),
+            );
         }
     }
 
+    if cfg.scrape.developers_publishers {
+        let (developers, publishers) = extract_developers_publishers(
+            doc,
+            "div.entry-content",
+            &cfg.profile.developer_selectors,
+            &cfg.profile.publisher_selectors,
+        );
+        release.developers = developers;
+        release.publishers = publishers;
+    }
+
+    if cfg.scrape.languages_parsed
+        && let Some(raw) = release.languages_raw.as_deref()
+    {
+        let (languages, multi_count) = normalize::parse_languages(raw);
+        release.languages = languages;
+        release.multi_count = multi_count;
+    }
+
+    if cfg.scrape.size_bytes {
+        if let Some(raw) = release.original_size_raw.as_deref() {
+            match normalize::parse_size_range(raw) {
+                Some((_, max)) => release.original_size_bytes = Some(max),
+                None => out
+                    .warnings
+                    .push(format!("could not parse original size {raw:?}")),
+            }
+        }
+        if let Some(raw) = release.repack_size_raw.as_deref() {
+            match normalize::parse_size_range(raw) {
+                Some((min, max)) => {
+                    release.repack_size_bytes_min = Some(min);
+                    release.repack_size_bytes_max = Some(max);
+                }
+                None => out
+                    .warnings
+                    .push(format!("could not parse repack size {raw:?}")),
+            }
+        }
+    }
+
+    if cfg.scrape.age_ratings {
+        release.age_ratings = extract_age_ratings(doc, "div.entry-content");
+    }
+    if cfg.scrape.steam_appids {
+        release.steam_appids = links::extract_steam_appids(doc, "div.entry-content a[href]");
+    }
+    if cfg.scrape.gog_ids {
+        release.gog_ids = links::extract_gog_ids(doc, "div.entry-content a[href]");
+    }
+    if cfg.scrape.review_scores {
+        release.review_scores = links::extract_review_scores(doc, "div.entry-content");
+    }
+
+    if cfg.scrape.tag_aliases {
+        release.genres_tags_canonical =
+            normalize::canonicalize_tags(&release.genres_tags, &cfg.taxonomy.tag_aliases);
+    }
+
     out.release = Some(release);
 
+    if cfg.scrape.content_language {
+        out.content_language = select_text(doc, "div.entry-content")
+            .as_deref()
+            .and_then(normalize::detect_language);
+    }
+
     if cfg.scrape.spoiler_sections {
-        out.spoiler_sections = extract_spoilers(&doc, &cfg.profile.spoiler_denylist);
+        out.spoiler_sections = extract_spoilers(doc, &cfg.profile.spoiler_denylist);
+    }
+
+    if cfg.scrape.static_comments {
+        let items = extract_static_comments(doc);
+        if !items.is_empty() {
+            out.comments = Some(CommentThread {
+                count: items.len(),
+                items,
+            });
+        }
+    }
+
+    if cfg.scrape.poll {
+        out.poll = extract_poll(doc);
+    }
+
+    if cfg.scrape.direct_download_links {
+        out.direct_download_links =
+            extract_denylisted_spoiler_links(doc, base.as_ref(), &cfg.profile.spoiler_denylist);
     }
 
     if cfg.scrape.download_section_presence {
-        out.download_section_headings = select_all_text(&doc, "div.entry-content > h3")
+        out.download_section_headings = select_all_text(doc, "div.entry-content > h3")
             .into_iter()
             .filter(|title| title.to_ascii_lowercase().contains("download mirrors"))
             .collect();
     }
 
+    if cfg.scrape.download_sections {
+        out.download_sections =
+            links::extract_download_sections(doc, "div.entry-content", base.as_ref());
+        if cfg.links.classify_hosts {
+            for link in out
+                .download_sections
+                .iter_mut()
+                .flat_map(|s| s.links.iter_mut())
+            {
+                link.category = link
+                    .host
+                    .as_deref()
+                    .and_then(|h| links::classify_host(h, &cfg.links.host_categories));
+            }
+        }
+    }
+
     if cfg.links.domain_counts {
-        out.link_domain_counts = extract_domain_counts(&doc, cfg.links.ignore_magnet);
+        out.link_domain_counts = extract_domain_counts(doc, base.as_ref(), cfg.links.ignore_magnet);
+    }
+
+    if cfg.links.internal_external {
+        let site_host = select_attr(doc, "link[rel='canonical']", "href")
+            .or_else(|| select_attr(doc, "meta[property='og:url']", "content"))
+            .and_then(|u| links::host_of(u.trim()));
+        out.link_profile = Some(links::extract_link_profile(
+            doc,
+            site_host,
+            base.as_ref(),
+            cfg.links.resolve_relative,
+            cfg.links.ignore_magnet,
+        ));
+    }
+
+    if cfg.links.section_density {
+        out.link_section_counts = links::extract_section_link_counts(doc);
+    }
+
+    if cfg.links.classify_hosts {
+        out.link_category_counts =
+            links::extract_host_category_counts(doc, base.as_ref(), &cfg.links.host_categories);
+    }
+
+    if cfg.links.anchor_inventory {
+        out.links = links::extract_anchor_inventory(doc, base.as_ref());
     }
 
     if cfg.scrape.torrent_file
@@ -203,7 +581,7 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
         || cfg.scrape.torrent_file_link
         || cfg.scrape.magnet
     {
-        let extracted = extract_torrent_and_magnet(&doc);
+        let extracted = extract_torrent_and_magnet(doc, base.as_ref());
         if cfg.scrape.torrent_file {
             out.torrent_file = Some(!extracted.torrent_file_links.is_empty());
         }
@@ -215,57 +593,237 @@ pub fn parse_wordpress_release(html: &str, cfg: &Config) -> Result<ParsedDocumen
         }
         if cfg.scrape.magnet {
             out.magnet_links = extracted.magnet_links;
+            out.infohashes = links::magnet_infohashes(&out.magnet_links);
         }
     }
 
+    if cfg.scrape.images {
+        out.images = extract_images(doc, "div.entry-content img");
+    }
+
+    if cfg.scrape.site_donations {
+        out.site_donations = links::extract_donations(doc);
+    }
+
+    if cfg.scrape.raw_html {
+        out.raw_html = extract_raw_html(doc, &cfg.profile.raw_html_selectors, &mut out.warnings);
+    }
+
+    if cfg.output.explain {
+        record_provenance(&mut out, PAGE_SOURCES);
+        record_provenance(&mut out, WORDPRESS_SOURCES);
+    }
+
     Ok(out)
 }
 
 #[instrument(level = "debug", skip_all)]
-pub fn parse_generic(html: &str, cfg: &Config) -> Result<ParsedDocument> {
-    let doc = Html::parse_document(html);
+pub fn parse_generic(doc: &Html, cfg: &Config) -> Result<ParsedDocument> {
+    let base = if cfg.links.resolve_relative {
+        links::document_base_url(doc)
+    } else {
+        None
+    };
 
     let mut out = ParsedDocument {
+        doc_id: None,
         source: SourceInfo {
             path: String::new(),
             bytes: 0,
             sha256: String::new(),
+            mtime: None,
         },
         site: "generic".to_string(),
+        warnings: vec![],
         page: None,
         post: None,
         release: None,
+        pricing: None,
+        content_language: None,
+        breadcrumbs: vec![],
         spoiler_sections: vec![],
+        comments: None,
+        poll: None,
+        direct_download_links: vec![],
+        site_donations: None,
         link_domain_counts: BTreeMap::new(),
+        link_profile: None,
+        link_section_counts: BTreeMap::new(),
+        links: vec![],
+        link_category_counts: BTreeMap::new(),
         download_section_headings: vec![],
+        download_sections: vec![],
         torrent_file: None,
         torrent_file_names: vec![],
         torrent_file_links: vec![],
         magnet_links: vec![],
+        infohashes: vec![],
+        shares_payload_with: vec![],
+        images: vec![],
+        raw_html: BTreeMap::new(),
+        confidence: None,
+        redacted: None,
+        provenance: BTreeMap::new(),
+        history: vec![],
+        enrichment: None,
     };
 
-    if cfg.scrape.page_title || cfg.scrape.canonical_url || cfg.scrape.meta_tags {
+    if cfg.scrape.page_title
+        || cfg.scrape.canonical_url
+        || cfg.scrape.meta_tags
+        || cfg.scrape.head_links
+        || cfg.scrape.hreflang
+        || cfg.scrape.generator
+    {
         let mut page = PageMeta {
             title: None,
             canonical_url: None,
             meta: BTreeMap::new(),
+            favicon: None,
+            apple_touch_icon: None,
+            feeds: vec![],
+            shortlink: None,
+            hreflang: BTreeMap::new(),
+            generator: None,
+            theme: None,
         };
 
         if cfg.scrape.page_title {
-            page.title = select_text(&doc, "head > title");
+            page.title = select_text(doc, "head > title");
         }
         if cfg.scrape.canonical_url {
-            page.canonical_url = select_attr(&doc, "link[rel='canonical']", "href");
+            page.canonical_url = select_attr(doc, "link[rel='canonical']", "href");
         }
         if cfg.scrape.meta_tags {
-            page.meta = extract_meta_tags(&doc);
+            page.meta = extract_meta_tags(doc);
+        }
+        if cfg.scrape.head_links {
+            page.favicon = select_attr(doc, "head link[rel~='icon']", "href");
+            page.apple_touch_icon = select_attr(doc, "head link[rel~='apple-touch-icon']", "href");
+            page.feeds = extract_feed_links(doc);
+            page.shortlink = select_attr(doc, "head link[rel~='shortlink']", "href");
+        }
+        if cfg.scrape.hreflang {
+            page.hreflang = extract_hreflang(doc);
+        }
+        if cfg.scrape.generator {
+            page.generator = select_attr(doc, "meta[name='generator']", "content")
+                .map(|s| normalize_ws(&s))
+                .filter(|s| !s.is_empty());
+            page.theme = detect_wp_theme(doc);
         }
 
         out.page = Some(page);
     }
 
+    if cfg.scrape.pricing
+        && let Some(store) = store::detect_store(doc)
+    {
+        out.pricing = store::extract_pricing(doc, store);
+        if out.pricing.is_some() {
+            explain(
+                &mut out,
+                cfg,
+                "pricing",
+                &format!(
+                    "store:{} price selectors, JSON-LD Offer, price meta",
+                    store.as_str()
+                ),
+            );
+        }
+    }
+
+    if cfg.scrape.age_ratings {
+        let age_ratings = extract_age_ratings(doc, "body");
+        if !age_ratings.is_empty() {
+            out.release
+                .get_or_insert_with(ReleaseMeta::default)
+                .age_ratings = age_ratings;
+        }
+    }
+    if cfg.scrape.steam_appids {
+        let mut appids = links::extract_steam_appids(doc, "a[href]");
+        if let Some(canonical) = select_attr(doc, "link[rel='canonical']", "href") {
+            appids.extend(links::steam_appid_from_url(&canonical));
+            appids.sort_unstable();
+            appids.dedup();
+        }
+        if !appids.is_empty() {
+            out.release
+                .get_or_insert_with(ReleaseMeta::default)
+                .steam_appids = appids;
+        }
+    }
+    if cfg.scrape.gog_ids {
+        let mut ids = links::extract_gog_ids(doc, "a[href]");
+        if let Some(canonical) = select_attr(doc, "link[rel='canonical']", "href") {
+            ids.extend(links::gog_id_from_url(&canonical));
+            ids.sort();
+            ids.dedup();
+        }
+        if !ids.is_empty() {
+            out.release.get_or_insert_with(ReleaseMeta::default).gog_ids = ids;
+        }
+    }
+    if cfg.scrape.review_scores {
+        let scores = links::extract_review_scores(doc, "body");
+        if !scores.is_empty() {
+            out.release
+                .get_or_insert_with(ReleaseMeta::default)
+                .review_scores = scores;
+        }
+    }
+
+    if cfg.scrape.developers_publishers {
+        let (developers, publishers) = extract_developers_publishers(
+            doc,
+            "body",
+            &cfg.profile.developer_selectors,
+            &cfg.profile.publisher_selectors,
+        );
+        if !developers.is_empty() || !publishers.is_empty() {
+            let release = out.release.get_or_insert_with(ReleaseMeta::default);
+            release.developers = developers;
+            release.publishers = publishers;
+        }
+    }
+
+    if cfg.scrape.breadcrumbs {
+        out.breadcrumbs = extract_breadcrumbs(doc);
+    }
+
+    if cfg.scrape.content_language {
+        out.content_language = normalize::detect_language(&links::visible_text(doc));
+    }
+
     if cfg.links.domain_counts {
-        out.link_domain_counts = extract_domain_counts(&doc, cfg.links.ignore_magnet);
+        out.link_domain_counts = extract_domain_counts(doc, base.as_ref(), cfg.links.ignore_magnet);
+    }
+
+    if cfg.links.internal_external {
+        let site_host = select_attr(doc, "link[rel='canonical']", "href")
+            .or_else(|| select_attr(doc, "meta[property='og:url']", "content"))
+            .and_then(|u| links::host_of(u.trim()));
+        out.link_profile = Some(links::extract_link_profile(
+            doc,
+            site_host,
+            base.as_ref(),
+            cfg.links.resolve_relative,
+            cfg.links.ignore_magnet,
+        ));
+    }
+
+    if cfg.links.section_density {
+        out.link_section_counts = links::extract_section_link_counts(doc);
+    }
+
+    if cfg.links.classify_hosts {
+        out.link_category_counts =
+            links::extract_host_category_counts(doc, base.as_ref(), &cfg.links.host_categories);
+    }
+
+    if cfg.links.anchor_inventory {
+        out.links = links::extract_anchor_inventory(doc, base.as_ref());
     }
 
     if cfg.scrape.torrent_file
@@ -273,7 +831,7 @@ pub fn parse_generic(html: &str, cfg: &Config) -> Result<ParsedDocument> {
         || cfg.scrape.torrent_file_link
         || cfg.scrape.magnet
     {
-        let extracted = extract_torrent_and_magnet(&doc);
+        let extracted = extract_torrent_and_magnet(doc, base.as_ref());
         if cfg.scrape.torrent_file {
             out.torrent_file = Some(!extracted.torrent_file_links.is_empty());
         }
@@ -285,46 +843,582 @@ pub fn parse_generic(html: &str, cfg: &Config) -> Result<ParsedDocument> {
         }
         if cfg.scrape.magnet {
             out.magnet_links = extracted.magnet_links;
+            out.infohashes = links::magnet_infohashes(&out.magnet_links);
         }
     }
 
+    if cfg.scrape.images {
+        out.images = extract_images(doc, "body img");
+    }
+
+    if cfg.scrape.site_donations {
+        out.site_donations = links::extract_donations(doc);
+    }
+
+    if cfg.scrape.raw_html {
+        out.raw_html = extract_raw_html(doc, &cfg.profile.raw_html_selectors, &mut out.warnings);
+    }
+
+    if cfg.output.explain {
+        record_provenance(&mut out, PAGE_SOURCES);
+        record_provenance(&mut out, GENERIC_SOURCES);
+    }
+
     Ok(out)
 }
 
+const PAGE_SOURCES: &[(&str, &str)] = &[
+    ("page.title", "css:head > title"),
+    ("page.canonical_url", "css:link[rel='canonical']@href"),
+    ("page.meta", "css:meta[name|property]@content"),
+    ("page.favicon", "css:head link[rel~='icon']@href"),
+    (
+        "page.apple_touch_icon",
+        "css:head link[rel~='apple-touch-icon']@href",
+    ),
+    ("page.feeds", "css:head link[rel~='alternate'][href][type]"),
+    ("page.shortlink", "css:head link[rel~='shortlink']@href"),
+    (
+        "page.hreflang",
+        "css:link[rel~='alternate'][hreflang][href]",
+    ),
+    ("page.generator", "css:meta[name='generator']@content"),
+    (
+        "page.theme",
+        "regex:/wp-content/themes/<name>/ in stylesheet/script URLs, body class",
+    ),
+    (
+        "breadcrumbs",
+        "css:breadcrumb trail selectors, JSON-LD BreadcrumbList",
+    ),
+    ("link_domain_counts", "css:a[href]"),
+    ("link_profile", "css:a[href] vs canonical/og:url host"),
+    ("link_section_counts", "css:a[href] grouped by page section"),
+    (
+        "link_category_counts",
+        "css:a[href] + links.host_categories",
+    ),
+    ("links", "css:a[href]"),
+    ("torrent_file", "css:a[href] (.torrent href or text)"),
+    ("torrent_file_names", "css:a[href] (.torrent href or text)"),
+    ("torrent_file_links", "css:a[href] (.torrent href or text)"),
+    ("magnet_links", "css:a[href^='magnet:']"),
+    ("infohashes", "magnet_links (xt=urn:btih:)"),
+    (
+        "site_donations",
+        "visible text crypto regexes, donation platform links",
+    ),
+    ("raw_html", "profile:raw_html_selectors"),
+];
+
+const WORDPRESS_SOURCES: &[(&str, &str)] = &[
+    (
+        "post.post_id",
+        "css:article[id^='post-']@id + regex:post-(\\d+)",
+    ),
+    (
+        "post.slug",
+        "css:link[rel='canonical']@href (last path segment)",
+    ),
+    ("post.shortlink", "css:link[rel~='shortlink']@href"),
+    ("post.categories", "css:span.cat-links a"),
+    ("post.genres", "css:span.cat-links a + taxonomy.genres"),
+    ("post.wp_tags", "css:article[id^='post-']@class (tag-*)"),
+    (
+        "post.wp_tags_canonical",
+        "post.wp_tags + taxonomy.tag_aliases",
+    ),
+    ("post.entry_title", "css:h1.entry-title"),
+    (
+        "post.entry_datetime_rfc3339",
+        "post.entry_datetime + profile:default_utc_offset",
+    ),
+    (
+        "post.entry_datetime_epoch",
+        "post.entry_datetime + profile:default_utc_offset",
+    ),
+    (
+        "post.modified_rfc3339",
+        "post.modified + profile:default_utc_offset",
+    ),
+    (
+        "post.modified_epoch",
+        "post.modified + profile:default_utc_offset",
+    ),
+    ("post.author", "css:span.author a"),
+    (
+        "post.comments_count",
+        "css:span.tolstoycomments-cc + regex:(\\d+)",
+    ),
+    ("release.game_title_line", "css:div.entry-content > h3"),
+    (
+        "release.release_number",
+        "css:div.entry-content > h3 + regex:#\\s*(\\d{1,6})",
+    ),
+    (
+        "release.genres_tags",
+        "css:div.entry-content p (Genres/Tags:) a[href*='/tag/']",
+    ),
+    (
+        "release.genres_tags_canonical",
+        "release.genres_tags + taxonomy.tag_aliases",
+    ),
+    ("release.companies", "label:Companies:"),
+    (
+        "release.developers",
+        "profile:developer_selectors, then Developer:/Developed by labels",
+    ),
+    (
+        "release.publishers",
+        "profile:publisher_selectors, then Publisher:/Published by labels",
+    ),
+    ("release.languages_raw", "label:Languages:"),
+    (
+        "release.languages",
+        "release.languages_raw + language table",
+    ),
+    ("release.multi_count", "release.languages_raw (MULTI<n>)"),
+    ("release.original_size_raw", "label:Original Size:"),
+    ("release.repack_size_raw", "label:Repack Size:"),
+    ("release.original_size_bytes", "release.original_size_raw"),
+    ("release.repack_size_bytes_min", "release.repack_size_raw"),
+    ("release.repack_size_bytes_max", "release.repack_size_raw"),
+    (
+        "release.age_ratings",
+        "regex:ESRB/PEGI/USK/CERO/ACB in div.entry-content text and img assets",
+    ),
+    (
+        "release.steam_appids",
+        "css:div.entry-content a[href] (Steam app URLs)",
+    ),
+    (
+        "release.gog_ids",
+        "css:div.entry-content a[href] (GOG URLs)",
+    ),
+    (
+        "release.review_scores",
+        "css:div.entry-content a[href] (review aggregator hosts)",
+    ),
+    ("content_language", "whatlang on css:div.entry-content text"),
+    ("spoiler_sections", "css:div.entry-content div.su-spoiler"),
+    (
+        "comments",
+        "css:ol.commentlist li.comment, ol.comment-list li.comment, ul.comment-list li.comment",
+    ),
+    ("poll", "css:.wp-polls, .yop-poll-container, .poll, .polls"),
+    (
+        "direct_download_links",
+        "css:div.su-spoiler-content a[href] + profile:spoiler_denylist",
+    ),
+    (
+        "download_section_headings",
+        "css:div.entry-content > h3 (download mirrors)",
+    ),
+    (
+        "download_sections",
+        "css:div.entry-content h3 + following links",
+    ),
+    ("images", "css:div.entry-content img"),
+];
+
+const GENERIC_SOURCES: &[(&str, &str)] = &[
+    (
+        "release.age_ratings",
+        "regex:ESRB/PEGI/USK/CERO/ACB in body text and img assets",
+    ),
+    (
+        "release.steam_appids",
+        "css:a[href] (Steam app URLs), link[rel='canonical']@href",
+    ),
+    (
+        "release.gog_ids",
+        "css:a[href] (GOG URLs), link[rel='canonical']@href",
+    ),
+    (
+        "release.review_scores",
+        "css:body a[href] (review aggregator hosts)",
+    ),
+    (
+        "release.developers",
+        "profile:developer_selectors, then Developer:/Developed by labels",
+    ),
+    (
+        "release.publishers",
+        "profile:publisher_selectors, then Publisher:/Published by labels",
+    ),
+    ("content_language", "whatlang on visible body text"),
+    ("images", "css:body img"),
+];
+
+fn explain(out: &mut ParsedDocument, cfg: &Config, field: &str, source: &str) {
+    if cfg.output.explain {
+        out.provenance.insert(field.to_string(), source.to_string());
+    }
+}
+
+// Fields recorded inline (those with fallback chains) win over the static
+// tables, which only describe the single source a field can come from.
+fn record_provenance(out: &mut ParsedDocument, sources: &[(&str, &str)]) {
+    let value = match serde_json::to_value(&*out) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+    for (field, source) in sources {
+        if field_value(&value, field).is_some_and(is_present) {
+            out.provenance
+                .entry(field.to_string())
+                .or_insert_with(|| source.to_string());
+        }
+    }
+}
+
+pub fn field_value<'a>(doc: &'a serde_json::Value, field: &str) -> Option<&'a serde_json::Value> {
+    field.split('.').try_fold(doc, |v, key| v.get(key))
+}
+
+pub fn is_present(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Array(a) => !a.is_empty(),
+        serde_json::Value::Object(o) => !o.is_empty(),
+        serde_json::Value::String(s) => !s.is_empty(),
+        _ => true,
+    }
+}
+
+// Every field the parser has a known source for in a layout, as `explain`
+// lists them: the static tables plus the fallback chains of fields whose
+// provenance is recorded inline.
+pub fn field_sources(wordpress_release: bool) -> Vec<(&'static str, &'static str)> {
+    let (layout, chains) = if wordpress_release {
+        (WORDPRESS_SOURCES, WORDPRESS_CHAINS)
+    } else {
+        (GENERIC_SOURCES, GENERIC_CHAINS)
+    };
+    PAGE_SOURCES
+        .iter()
+        .chain(layout)
+        .chain(chains)
+        .copied()
+        .collect()
+}
+
+const WORDPRESS_CHAINS: &[(&str, &str)] = &[
+    (
+        "post.entry_datetime",
+        "css:time.entry-date@datetime, css:time.entry-date",
+    ),
+    (
+        "post.published",
+        "css:time.entry-date[datetime]@datetime, css:meta[property='article:published_time']@content",
+    ),
+    (
+        "post.modified",
+        "css:meta[property='article:modified_time']@content, css:time.updated[datetime]@datetime, css:meta[property='og:updated_time']@content",
+    ),
+    (
+        "release.title_slug",
+        "css:div.entry-content > h3, css:h1.entry-title",
+    ),
+    (
+        "release.version",
+        "css:div.entry-content > h3, css:h1.entry-title",
+    ),
+    (
+        "release.group",
+        "css:div.entry-content > h3, h1.entry-title, og:site_name, head > title, canonical URL, footer + profile:release_groups",
+    ),
+];
+
+const GENERIC_CHAINS: &[(&str, &str)] = &[(
+    "pricing",
+    "store price selectors, JSON-LD Offer, price meta",
+)];
+
+fn select_first_attr(doc: &Html, candidates: &[(&str, &str)]) -> Option<(String, String)> {
+    candidates.iter().find_map(|(selector, attr)| {
+        select_attr(doc, selector, attr).map(|value| (value, format!("css:{selector}@{attr}")))
+    })
+}
+
 struct TorrentMagnetExtract {
     torrent_file_names: Vec<String>,
     torrent_file_links: Vec<String>,
     magnet_links: Vec<String>,
 }
 
-fn select_text(doc: &Html, selector: &str) -> Option<String> {
-    let selector = Selector::parse(selector).ok()?;
-    doc.select(&selector)
-        .next()
-        .map(|e| normalize_ws(&e.text().collect::<Vec<_>>().join(" ")))
-        .filter(|s| !s.is_empty())
+fn extract_raw_html(
+    doc: &Html,
+    selectors: &[String],
+    warnings: &mut Vec<String>,
+) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+
+    for raw in selectors {
+        let selector = match Selector::parse(raw) {
+            Ok(s) => s,
+            Err(_) => {
+                warn!(selector = %raw, "invalid raw_html selector; skipping");
+                warnings.push(format!("invalid raw_html selector {raw:?}; skipping"));
+                continue;
+            }
+        };
+
+        if let Some(e) = doc.select(&selector).next() {
+            out.insert(raw.clone(), e.inner_html());
+        }
+    }
+
+    out
 }
 
-fn select_all_text(doc: &Html, selector: &str) -> Vec<String> {
-    let selector = match Selector::parse(selector) {
+fn extract_feed_links(doc: &Html) -> Vec<FeedLink> {
+    let selector = match Selector::parse("head link[rel~='alternate'][href][type]") {
         Ok(s) => s,
         Err(_) => return vec![],
     };
 
     doc.select(&selector)
-        .filter_map(|e| {
-            let s = normalize_ws(&e.text().collect::<Vec<_>>().join(" "));
-            if s.is_empty() { None } else { Some(s) }
+        .filter_map(|link| {
+            let node = link.value();
+            let kind = match node.attr("type")?.trim().to_ascii_lowercase().as_str() {
+                "application/rss+xml" => "rss",
+                "application/atom+xml" => "atom",
+                _ => return None,
+            };
+
+            Some(FeedLink {
+                kind: kind.to_string(),
+                href: node.attr("href")?.trim().to_string(),
+                title: node
+                    .attr("title")
+                    .map(normalize_ws)
+                    .filter(|s| !s.is_empty()),
+            })
         })
         .collect()
 }
 
-fn select_attr(doc: &Html, selector: &str, attr: &str) -> Option<String> {
-    let selector = Selector::parse(selector).ok()?;
+fn extract_hreflang(doc: &Html) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    let selector = match Selector::parse("link[rel~='alternate'][hreflang][href]") {
+        Ok(s) => s,
+        Err(_) => return out,
+    };
+
+    for link in doc.select(&selector) {
+        let node = link.value();
+        let lang = node.attr("hreflang").unwrap_or_default().trim();
+        let href = node.attr("href").unwrap_or_default().trim();
+        if !lang.is_empty() && !href.is_empty() {
+            out.entry(lang.to_ascii_lowercase())
+                .or_insert_with(|| href.to_string());
+        }
+    }
+
+    out
+}
+
+fn detect_wp_theme(doc: &Html) -> Option<String> {
+    if let Ok(selector) = Selector::parse("link[rel~='stylesheet'][href], script[src]") {
+        for e in doc.select(&selector) {
+            let url = e
+                .value()
+                .attr("href")
+                .or_else(|| e.value().attr("src"))
+                .unwrap_or_default();
+            if let Some(cap) = RE_WP_THEME_PATH.captures(url) {
+                return cap.get(1).map(|m| m.as_str().to_ascii_lowercase());
+            }
+        }
+    }
+
+    let body_class = select_attr(doc, "body", "class")?;
+    body_class
+        .split_whitespace()
+        .find_map(|tok| tok.strip_prefix("wp-theme-"))
+        .map(|s| s.to_ascii_lowercase())
+}
+
+const BREADCRUMB_SELECTORS: &[&str] = &[
+    "[itemtype*='BreadcrumbList'] [itemprop='itemListElement']",
+    "#breadcrumbs a, #breadcrumbs span.breadcrumb_last",
+    ".rank-math-breadcrumb a, .rank-math-breadcrumb span.last",
+    "nav.breadcrumb a, nav.breadcrumbs a, .breadcrumbs a, .breadcrumb a",
+];
+
+fn extract_breadcrumbs(doc: &Html) -> Vec<Breadcrumb> {
+    let from_json_ld = extract_json_ld_breadcrumbs(doc);
+    if !from_json_ld.is_empty() {
+        return from_json_ld;
+    }
+
+    let href_sel = Selector::parse("[href]").ok();
+    for raw in BREADCRUMB_SELECTORS {
+        let selector = match Selector::parse(raw) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+
+        let crumbs: Vec<Breadcrumb> = doc
+            .select(&selector)
+            .filter_map(|e| {
+                let name = normalize_ws(&e.text().collect::<Vec<_>>().join(" "));
+                if name.is_empty() {
+                    return None;
+                }
+                let url = e.value().attr("href").map(str::to_string).or_else(|| {
+                    href_sel
+                        .as_ref()
+                        .and_then(|sel| e.select(sel).next())
+                        .and_then(|a| a.value().attr("href"))
+                        .map(str::to_string)
+                });
+                Some(Breadcrumb { name, url })
+            })
+            .collect();
+
+        if !crumbs.is_empty() {
+            return crumbs;
+        }
+    }
+
+    vec![]
+}
+
+fn extract_json_ld_breadcrumbs(doc: &Html) -> Vec<Breadcrumb> {
+    for value in json_ld_values(doc) {
+        if let Some(list) = find_json_ld_type(&value, "BreadcrumbList") {
+            let mut items: Vec<(u64, Breadcrumb)> = list
+                .get("itemListElement")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .filter_map(|(idx, item)| {
+                    let position = item
+                        .get("position")
+                        .and_then(|p| p.as_u64().or_else(|| p.as_str()?.parse().ok()))
+                        .unwrap_or(idx as u64);
+                    let nested = item.get("item");
+                    let name = item
+                        .get("name")
+                        .or_else(|| nested.and_then(|n| n.get("name")))
+                        .and_then(|n| n.as_str())
+                        .map(normalize_ws)
+                        .filter(|s| !s.is_empty())?;
+                    let url = nested
+                        .and_then(|n| n.as_str().or_else(|| n.get("@id")?.as_str()))
+                        .map(str::to_string);
+                    Some((position, Breadcrumb { name, url }))
+                })
+                .collect();
+            items.sort_by_key(|(position, _)| *position);
+            return items.into_iter().map(|(_, crumb)| crumb).collect();
+        }
+    }
+
+    vec![]
+}
+
+// A group matches only as whole words of the title ("R.G. Mechanics" is the
+// words `r g mechanics`), so "DODI" does not match inside "Commando Diaries".
+fn detect_release_group(text: &str, groups: &[String]) -> Option<String> {
+    let words = |s: &str| {
+        s.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+    };
+    let haystack = words(text);
+
+    groups
+        .iter()
+        .find(|group| {
+            let needle = words(group);
+            !needle.is_empty()
+                && haystack
+                    .windows(needle.len())
+                    .any(|w| w == needle.as_slice())
+        })
+        .cloned()
+}
+
+fn extract_age_ratings(doc: &Html, root_selector: &str) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+
+    if let Ok(selector) = Selector::parse(&format!("{root_selector} img")) {
+        for img in doc.select(&selector) {
+            let node = img.value();
+            let hint = [node.attr("src"), node.attr("alt"), node.attr("title")]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+            if let Some(cap) = RE_AGE_RATING_ASSET.captures(&hint) {
+                out.entry(cap[1].to_ascii_uppercase())
+                    .or_insert_with(|| normalize_age_rating(&cap[2]));
+            }
+        }
+    }
+
+    if let Ok(selector) = Selector::parse(root_selector)
+        && let Some(root) = doc.select(&selector).next()
+    {
+        let text = normalize_ws(&root.text().collect::<Vec<_>>().join(" "));
+        for cap in RE_AGE_RATING_TEXT.captures_iter(&text) {
+            out.entry(cap[1].to_ascii_uppercase())
+                .or_insert_with(|| normalize_age_rating(&cap[2]));
+        }
+    }
+
+    out
+}
+
+fn normalize_age_rating(raw: &str) -> String {
+    let squashed = normalize_ws(raw).to_ascii_uppercase();
+    match squashed.as_str() {
+        "EVERYONE" => "E".to_string(),
+        "EVERYONE 10+" | "EVERYONE10+" | "E10" => "E10+".to_string(),
+        "TEEN" => "T".to_string(),
+        "MATURE" | "MATURE 17+" | "MATURE17+" => "M".to_string(),
+        "ADULTS ONLY" => "AO".to_string(),
+        _ => squashed.trim_end_matches('+').to_string(),
+    }
+}
+
+fn slug_from_url(raw: &str) -> Option<String> {
+    let url = Url::parse(raw.trim()).ok()?;
+    url.path_segments()?
+        .rfind(|seg| !seg.is_empty())
+        .map(|seg| seg.to_string())
+}
+
+fn extract_images(doc: &Html, selector: &str) -> Vec<ImageAsset> {
+    let selector = match Selector::parse(selector) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
     doc.select(&selector)
-        .next()
-        .and_then(|e| e.value().attr(attr))
-        .map(|s| s.to_string())
+        .filter_map(|img| {
+            let node = img.value();
+            let src = node
+                .attr("src")
+                .or_else(|| node.attr("data-src"))
+                .or_else(|| node.attr("data-lazy-src"))
+                .map(str::trim)
+                .filter(|s| !s.is_empty())?;
+            let attr = |name: &str| node.attr(name).map(normalize_ws).filter(|s| !s.is_empty());
+
+            Some(ImageAsset {
+                src: src.to_string(),
+                alt: attr("alt"),
+                width: attr("width"),
+                height: attr("height"),
+            })
+        })
+        .collect()
 }
 
 fn extract_meta_tags(doc: &Html) -> BTreeMap<String, String> {
@@ -408,6 +1502,73 @@ fn capture_between_labels(text: &str, label: &str, next_labels: &[&str]) -> Opti
     }
 }
 
+const COMPANY_LABELS: &[&str] = &[
+    "Developer:",
+    "Developers:",
+    "Developed by",
+    "Publisher:",
+    "Publishers:",
+    "Published by",
+    "Release Date:",
+    "Released:",
+    "Genre:",
+    "Genres/Tags:",
+    "Languages:",
+    "Original Size:",
+    "Repack Size:",
+    "Franchise:",
+];
+
+fn extract_developers_publishers(
+    doc: &Html,
+    root_selector: &str,
+    developer_selectors: &[String],
+    publisher_selectors: &[String],
+) -> (Vec<String>, Vec<String>) {
+    let from_selectors = |selectors: &[String]| {
+        selectors
+            .iter()
+            .map(|sel| select_all_text(doc, sel))
+            .find(|found| !found.is_empty())
+            .unwrap_or_default()
+    };
+    let mut developers = from_selectors(developer_selectors);
+    let mut publishers = from_selectors(publisher_selectors);
+
+    // The label fallback reads one line at a time, so a value never runs on
+    // into the post body below the metadata.
+    if developers.is_empty() || publishers.is_empty() {
+        let lines = select_text_lines(doc, root_selector);
+        let by_labels = |labels: &[&str]| {
+            let others: Vec<&str> = COMPANY_LABELS
+                .iter()
+                .copied()
+                .filter(|l| !labels.contains(l))
+                .collect();
+            labels.iter().find_map(|label| {
+                lines
+                    .iter()
+                    .find_map(|line| capture_between_labels(line, label, &others))
+                    .map(|v| split_csvish(&v))
+            })
+        };
+        if developers.is_empty() {
+            developers =
+                by_labels(&["Developer:", "Developers:", "Developed by"]).unwrap_or_default();
+        }
+        if publishers.is_empty() {
+            publishers =
+                by_labels(&["Publisher:", "Publishers:", "Published by"]).unwrap_or_default();
+        }
+    }
+
+    let mut seen = BTreeSet::new();
+    developers.retain(|d| seen.insert(d.clone()));
+    let mut seen = BTreeSet::new();
+    publishers.retain(|p| seen.insert(p.clone()));
+    (developers, publishers)
+}
+
 fn split_csvish(s: &str) -> Vec<String> {
     s.split(',')
         .map(|x| x.trim())
@@ -433,11 +1594,7 @@ fn extract_spoilers(doc: &Html, denylist: &[String]) -> Vec<SpoilerSection> {
             .map(|e| normalize_ws(&e.text().collect::<Vec<_>>().join(" ")))
             .unwrap_or_default();
 
-        let title_l = title.to_ascii_lowercase();
-        let denied = denylist
-            .iter()
-            .any(|term| title_l.contains(&term.to_ascii_lowercase()));
-        if denied {
+        if is_denied_spoiler(&title, denylist) {
             debug!(title = %title, "skipping spoiler due to denylist");
             continue;
         }
@@ -456,7 +1613,197 @@ fn extract_spoilers(doc: &Html, denylist: &[String]) -> Vec<SpoilerSection> {
     out
 }
 
-fn extract_domain_counts(doc: &Html, ignore_magnet: bool) -> BTreeMap<String, u64> {
+fn extract_static_comments(doc: &Html) -> Vec<Comment> {
+    let item_sel = match Selector::parse(
+        "ol.commentlist li.comment, ol.comment-list li.comment, ul.comment-list li.comment",
+    ) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+    let body_sel = Selector::parse(":scope > article.comment-body, :scope > div.comment-body").ok();
+    let author_sel = Selector::parse(".comment-author .fn, .comment-author cite").ok();
+    let time_sel = Selector::parse(".comment-metadata time, .comment-meta time").ok();
+    let date_text_sel = Selector::parse(".comment-metadata a, .comment-meta a").ok();
+    let content_sel = Selector::parse(".comment-content").ok();
+    let para_sel = Selector::parse("p").ok();
+
+    let mut out = Vec::new();
+
+    for li in doc.select(&item_sel) {
+        let body = body_sel
+            .as_ref()
+            .and_then(|sel| li.select(sel).next())
+            .unwrap_or(li);
+        let text_of =
+            |e: scraper::ElementRef| normalize_ws(&e.text().collect::<Vec<_>>().join(" "));
+        let first = |sel: &Option<Selector>| {
+            sel.as_ref()
+                .and_then(|s| body.select(s).next())
+                .map(text_of)
+                .filter(|s| !s.is_empty())
+        };
+
+        let date = time_sel
+            .as_ref()
+            .and_then(|s| body.select(s).next())
+            .and_then(|t| t.value().attr("datetime").map(str::to_string))
+            .or_else(|| first(&time_sel))
+            .or_else(|| first(&date_text_sel));
+        let text = first(&content_sel).unwrap_or_else(|| {
+            para_sel
+                .as_ref()
+                .map(|s| body.select(s).map(text_of).collect::<Vec<_>>().join(" "))
+                .unwrap_or_default()
+        });
+        if text.is_empty() {
+            continue;
+        }
+
+        out.push(Comment {
+            id: li
+                .value()
+                .attr("id")
+                .and_then(|id| RE_COMMENT_ID.captures(id))
+                .and_then(|cap| cap[1].parse::<u64>().ok()),
+            author: first(&author_sel),
+            date,
+            text,
+        });
+    }
+
+    out
+}
+
+fn extract_poll(doc: &Html) -> Option<Poll> {
+    let mut poll = Poll {
+        question: None,
+        total_votes: None,
+        options: vec![],
+    };
+
+    if let Ok(root_sel) = Selector::parse(".wp-polls, .yop-poll-container, .poll, .polls") {
+        let question_sel = Selector::parse("strong, .basic-question-title, .poll-question").ok();
+        let option_sel = Selector::parse("ul.wp-polls-ul li, .basic-answer, li.poll-option").ok();
+
+        if let Some(root) = doc.select(&root_sel).next() {
+            poll.question = question_sel
+                .as_ref()
+                .and_then(|sel| root.select(sel).next())
+                .map(|e| normalize_ws(&e.text().collect::<Vec<_>>().join(" ")))
+                .filter(|s| !s.is_empty());
+
+            for li in option_sel.iter().flat_map(|sel| root.select(sel)) {
+                let text = normalize_ws(&li.text().collect::<Vec<_>>().join(" "));
+                let (label, stats) = match text.rfind('(') {
+                    Some(pos) => (text[..pos].trim(), &text[pos..]),
+                    None => (text.as_str(), ""),
+                };
+                if label.is_empty() {
+                    continue;
+                }
+                let cap = RE_OPTION_VOTES
+                    .captures_iter(stats)
+                    .find(|c| c.get(1).is_some() || c.get(2).is_some());
+                poll.options.push(PollOption {
+                    label: label.to_string(),
+                    votes: cap
+                        .as_ref()
+                        .and_then(|c| c.get(2))
+                        .and_then(|m| parse_count(m.as_str())),
+                    percent: cap
+                        .as_ref()
+                        .and_then(|c| c.get(1))
+                        .and_then(|m| m.as_str().replace(',', ".").parse::<f64>().ok()),
+                });
+            }
+
+            let text = normalize_ws(&root.text().collect::<Vec<_>>().join(" "));
+            poll.total_votes = RE_TOTAL_VOTERS
+                .captures(&text)
+                .and_then(|cap| parse_count(&cap[1]));
+        }
+    }
+
+    if poll.total_votes.is_none()
+        && let Some(text) = select_text(doc, "div.entry-content")
+    {
+        poll.total_votes = RE_VOTED_TOTAL
+            .captures(&text)
+            .and_then(|cap| parse_count(&cap[1]));
+    }
+
+    if poll.total_votes.is_none() && poll.options.is_empty() {
+        return None;
+    }
+    if poll.total_votes.is_none() && poll.options.iter().all(|o| o.votes.is_some()) {
+        poll.total_votes = Some(poll.options.iter().filter_map(|o| o.votes).sum());
+    }
+
+    Some(poll)
+}
+
+fn parse_count(raw: &str) -> Option<u64> {
+    raw.chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse::<u64>()
+        .ok()
+}
+
+fn extract_denylisted_spoiler_links(
+    doc: &Html,
+    base: Option<&Url>,
+    denylist: &[String],
+) -> Vec<String> {
+    let spoiler_sel = match Selector::parse("div.entry-content div.su-spoiler") {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+    let title_sel = Selector::parse("div.su-spoiler-title").ok();
+    let link_sel = match Selector::parse("div.su-spoiler-content a[href]") {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    let mut out: Vec<String> = Vec::new();
+
+    for sp in doc.select(&spoiler_sel) {
+        let title = title_sel
+            .as_ref()
+            .and_then(|sel| sp.select(sel).next())
+            .map(|e| normalize_ws(&e.text().collect::<Vec<_>>().join(" ")))
+            .unwrap_or_default();
+        if !is_denied_spoiler(&title, denylist) {
+            continue;
+        }
+
+        for a in sp.select(&link_sel) {
+            if let Some(href) = a.value().attr("href").map(str::trim)
+                && !href.is_empty()
+            {
+                let href = links::resolve_href(base, href).unwrap_or_else(|| href.to_string());
+                if !out.contains(&href) {
+                    out.push(href);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn is_denied_spoiler(title: &str, denylist: &[String]) -> bool {
+    let title_l = title.to_ascii_lowercase();
+    denylist
+        .iter()
+        .any(|term| title_l.contains(&term.to_ascii_lowercase()))
+}
+
+fn extract_domain_counts(
+    doc: &Html,
+    base: Option<&Url>,
+    ignore_magnet: bool,
+) -> BTreeMap<String, u64> {
     let mut out = BTreeMap::new();
     let selector = match Selector::parse("a[href]") {
         Ok(s) => s,
@@ -470,11 +1817,12 @@ fn extract_domain_counts(doc: &Html, ignore_magnet: bool) -> BTreeMap<String, u6
                 continue;
             }
 
-            if !(href_l.starts_with("http://") || href_l.starts_with("https://")) {
-                continue;
-            }
+            let href = match links::resolve_href(base, href.trim()) {
+                Some(h) => h,
+                None => continue,
+            };
 
-            if let Ok(url) = Url::parse(href) {
+            if let Ok(url) = Url::parse(&href) {
                 if let Some(host) = url.host_str() {
                     bump_domain_count(&mut out, host);
                 }
@@ -485,7 +1833,24 @@ fn extract_domain_counts(doc: &Html, ignore_magnet: bool) -> BTreeMap<String, u6
     out
 }
 
-fn extract_torrent_and_magnet(doc: &Html) -> TorrentMagnetExtract {
+// The `extract` fast path: magnet URIs and torrent URLs exactly as `parse`
+// finds them, with none of the other extraction.
+pub fn download_links(doc: &Html, cfg: &Config) -> (Vec<String>, Vec<String>) {
+    let base = if cfg.links.resolve_relative {
+        links::document_base_url(doc)
+    } else {
+        None
+    };
+    let mut extracted = extract_torrent_and_magnet(doc, base.as_ref());
+    if cfg.links.strip_tracking_params {
+        for href in &mut extracted.torrent_file_links {
+            *href = links::strip_tracking_params(href, &cfg.links.tracking_params);
+        }
+    }
+    (extracted.magnet_links, extracted.torrent_file_links)
+}
+
+fn extract_torrent_and_magnet(doc: &Html, base: Option<&Url>) -> TorrentMagnetExtract {
     let mut names: Vec<String> = Vec::new();
     let mut torrent_links: Vec<String> = Vec::new();
     let mut magnet_links: Vec<String> = Vec::new();
@@ -514,13 +1879,12 @@ fn extract_torrent_and_magnet(doc: &Html) -> TorrentMagnetExtract {
             continue;
         }
 
-        let is_http = href_l.starts_with("http://") || href_l.starts_with("https://");
         let text_l = text.to_ascii_lowercase();
         let looks_torrent = href_l.contains(".torrent")
             || text_l.contains(".torrent")
             || text_l.contains("torrent file");
-        if is_http && looks_torrent {
-            torrent_links.push(href.to_string());
+        if looks_torrent && let Some(resolved) = links::resolve_href(base, href) {
+            torrent_links.push(resolved);
             if !text.is_empty() {
                 names.push(text);
             }
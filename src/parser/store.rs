@@ -0,0 +1,194 @@
+use crate::model::Pricing;
+use crate::parser::links::host_of;
+use crate::parser::util::{find_json_ld_type, json_ld_values, select_attr, select_text};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::Html;
+
+static RE_AMOUNT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d[\d.,\s]*)").expect("valid regex"));
+static RE_PERCENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d{1,3})\s*%").expect("valid regex"));
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Store {
+    Steam,
+    Gog,
+    Itch,
+}
+
+impl Store {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Store::Steam => "steam",
+            Store::Gog => "gog",
+            Store::Itch => "itch",
+        }
+    }
+}
+
+struct PriceSelectors {
+    current: &'static [&'static str],
+    original: &'static [&'static str],
+    discount: &'static [&'static str],
+}
+
+const STEAM_SELECTORS: PriceSelectors = PriceSelectors {
+    current: &[
+        ".game_purchase_action .discount_final_price",
+        ".game_purchase_action .game_purchase_price",
+    ],
+    original: &[".game_purchase_action .discount_original_price"],
+    discount: &[".game_purchase_action .discount_pct"],
+};
+
+const GOG_SELECTORS: PriceSelectors = PriceSelectors {
+    current: &[
+        "[selenium-id='ProductFinalPrice']",
+        ".product-actions-price__final-amount",
+    ],
+    original: &[
+        "[selenium-id='ProductBasePrice']",
+        ".product-actions-price__base-amount",
+    ],
+    discount: &[
+        "[selenium-id='ProductPriceDiscount']",
+        ".product-actions-price__discount",
+    ],
+};
+
+const ITCH_SELECTORS: PriceSelectors = PriceSelectors {
+    current: &[".buy_row .dollars", ".button_message .dollars"],
+    original: &[".buy_row .original_price", ".original_price"],
+    discount: &[".sale_rate", ".sale_tag"],
+};
+
+pub fn detect_store(doc: &Html) -> Option<Store> {
+    let host = select_attr(doc, "link[rel='canonical']", "href")
+        .or_else(|| select_attr(doc, "meta[property='og:url']", "content"))
+        .and_then(|u| host_of(u.trim()))?;
+
+    if host == "store.steampowered.com" {
+        Some(Store::Steam)
+    } else if host == "gog.com" || host.ends_with(".gog.com") {
+        Some(Store::Gog)
+    } else if host == "itch.io" || host.ends_with(".itch.io") {
+        Some(Store::Itch)
+    } else {
+        None
+    }
+}
+
+pub fn extract_pricing(doc: &Html, store: Store) -> Option<Pricing> {
+    let selectors = match store {
+        Store::Steam => &STEAM_SELECTORS,
+        Store::Gog => &GOG_SELECTORS,
+        Store::Itch => &ITCH_SELECTORS,
+    };
+
+    let current_raw = first_text(doc, selectors.current);
+    let original_raw = first_text(doc, selectors.original);
+    let discount_raw = first_text(doc, selectors.discount);
+
+    let offer = json_ld_offer(doc);
+    let meta_amount = select_attr(doc, "meta[property='product:price:amount']", "content")
+        .or_else(|| select_attr(doc, "meta[itemprop='price']", "content"));
+    let meta_currency = select_attr(doc, "meta[property='product:price:currency']", "content")
+        .or_else(|| select_attr(doc, "meta[itemprop='priceCurrency']", "content"));
+
+    let current = current_raw
+        .as_deref()
+        .and_then(parse_amount)
+        .or_else(|| offer.as_ref().and_then(|o| o.0))
+        .or_else(|| meta_amount.as_deref().and_then(parse_amount));
+    let original = original_raw.as_deref().and_then(parse_amount);
+    let discount_percent = discount_raw
+        .as_deref()
+        .and_then(|s| RE_PERCENT.captures(s))
+        .and_then(|cap| cap.get(1))
+        .and_then(|m| m.as_str().parse::<u32>().ok())
+        .or_else(|| match (current, original) {
+            (Some(c), Some(o)) if o > 0.0 && c < o => Some(((1.0 - c / o) * 100.0).round() as u32),
+            _ => None,
+        });
+    let currency = meta_currency
+        .or_else(|| offer.and_then(|o| o.1))
+        .or_else(|| current_raw.as_deref().and_then(currency_from_symbol));
+
+    if current.is_none() && original.is_none() && current_raw.is_none() {
+        return None;
+    }
+
+    Some(Pricing {
+        store: store.as_str().to_string(),
+        current,
+        original,
+        discount_percent,
+        currency,
+        current_raw,
+    })
+}
+
+fn first_text(doc: &Html, selectors: &[&str]) -> Option<String> {
+    selectors.iter().find_map(|sel| select_text(doc, sel))
+}
+
+fn json_ld_offer(doc: &Html) -> Option<(Option<f64>, Option<String>)> {
+    json_ld_values(doc).iter().find_map(|value| {
+        let product = find_json_ld_type(value, "Product")?;
+        let offers = product.get("offers")?;
+        let offer = offers.as_array().and_then(|a| a.first()).unwrap_or(offers);
+        let price = offer
+            .get("price")
+            .and_then(|p| p.as_f64().or_else(|| p.as_str().and_then(parse_amount)));
+        let currency = offer
+            .get("priceCurrency")
+            .and_then(|c| c.as_str())
+            .map(|c| c.to_ascii_uppercase());
+        Some((price, currency))
+    })
+}
+
+fn parse_amount(raw: &str) -> Option<f64> {
+    if raw.trim().eq_ignore_ascii_case("free") || raw.trim().eq_ignore_ascii_case("free to play") {
+        return Some(0.0);
+    }
+
+    let digits: String = RE_AMOUNT
+        .captures(raw)?
+        .get(1)?
+        .as_str()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let digits = digits.trim_end_matches(['.', ',']);
+
+    let normalized = match digits.rfind(['.', ',']) {
+        Some(pos) if digits.len() - pos - 1 <= 2 => {
+            let (int_part, frac_part) = digits.split_at(pos);
+            format!("{}.{}", int_part.replace(['.', ','], ""), &frac_part[1..])
+        }
+        _ => digits.replace(['.', ','], ""),
+    };
+
+    normalized.parse::<f64>().ok()
+}
+
+fn currency_from_symbol(raw: &str) -> Option<String> {
+    let code = if raw.contains('€') {
+        "EUR"
+    } else if raw.contains('£') {
+        "GBP"
+    } else if raw.contains('₽') || raw.contains("руб") {
+        "RUB"
+    } else if raw.contains('¥') {
+        "JPY"
+    } else if raw.contains("zł") {
+        "PLN"
+    } else if raw.contains("R$") {
+        "BRL"
+    } else if raw.contains('$') {
+        "USD"
+    } else {
+        return None;
+    };
+    Some(code.to_string())
+}
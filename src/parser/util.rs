@@ -1,5 +1,7 @@
+use scraper::{Html, Node, Selector};
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
+use tracing::debug;
 
 pub fn sha256_hex(bytes: &[u8]) -> String {
     let mut h = Sha256::new();
@@ -14,3 +16,106 @@ pub fn normalize_ws(s: &str) -> String {
 pub fn bump_domain_count(map: &mut BTreeMap<String, u64>, domain: &str) {
     *map.entry(domain.to_string()).or_insert(0) += 1;
 }
+
+pub fn select_text(doc: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    doc.select(&selector)
+        .next()
+        .map(|e| normalize_ws(&e.text().collect::<Vec<_>>().join(" ")))
+        .filter(|s| !s.is_empty())
+}
+
+pub fn select_all_text(doc: &Html, selector: &str) -> Vec<String> {
+    let selector = match Selector::parse(selector) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    doc.select(&selector)
+        .filter_map(|e| {
+            let s = normalize_ws(&e.text().collect::<Vec<_>>().join(" "));
+            if s.is_empty() { None } else { Some(s) }
+        })
+        .collect()
+}
+
+// Text of the first match split into lines at `<br>` and block elements, so a
+// labelled value can be cut at the end of its line.
+pub fn select_text_lines(doc: &Html, selector: &str) -> Vec<String> {
+    let selector = match Selector::parse(selector) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+    let Some(root) = doc.select(&selector).next() else {
+        return vec![];
+    };
+
+    let mut text = String::new();
+    for node in root.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e)
+                if matches!(
+                    e.name(),
+                    "br" | "p" | "div" | "li" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+                ) =>
+            {
+                text.push('\n')
+            }
+            _ => {}
+        }
+    }
+    text.lines()
+        .map(normalize_ws)
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+pub fn select_attr(doc: &Html, selector: &str, attr: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    doc.select(&selector)
+        .next()
+        .and_then(|e| e.value().attr(attr))
+        .map(|s| s.to_string())
+}
+
+pub fn json_ld_values(doc: &Html) -> Vec<serde_json::Value> {
+    let selector = match Selector::parse("script[type='application/ld+json']") {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    doc.select(&selector)
+        .filter_map(|script| {
+            let raw = script.text().collect::<String>();
+            match serde_json::from_str(raw.trim()) {
+                Ok(v) => Some(v),
+                Err(err) => {
+                    debug!(error = %err, "skipping unparsable JSON-LD block");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+pub fn find_json_ld_type<'a>(
+    value: &'a serde_json::Value,
+    ty: &str,
+) -> Option<&'a serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items.iter().find_map(|v| find_json_ld_type(v, ty)),
+        serde_json::Value::Object(map) => {
+            let matches = match map.get("@type") {
+                Some(serde_json::Value::String(s)) => s == ty,
+                Some(serde_json::Value::Array(types)) => types.iter().any(|t| t == ty),
+                _ => false,
+            };
+            if matches {
+                return Some(value);
+            }
+            map.get("@graph").and_then(|g| find_json_ld_type(g, ty))
+        }
+        _ => None,
+    }
+}
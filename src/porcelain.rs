@@ -0,0 +1,46 @@
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Instant;
+
+// Bump when a key of the status line is renamed or removed; new keys keep
+// the version.
+const VERSION: u32 = 1;
+
+static FIELDS: Lazy<Mutex<Map<String, Value>>> = Lazy::new(|| Mutex::new(Map::new()));
+
+// Adds a result count or path to the `--porcelain` status line.
+pub fn record(key: &str, value: impl Into<Value>) {
+    if let Ok(mut fields) = FIELDS.lock() {
+        fields.insert(key.to_string(), value.into());
+    }
+}
+
+// `--porcelain`: one JSON object on one line of stderr, written once the
+// command is done, with whatever the command recorded.
+pub fn write_status<W: Write>(
+    out: &mut W,
+    command: &str,
+    started: Instant,
+    result: &Result<()>,
+) -> Result<()> {
+    let mut line = json!({
+        "porcelain": VERSION,
+        "command": command,
+        "status": if result.is_ok() { "ok" } else { "error" },
+        "exit_code": i32::from(result.is_err()),
+        "elapsed_ms": started.elapsed().as_millis() as u64,
+    });
+    if let Err(err) = result {
+        line["error"] = Value::String(format!("{err:#}"));
+    }
+    if let (Some(line), Ok(fields)) = (line.as_object_mut(), FIELDS.lock()) {
+        for (key, value) in fields.iter() {
+            line.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    writeln!(out, "{line}")?;
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use indicatif::{ProgressBar, ProgressState, ProgressStyle};
+use once_cell::sync::OnceCell;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// One bar per process, started by `parse`; the parser loops report to it and
+// do nothing when it was never started.
+static BAR: OnceCell<ProgressBar> = OnceCell::new();
+static ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn start(total: usize) {
+    // `wide_msg` takes the rest of the line and cuts long paths instead of
+    // wrapping the bar onto a second line.
+    let style = ProgressStyle::with_template(
+        "{bar:30.cyan/blue} {pos}/{len} {rate:.dim} eta {eta} {wide_msg}",
+    )
+    .expect("valid progress template")
+    .with_key("rate", |state: &ProgressState, out: &mut dyn fmt::Write| {
+        let _ = write!(out, "{:.1} files/s", state.per_sec());
+    })
+    .progress_chars("=> ");
+    let _ = BAR.set(ProgressBar::new(total as u64).with_style(style));
+}
+
+pub fn file_started(path: &Path) {
+    if let Some(bar) = BAR.get() {
+        bar.set_message(format!(
+            "{} errors  {}",
+            ERRORS.load(Ordering::Relaxed),
+            path.display()
+        ));
+    }
+}
+
+pub fn file_done(failed: bool) {
+    if let Some(bar) = BAR.get() {
+        if failed {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+        bar.inc(1);
+    }
+}
+
+pub fn finish() {
+    if let Some(bar) = BAR.get() {
+        bar.finish_and_clear();
+    }
+}
+
+// Log output goes through this so lines are printed above the bar instead of
+// through it.
+pub struct Stderr;
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match BAR.get() {
+            Some(bar) if !bar.is_finished() => bar.suspend(|| io::stderr().write(buf)),
+            _ => io::stderr().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
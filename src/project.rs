@@ -0,0 +1,167 @@
+use crate::config::{SortBy, SortOrder};
+use crate::model::ParsedDocument;
+use crate::parser::normalize::parse_datetime;
+use chrono::FixedOffset;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Default)]
+pub struct Projection {
+    include: Vec<Vec<String>>,
+    exclude: Vec<Vec<String>>,
+}
+
+impl Projection {
+    pub fn new(include: &[String], exclude: &[String]) -> Self {
+        Self {
+            include: split_paths(include),
+            exclude: split_paths(exclude),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn apply(&self, doc: Value) -> Value {
+        let mut out = if self.include.is_empty() {
+            doc
+        } else {
+            let mut kept = Value::Object(Map::new());
+            for path in &self.include {
+                if let Some(selected) = select(&doc, path) {
+                    merge(&mut kept, selected);
+                }
+            }
+            kept
+        };
+        for path in &self.exclude {
+            remove(&mut out, path);
+        }
+        out
+    }
+}
+
+fn split_paths(raw: &[String]) -> Vec<Vec<String>> {
+    raw.iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.split('.').map(str::to_string).collect())
+        .collect()
+}
+
+// Arrays are projected element-wise, so `download_sections.heading` keeps the
+// heading of every section.
+fn select(value: &Value, path: &[String]) -> Option<Value> {
+    let Some((head, rest)) = path.split_first() else {
+        return Some(value.clone());
+    };
+    match value {
+        Value::Object(fields) => {
+            let child = select(fields.get(head)?, rest)?;
+            let mut out = Map::new();
+            out.insert(head.clone(), child);
+            Some(Value::Object(out))
+        }
+        Value::Array(items) => Some(Value::Array(
+            items
+                .iter()
+                .map(|item| select(item, path).unwrap_or(Value::Null))
+                .collect(),
+        )),
+        _ => None,
+    }
+}
+
+fn merge(into: &mut Value, from: Value) {
+    match (into, from) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in b {
+                match a.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        a.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (existing, value) in a.iter_mut().zip(b) {
+                merge(existing, value);
+            }
+        }
+        (_, Value::Null) => {}
+        (into, from) => *into = from,
+    }
+}
+
+fn remove(value: &mut Value, path: &[String]) {
+    let Some((head, rest)) = path.split_first() else {
+        return;
+    };
+    match value {
+        Value::Object(fields) if rest.is_empty() => {
+            fields.remove(head);
+        }
+        Value::Object(fields) => {
+            if let Some(child) = fields.get_mut(head) {
+                remove(child, rest);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| remove(item, path)),
+        _ => {}
+    }
+}
+
+pub fn sort_documents(
+    docs: &mut [ParsedDocument],
+    by: SortBy,
+    order: SortOrder,
+    offset: FixedOffset,
+) {
+    let ordered = |ord: Ordering| match order {
+        SortOrder::Asc => ord,
+        SortOrder::Desc => ord.reverse(),
+    };
+    match by {
+        SortBy::None => {}
+        SortBy::ReleaseNumber => docs.sort_by(|a, b| {
+            missing_last(release_number(a), release_number(b), |x, y| {
+                ordered(x.cmp(y))
+            })
+        }),
+        SortBy::EntryDatetime => docs.sort_by(|a, b| {
+            missing_last(
+                entry_timestamp(a, offset),
+                entry_timestamp(b, offset),
+                |x, y| ordered(x.cmp(y)),
+            )
+        }),
+        SortBy::Path => docs.sort_by(|a, b| ordered(a.source.path.cmp(&b.source.path))),
+    }
+}
+
+fn missing_last<T>(a: Option<T>, b: Option<T>, cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => cmp(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn release_number(doc: &ParsedDocument) -> Option<u64> {
+    doc.release.as_ref().and_then(|r| r.release_number)
+}
+
+// Offset-less dates are read in `offset` ([profile] default_utc_offset).
+pub fn entry_timestamp(doc: &ParsedDocument, offset: FixedOffset) -> Option<i64> {
+    let post = doc.post.as_ref()?;
+    post.entry_datetime_epoch.or_else(|| {
+        post.entry_datetime
+            .as_deref()
+            .or(post.published.as_deref())
+            .and_then(|raw| parse_datetime(raw, offset))
+            .map(|ts| ts.timestamp())
+    })
+}
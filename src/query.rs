@@ -0,0 +1,80 @@
+use crate::model::OutputBundle;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::Write;
+
+// JMESPath over the whole bundle, so NDJSON inputs are queried as the same
+// `{documents, errors, stats, …}` shape as a JSON bundle.
+pub fn run(bundle: &OutputBundle, expression: &str) -> Result<Value> {
+    let expression = quote_bare_literals(expression);
+    let expr = jmespath::compile(&expression)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+        .context("compile query")?;
+    let data = serde_json::to_value(bundle)?;
+    let result = expr
+        .search(data)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+        .context("evaluate query")?;
+    Ok(serde_json::to_value(&*result)?)
+}
+
+// Older JMESPath accepted literals with elided quotes (`` `RPG` `` for
+// `` `"RPG"` ``); the parser no longer does, so quote any literal that is not
+// valid JSON on its own.
+fn quote_bare_literals(expression: &str) -> String {
+    let mut out = String::with_capacity(expression.len());
+    let mut chars = expression.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '\'' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '`' => {
+                let mut literal = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' if chars.as_str().starts_with('`') => {
+                            literal.push('`');
+                            chars.next();
+                        }
+                        '`' => break,
+                        _ => literal.push(c),
+                    }
+                }
+                let json = match serde_json::from_str::<Value>(&literal) {
+                    Ok(_) => literal,
+                    Err(_) => Value::from(literal.trim()).to_string(),
+                };
+                out.push('`');
+                out.push_str(&json.replace('`', "\\`"));
+                out.push('`');
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// `--raw` prints strings without quotes and arrays one element per line,
+// like `jq -r`; anything else is printed as JSON.
+pub fn write_result<W: Write>(out: &mut W, result: &Value, raw: bool, compact: bool) -> Result<()> {
+    match result {
+        Value::String(s) if raw => writeln!(out, "{s}")?,
+        Value::Array(items) if raw => {
+            for item in items {
+                write_result(out, item, raw, true)?;
+            }
+        }
+        value if compact => writeln!(out, "{}", serde_json::to_string(value)?)?,
+        value => writeln!(out, "{}", serde_json::to_string_pretty(value)?)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::model::{OutputBundle, ParsedDocument, RedactionSummary};
+use std::collections::BTreeSet;
+
+pub fn redact_links(bundle: &mut OutputBundle) {
+    for doc in &mut bundle.documents {
+        redact_document_links(doc);
+    }
+    bundle.infohash_index.clear();
+}
+
+// Hrefs go, counts and hosts stay: sections keep each link's text, host and
+// category, and the summary records how many of each kind were removed.
+pub fn redact_document_links(doc: &mut ParsedDocument) {
+    let mut removed: BTreeSet<String> = BTreeSet::new();
+    removed.extend(doc.magnet_links.iter().cloned());
+    removed.extend(doc.torrent_file_links.iter().cloned());
+    removed.extend(doc.direct_download_links.iter().cloned());
+
+    let mut mirror_links = 0;
+    for link in doc
+        .download_sections
+        .iter_mut()
+        .flat_map(|s| s.links.iter_mut())
+    {
+        if !link.href.is_empty() {
+            removed.insert(std::mem::take(&mut link.href));
+            mirror_links += 1;
+        }
+    }
+
+    let summary = RedactionSummary {
+        magnet_links: doc.magnet_links.len(),
+        infohashes: doc.infohashes.len(),
+        torrent_file_links: doc.torrent_file_links.len(),
+        direct_download_links: doc.direct_download_links.len(),
+        mirror_links,
+    };
+
+    doc.magnet_links.clear();
+    doc.infohashes.clear();
+    doc.torrent_file_links.clear();
+    doc.direct_download_links.clear();
+    // Raw HTML snippets would carry the same hrefs verbatim.
+    doc.raw_html.clear();
+    for anchor in &mut doc.links {
+        if removed.contains(&anchor.href) || anchor.href.to_ascii_lowercase().starts_with("magnet:")
+        {
+            anchor.href.clear();
+        }
+    }
+
+    doc.redacted = Some(summary);
+}
@@ -0,0 +1,323 @@
+use crate::model::OutputBundle;
+use crate::parser::{links, release_page};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Io,
+    Encoding,
+    MissingMarker,
+    SelectorFailure,
+    UnparsedValue,
+    Other,
+}
+
+impl Category {
+    fn label(self) -> &'static str {
+        match self {
+            Category::Io => "io",
+            Category::Encoding => "encoding",
+            Category::MissingMarker => "missing marker",
+            Category::SelectorFailure => "selector failure",
+            Category::UnparsedValue => "unparsed value",
+            Category::Other => "other",
+        }
+    }
+}
+
+// `report` subcommand: what went wrong in a run and how much of each field
+// the corpus yielded.
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub inputs: usize,
+    pub parsed_ok: usize,
+    pub parsed_err: usize,
+    pub categories: Vec<CategoryCount>,
+    pub worst_offenders: Vec<Offender>,
+    pub coverage: Vec<FieldCoverage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryCount {
+    pub category: Category,
+    pub errors: usize,
+    pub warnings: usize,
+    // One message per category, to show what the bucket holds.
+    pub example: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Offender {
+    pub path: String,
+    pub problems: Vec<String>,
+    // Share of the layout's fields that were extracted; None for files that
+    // failed to parse.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldCoverage {
+    pub field: String,
+    pub present: usize,
+    pub documents: usize,
+    pub percent: f64,
+}
+
+// `report --domains`: link hosts across the bundle against
+// `[links] host_categories`.
+#[derive(Debug, Serialize)]
+pub struct DomainReport {
+    pub domains_total: usize,
+    pub unknown_total: usize,
+    pub domains: Vec<DomainCount>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DomainCount {
+    pub domain: String,
+    pub links: u64,
+    pub documents: usize,
+    // None when no entry of the host map covers the domain.
+    pub category: Option<String>,
+}
+
+// Parse errors and document warnings are free text; sort them by what they
+// say.
+fn categorize(message: &str) -> Category {
+    let lower = message.to_lowercase();
+    if lower.contains("utf-8") || lower.contains("encoding") {
+        Category::Encoding
+    } else if lower.starts_with("read ") || lower.contains("os error") {
+        Category::Io
+    } else if lower.contains("selector") {
+        Category::SelectorFailure
+    } else if lower.contains("could not find") {
+        Category::MissingMarker
+    } else if lower.contains("could not parse") || lower.contains("could not normalize") {
+        Category::UnparsedValue
+    } else {
+        Category::Other
+    }
+}
+
+pub fn report(bundle: &OutputBundle, top: usize) -> Result<RunReport> {
+    let mut categories: BTreeMap<Category, CategoryCount> = BTreeMap::new();
+    let mut count = |message: &str, error: bool| {
+        let category = categorize(message);
+        let entry = categories.entry(category).or_insert_with(|| CategoryCount {
+            category,
+            errors: 0,
+            warnings: 0,
+            example: message.to_string(),
+        });
+        if error {
+            entry.errors += 1;
+        } else {
+            entry.warnings += 1;
+        }
+    };
+
+    let mut offenders: Vec<Offender> = Vec::new();
+    for err in &bundle.errors {
+        count(&err.error, true);
+        offenders.push(Offender {
+            path: err.path.clone(),
+            problems: vec![err.error.clone()],
+            coverage: None,
+        });
+    }
+
+    // Fields are counted against the documents whose layout has them.
+    let mut fields: Vec<String> = Vec::new();
+    let mut present: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for doc in &bundle.documents {
+        for warning in &doc.warnings {
+            count(warning, false);
+        }
+        let value = serde_json::to_value(doc)?;
+        let sources = release_page::field_sources(doc.site == "wordpress_release");
+        let mut matched = 0;
+        for (field, _) in &sources {
+            let hit =
+                release_page::field_value(&value, field).is_some_and(release_page::is_present);
+            let entry = present.entry(field.to_string()).or_insert_with(|| {
+                fields.push(field.to_string());
+                (0, 0)
+            });
+            entry.0 += usize::from(hit);
+            entry.1 += 1;
+            matched += usize::from(hit);
+        }
+        offenders.push(Offender {
+            path: doc.source.path.clone(),
+            problems: doc.warnings.clone(),
+            coverage: (!sources.is_empty()).then(|| matched as f64 / sources.len() as f64),
+        });
+    }
+
+    // Failed files first, then the most warnings, then the least extracted.
+    offenders.sort_by(|a, b| {
+        a.coverage
+            .is_some()
+            .cmp(&b.coverage.is_some())
+            .then(b.problems.len().cmp(&a.problems.len()))
+            .then(
+                a.coverage
+                    .unwrap_or_default()
+                    .total_cmp(&b.coverage.unwrap_or_default()),
+            )
+            .then(a.path.cmp(&b.path))
+    });
+    offenders.truncate(top);
+
+    let coverage = fields
+        .into_iter()
+        .map(|field| {
+            let (hits, documents) = present[&field];
+            FieldCoverage {
+                field,
+                present: hits,
+                documents,
+                percent: percent(hits, documents),
+            }
+        })
+        .collect();
+
+    Ok(RunReport {
+        inputs: bundle.stats.input_count,
+        parsed_ok: bundle.stats.parsed_ok,
+        parsed_err: bundle.stats.parsed_err,
+        categories: categories.into_values().collect(),
+        worst_offenders: offenders,
+        coverage,
+    })
+}
+
+pub fn domains(
+    bundle: &OutputBundle,
+    categories: &BTreeMap<String, String>,
+    top: usize,
+) -> DomainReport {
+    let mut counts: BTreeMap<&str, (u64, usize)> = BTreeMap::new();
+    for doc in &bundle.documents {
+        for (domain, &n) in &doc.link_domain_counts {
+            let entry = counts.entry(domain).or_default();
+            entry.0 += n;
+            entry.1 += 1;
+        }
+    }
+    let mut domains: Vec<DomainCount> = counts
+        .into_iter()
+        .map(|(domain, (links, documents))| DomainCount {
+            domain: domain.to_string(),
+            links,
+            documents,
+            category: links::classify_host(domain, categories),
+        })
+        .collect();
+    domains.sort_by_key(|d| std::cmp::Reverse(d.links));
+    let unknown_total = domains.iter().filter(|d| d.category.is_none()).count();
+    let domains_total = domains.len();
+    domains.truncate(top);
+    DomainReport {
+        domains_total,
+        unknown_total,
+        domains,
+    }
+}
+
+pub fn write_domains_table<W: Write>(out: &mut W, report: &DomainReport) -> Result<()> {
+    let width = report
+        .domains
+        .iter()
+        .map(|d| d.domain.len())
+        .max()
+        .unwrap_or(0);
+    for d in &report.domains {
+        writeln!(
+            out,
+            "{:<width$}  {:>7} links {:>5} docs  {}",
+            d.domain,
+            d.links,
+            d.documents,
+            d.category.as_deref().unwrap_or("UNKNOWN")
+        )?;
+    }
+    writeln!(
+        out,
+        "\n{} domains, {} not in [links.host_categories]",
+        report.domains_total, report.unknown_total
+    )?;
+    Ok(())
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    (part as f64 * 1000.0 / whole as f64).round() / 10.0
+}
+
+pub fn write_report_table<W: Write>(out: &mut W, report: &RunReport) -> Result<()> {
+    writeln!(
+        out,
+        "{} inputs: {} parsed, {} failed ({}% ok)",
+        report.inputs,
+        report.parsed_ok,
+        report.parsed_err,
+        percent(report.parsed_ok, report.inputs)
+    )?;
+
+    writeln!(out, "\nproblems by category")?;
+    if report.categories.is_empty() {
+        writeln!(out, "  none")?;
+    }
+    for c in &report.categories {
+        writeln!(
+            out,
+            "  {:<17} {:>5} errors {:>5} warnings  e.g. {}",
+            c.category.label(),
+            c.errors,
+            c.warnings,
+            c.example
+        )?;
+    }
+
+    writeln!(out, "\nworst offenders")?;
+    for o in &report.worst_offenders {
+        match o.coverage {
+            Some(coverage) => writeln!(
+                out,
+                "  {}  ({} warnings, {:.0}% of fields)",
+                o.path,
+                o.problems.len(),
+                coverage * 100.0
+            )?,
+            None => writeln!(out, "  {}  (failed)", o.path)?,
+        }
+        for problem in &o.problems {
+            writeln!(out, "      {problem}")?;
+        }
+    }
+
+    writeln!(out, "\nfield coverage")?;
+    let width = report
+        .coverage
+        .iter()
+        .map(|f| f.field.len())
+        .max()
+        .unwrap_or(0);
+    for f in &report.coverage {
+        writeln!(
+            out,
+            "  {:<width$}  {:>5.1}%  {}/{}",
+            f.field, f.percent, f.present, f.documents
+        )?;
+    }
+    Ok(())
+}
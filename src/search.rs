@@ -0,0 +1,190 @@
+use crate::dedupe;
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::output::{genres, title};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, STORED, STRING, Schema, TEXT, Value};
+use tantivy::{Index, IndexWriter, TantivyDocument, Term};
+
+const WRITER_MEMORY: usize = 50_000_000;
+
+// Query terms hit these fields; matches in titles and tags weigh more than
+// ones buried in spoiler or description text.
+const BOOSTS: [(&str, f32); 4] = [
+    ("title", 3.0),
+    ("tags", 2.0),
+    ("spoilers", 1.0),
+    ("content", 1.0),
+];
+
+pub struct SearchIndex {
+    index: Index,
+    doc_id: Field,
+    path: Field,
+    title: Field,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hit {
+    pub score: f32,
+    pub doc_id: String,
+    pub path: String,
+    pub title: String,
+}
+
+fn schema() -> Schema {
+    let mut builder = Schema::builder();
+    builder.add_text_field("doc_id", STRING | STORED);
+    builder.add_text_field("path", STRING | STORED);
+    builder.add_text_field("title", TEXT | STORED);
+    builder.add_text_field("tags", TEXT);
+    builder.add_text_field("spoilers", TEXT);
+    builder.add_text_field("content", TEXT);
+    builder.build()
+}
+
+impl SearchIndex {
+    pub fn in_memory() -> Result<Self> {
+        Self::from_index(Index::create_in_ram(schema()))
+    }
+
+    pub fn open_or_create(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+        let directory = MmapDirectory::open(dir)
+            .with_context(|| format!("open search index {}", dir.display()))?;
+        let index = Index::open_or_create(directory, schema())
+            .with_context(|| format!("open search index {}", dir.display()))?;
+        Self::from_index(index)
+    }
+
+    fn from_index(index: Index) -> Result<Self> {
+        let schema = index.schema();
+        Ok(Self {
+            doc_id: schema.get_field("doc_id")?,
+            path: schema.get_field("path")?,
+            title: schema.get_field("title")?,
+            index,
+        })
+    }
+
+    pub fn add_bundle(&mut self, bundle: &OutputBundle) -> Result<()> {
+        self.write(bundle, false)
+    }
+
+    // Replaces documents already indexed under the same doc_id, so re-indexing
+    // a bundle refreshes entries instead of duplicating them.
+    pub fn upsert_bundle(&mut self, bundle: &OutputBundle) -> Result<()> {
+        self.write(bundle, true)
+    }
+
+    fn write(&mut self, bundle: &OutputBundle, replace: bool) -> Result<()> {
+        let mut writer: IndexWriter = self
+            .index
+            .writer(WRITER_MEMORY)
+            .context("open search index writer")?;
+        for doc in &bundle.documents {
+            let doc = self.document(doc)?;
+            if replace && let Some(id) = doc.get_first(self.doc_id).and_then(|v| v.as_str()) {
+                writer.delete_term(Term::from_field_text(self.doc_id, id));
+            }
+            writer.add_document(doc)?;
+        }
+        writer.commit().context("commit search index")?;
+        Ok(())
+    }
+
+    fn document(&self, doc: &ParsedDocument) -> Result<TantivyDocument> {
+        let schema = self.index.schema();
+        let tags = schema.get_field("tags")?;
+        let spoilers = schema.get_field("spoilers")?;
+        let content = schema.get_field("content")?;
+
+        let mut out = TantivyDocument::default();
+        out.add_text(
+            self.doc_id,
+            doc.doc_id.clone().unwrap_or_else(|| dedupe::doc_id(doc)),
+        );
+        out.add_text(self.path, &doc.source.path);
+        out.add_text(self.title, title(doc));
+        let post = doc.post.as_ref();
+        let release = doc.release.as_ref();
+        for tag in genres(doc)
+            .iter()
+            .chain(post.into_iter().flat_map(|p| &p.wp_tags))
+            .chain(post.into_iter().flat_map(|p| &p.categories))
+        {
+            out.add_text(tags, tag);
+        }
+        for spoiler in &doc.spoiler_sections {
+            out.add_text(spoilers, &spoiler.title);
+            out.add_text(spoilers, &spoiler.text);
+        }
+        let description = doc.page.as_ref().and_then(|p| p.meta.get("description"));
+        for text in description
+            .into_iter()
+            .chain(release.into_iter().flat_map(|r| &r.companies))
+            .chain(release.into_iter().flat_map(|r| &r.developers))
+            .chain(release.into_iter().flat_map(|r| &r.publishers))
+            .chain(&doc.download_section_headings)
+        {
+            out.add_text(content, text);
+        }
+        Ok(out)
+    }
+
+    // Terms are OR-ed and ranked by BM25; the usual query syntax (`+must`,
+    // `-not`, `"phrases"`, `title:word`) works too.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Hit>> {
+        let schema = self.index.schema();
+        let mut fields = Vec::with_capacity(BOOSTS.len());
+        for (name, _) in BOOSTS {
+            fields.push(schema.get_field(name)?);
+        }
+        let mut parser = QueryParser::for_index(&self.index, fields.clone());
+        for (field, (_, boost)) in fields.into_iter().zip(BOOSTS) {
+            parser.set_field_boost(field, boost);
+        }
+        let query = parser
+            .parse_query(query)
+            .with_context(|| format!("parse search query `{query}`"))?;
+
+        let searcher = self.index.reader()?.searcher();
+        let top = searcher.search(&query, &TopDocs::with_limit(limit).order_by_score())?;
+        let mut hits = Vec::with_capacity(top.len());
+        for (score, address) in top {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let text = |field: Field| {
+                doc.get_first(field)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            hits.push(Hit {
+                score,
+                doc_id: text(self.doc_id),
+                path: text(self.path),
+                title: text(self.title),
+            });
+        }
+        Ok(hits)
+    }
+}
+
+pub fn write_hits_table<W: Write>(out: &mut W, hits: &[Hit]) -> Result<()> {
+    for hit in hits {
+        writeln!(
+            out,
+            "{:>7.3}  {}  {}\n         {}",
+            hit.score, hit.doc_id, hit.title, hit.path
+        )?;
+    }
+    if hits.is_empty() {
+        writeln!(out, "no matches")?;
+    }
+    Ok(())
+}
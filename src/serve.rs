@@ -0,0 +1,173 @@
+use crate::dedupe;
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::search::SearchIndex;
+use crate::stats;
+use crate::store::Store;
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{debug, info, warn};
+
+const DEFAULT_PAGE: usize = 100;
+const MAX_PAGE: usize = 1000;
+
+// What the API answers from: the documents plus a search index over them.
+pub struct Catalogue {
+    bundle: OutputBundle,
+    search: SearchIndex,
+    by_id: HashMap<String, usize>,
+}
+
+impl Catalogue {
+    pub fn from_bundle(bundle: OutputBundle) -> Result<Self> {
+        let mut search = SearchIndex::in_memory()?;
+        search.add_bundle(&bundle)?;
+        Ok(Self::new(bundle, search))
+    }
+
+    pub fn from_store(store: &Store) -> Result<Self> {
+        Ok(Self::new(store.bundle()?, store.open_search()?))
+    }
+
+    fn new(bundle: OutputBundle, search: SearchIndex) -> Self {
+        // Later documents win, matching how the index upserts on doc_id.
+        let by_id = bundle
+            .documents
+            .iter()
+            .enumerate()
+            .map(|(i, doc)| (doc.doc_id.clone().unwrap_or_else(|| dedupe::doc_id(doc)), i))
+            .collect();
+        Self {
+            bundle,
+            search,
+            by_id,
+        }
+    }
+
+    // Documents re-parsed after the catalogue was built; the search index is
+    // the store's and already has them.
+    pub fn upsert(&mut self, docs: Vec<ParsedDocument>) {
+        for doc in docs {
+            let id = doc.doc_id.clone().unwrap_or_else(|| dedupe::doc_id(&doc));
+            match self.by_id.get(&id) {
+                Some(&i) => self.bundle.documents[i] = doc,
+                None => {
+                    self.by_id.insert(id, self.bundle.documents.len());
+                    self.bundle.documents.push(doc);
+                }
+            }
+        }
+    }
+
+    pub fn document_count(&self) -> usize {
+        self.bundle.documents.len()
+    }
+}
+
+// Shared with whatever refreshes the catalogue while the server runs.
+pub type SharedCatalogue = Arc<RwLock<Catalogue>>;
+
+pub fn serve(listen: &str, catalogue: SharedCatalogue) -> Result<()> {
+    let server = Server::http(listen).map_err(|err| anyhow!("listen on {listen}: {err}"))?;
+    info!(address = %listen, "serving API");
+    for request in server.incoming_requests() {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let (status, body) = match catalogue.read() {
+            Ok(catalogue) => route(&catalogue, &method, &url),
+            Err(_) => (500, json!({ "error": "catalogue unavailable" })),
+        };
+        debug!(%method, %url, status, "request");
+        if let Err(err) = respond(request, status, &body) {
+            warn!(%url, error = %err, "failed to send response");
+        }
+    }
+    Ok(())
+}
+
+fn respond(request: Request, status: u16, body: &Value) -> Result<()> {
+    let bytes = serde_json::to_vec(body)?;
+    let response = Response::from_data(bytes)
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+        // Read-only data for local frontends on other origins.
+        .with_header(header("Access-Control-Allow-Origin", "*"));
+    request.respond(response).context("write response")
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+// Status and JSON body; errors carry `{"error": ...}`.
+type Reply = (u16, Value);
+
+fn route(catalogue: &Catalogue, method: &Method, url: &str) -> Reply {
+    if *method != Method::Get {
+        return (405, json!({ "error": "only GET is supported" }));
+    }
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params: HashMap<String, String> = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    let result = match path.trim_end_matches('/') {
+        "/documents" => documents(catalogue, &params),
+        "/search" => search(catalogue, &params),
+        "/stats" => number(&params, "top", 10).map(|top| ok(stats::report(&catalogue.bundle, top))),
+        path => match path.strip_prefix("/documents/") {
+            Some(id) => match catalogue.by_id.get(id) {
+                Some(&i) => Ok(ok(&catalogue.bundle.documents[i])),
+                None => Err((404, json!({ "error": format!("no document {id}") }))),
+            },
+            None => Err((404, json!({ "error": format!("no route {path}") }))),
+        },
+    };
+    result.unwrap_or_else(|err| err)
+}
+
+fn documents(catalogue: &Catalogue, params: &HashMap<String, String>) -> Result<Reply, Reply> {
+    let offset = number(params, "offset", 0)?;
+    let limit = number(params, "limit", DEFAULT_PAGE)?.min(MAX_PAGE);
+    let docs = &catalogue.bundle.documents;
+    let page: Vec<_> = docs.iter().skip(offset).take(limit).collect();
+    Ok(ok(json!({
+        "total": docs.len(),
+        "offset": offset,
+        "limit": limit,
+        "documents": page,
+    })))
+}
+
+fn search(catalogue: &Catalogue, params: &HashMap<String, String>) -> Result<Reply, Reply> {
+    let Some(q) = params.get("q").filter(|q| !q.trim().is_empty()) else {
+        return Err((400, json!({ "error": "missing `q` parameter" })));
+    };
+    let limit = number(params, "limit", 10)?.min(MAX_PAGE);
+    catalogue
+        .search
+        .search(q, limit)
+        .map(|hits| ok(json!({ "query": q, "hits": hits })))
+        .map_err(|err| (400, json!({ "error": format!("{err:#}") })))
+}
+
+fn number(params: &HashMap<String, String>, name: &str, default: usize) -> Result<usize, Reply> {
+    match params.get(name) {
+        Some(raw) => raw.parse().map_err(|_| {
+            (
+                400,
+                json!({ "error": format!("`{name}` must be a number") }),
+            )
+        }),
+        None => Ok(default),
+    }
+}
+
+fn ok(value: impl Serialize) -> Reply {
+    match serde_json::to_value(value) {
+        Ok(value) => (200, value),
+        Err(err) => (500, json!({ "error": err.to_string() })),
+    }
+}
@@ -0,0 +1,290 @@
+use crate::model::{GapRange, OutputBundle, ParsedDocument, ReleaseGaps};
+use crate::output;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+pub fn release_gaps(docs: &[ParsedDocument]) -> Option<ReleaseGaps> {
+    release_number_gaps(
+        docs.iter()
+            .filter_map(|d| d.release.as_ref().and_then(|r| r.release_number))
+            .collect(),
+    )
+}
+
+pub fn release_number_gaps(mut numbers: Vec<u64>) -> Option<ReleaseGaps> {
+    numbers.sort_unstable();
+    numbers.dedup();
+
+    let (&first, &last) = (numbers.first()?, numbers.last()?);
+    let gaps: Vec<GapRange> = numbers
+        .windows(2)
+        .filter(|w| w[1] - w[0] > 1)
+        .map(|w| GapRange {
+            start: w[0] + 1,
+            end: w[1] - 1,
+        })
+        .collect();
+
+    Some(ReleaseGaps {
+        first,
+        last,
+        present: numbers.len(),
+        missing: gaps.iter().map(|g| g.end - g.start + 1).sum(),
+        gaps,
+    })
+}
+
+// `stats` subcommand: aggregates over a saved bundle.
+#[derive(Debug, Serialize)]
+pub struct BundleReport {
+    pub releases: usize,
+    pub parse_errors: usize,
+    pub undated: usize,
+    pub releases_per_month: BTreeMap<String, usize>,
+    pub repack_bytes_total: u64,
+    pub original_bytes_total: u64,
+    pub top_genres: Vec<Count>,
+    pub top_mirror_domains: Vec<Count>,
+    pub languages: Vec<Count>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_gaps: Option<ReleaseGaps>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Count {
+    pub name: String,
+    pub count: usize,
+}
+
+pub fn report(bundle: &OutputBundle, top: usize) -> BundleReport {
+    let docs = &bundle.documents;
+    let mut per_month = BTreeMap::new();
+    let mut undated = 0;
+    let mut genres = BTreeMap::new();
+    let mut mirrors = BTreeMap::new();
+    let mut languages = BTreeMap::new();
+    let (mut repack, mut original) = (0, 0);
+
+    for doc in docs {
+        match month(doc) {
+            Some(month) => *per_month.entry(month.to_string()).or_insert(0) += 1,
+            None => undated += 1,
+        }
+        for genre in output::genres(doc) {
+            *genres.entry(genre).or_insert(0) += 1;
+        }
+        for host in output::mirror_domains(doc) {
+            *mirrors.entry(host).or_insert(0) += 1;
+        }
+        if let Some(release) = &doc.release {
+            repack += release.repack_size_bytes_min.unwrap_or_default();
+            original += release.original_size_bytes.unwrap_or_default();
+            for language in &release.languages {
+                *languages.entry(language.name.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    BundleReport {
+        releases: docs.len(),
+        parse_errors: bundle.stats.parsed_err,
+        undated,
+        releases_per_month: per_month,
+        repack_bytes_total: repack,
+        original_bytes_total: original,
+        top_genres: ranked(genres, top),
+        top_mirror_domains: ranked(mirrors, top),
+        languages: ranked(languages, top),
+        release_gaps: release_gaps(docs),
+    }
+}
+
+// `YYYY-MM` of the post date.
+fn month(doc: &ParsedDocument) -> Option<&str> {
+    doc.post.as_ref().and_then(|p| {
+        p.entry_datetime_rfc3339
+            .as_deref()
+            .or(p.entry_datetime.as_deref())
+            .and_then(|d| d.get(..7))
+    })
+}
+
+// `timeline` subcommand: releases per publication month, with every month
+// between the first and last present so quiet periods show up.
+#[derive(Debug, Serialize)]
+pub struct Timeline {
+    pub months: Vec<TimelineMonth>,
+    pub undated: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineMonth {
+    pub month: String,
+    pub releases: usize,
+    pub repack_bytes: u64,
+    pub cumulative_repack_bytes: u64,
+}
+
+pub fn timeline(bundle: &OutputBundle) -> Timeline {
+    let mut per_month: BTreeMap<(i32, u32), (usize, u64)> = BTreeMap::new();
+    let mut undated = 0;
+    for doc in &bundle.documents {
+        let Some(key) = month(doc).and_then(parse_month) else {
+            undated += 1;
+            continue;
+        };
+        let entry = per_month.entry(key).or_default();
+        entry.0 += 1;
+        entry.1 += doc
+            .release
+            .as_ref()
+            .and_then(|r| r.repack_size_bytes_min)
+            .unwrap_or_default();
+    }
+
+    let mut months = Vec::new();
+    let (Some(&first), Some(&last)) = (per_month.keys().next(), per_month.keys().last()) else {
+        return Timeline { months, undated };
+    };
+    let mut cumulative = 0;
+    let (mut year, mut month) = first;
+    while (year, month) <= last {
+        let (releases, repack_bytes) = per_month.get(&(year, month)).copied().unwrap_or_default();
+        cumulative += repack_bytes;
+        months.push(TimelineMonth {
+            month: format!("{year:04}-{month:02}"),
+            releases,
+            repack_bytes,
+            cumulative_repack_bytes: cumulative,
+        });
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    Timeline { months, undated }
+}
+
+fn parse_month(month: &str) -> Option<(i32, u32)> {
+    let (year, month) = month.split_once('-')?;
+    let month: u32 = month.parse().ok()?;
+    (1..=12)
+        .contains(&month)
+        .then_some((year.parse().ok()?, month))
+}
+
+// One bar per month, scaled so the busiest month is `width` wide.
+pub fn write_timeline_chart<W: Write>(
+    out: &mut W,
+    timeline: &Timeline,
+    width: usize,
+) -> Result<()> {
+    let max = timeline
+        .months
+        .iter()
+        .map(|m| m.releases)
+        .max()
+        .unwrap_or(0);
+    let count_width = max.to_string().len();
+    for m in &timeline.months {
+        let bar = if max == 0 {
+            0
+        } else {
+            (m.releases * width).div_ceil(max)
+        };
+        writeln!(
+            out,
+            "{}  {:>count_width$}  {:<width$}  {}",
+            m.month,
+            m.releases,
+            "#".repeat(bar),
+            output::human_size(m.cumulative_repack_bytes)
+        )?;
+    }
+    let total: usize = timeline.months.iter().map(|m| m.releases).sum();
+    writeln!(
+        out,
+        "\n{total} releases over {} months, {} undated",
+        timeline.months.len(),
+        timeline.undated
+    )?;
+    Ok(())
+}
+
+// Most frequent first; ties keep name order.
+fn ranked(counts: BTreeMap<String, usize>, top: usize) -> Vec<Count> {
+    let mut ranked: Vec<Count> = counts
+        .into_iter()
+        .map(|(name, count)| Count { name, count })
+        .collect();
+    ranked.sort_by_key(|c| std::cmp::Reverse(c.count));
+    ranked.truncate(top);
+    ranked
+}
+
+pub fn write_report_table<W: Write>(out: &mut W, report: &BundleReport) -> Result<()> {
+    writeln!(
+        out,
+        "{} releases, {} parse errors",
+        report.releases, report.parse_errors
+    )?;
+    writeln!(
+        out,
+        "repack total {}, original total {}",
+        output::human_size(report.repack_bytes_total),
+        output::human_size(report.original_bytes_total)
+    )?;
+    if let Some(gaps) = &report.release_gaps {
+        writeln!(
+            out,
+            "release numbers #{}-#{}, {} missing",
+            gaps.first, gaps.last, gaps.missing
+        )?;
+    }
+
+    let mut months: Vec<(String, usize)> = report
+        .releases_per_month
+        .iter()
+        .map(|(month, count)| (month.clone(), *count))
+        .collect();
+    if report.undated > 0 {
+        months.push(("undated".to_string(), report.undated));
+    }
+    write_section(out, "Releases per month", &months)?;
+    let pairs = |counts: &[Count]| -> Vec<(String, usize)> {
+        counts.iter().map(|c| (c.name.clone(), c.count)).collect()
+    };
+    write_section(out, "Top genres", &pairs(&report.top_genres))?;
+    write_section(
+        out,
+        "Top mirror domains",
+        &pairs(&report.top_mirror_domains),
+    )?;
+    write_section(out, "Languages", &pairs(&report.languages))?;
+    Ok(())
+}
+
+fn write_section<W: Write>(out: &mut W, title: &str, rows: &[(String, usize)]) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "\n{title}")?;
+    let name_width = rows
+        .iter()
+        .map(|(n, _)| n.chars().count())
+        .max()
+        .unwrap_or(0);
+    let count_width = rows
+        .iter()
+        .map(|(_, c)| c.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for (name, count) in rows {
+        let pad = name_width - name.chars().count();
+        writeln!(out, "  {name}{}  {count:>count_width$}", " ".repeat(pad))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::bundle;
+use crate::config::OutputFormat;
+use crate::model::OutputBundle;
+use crate::output;
+use crate::search::SearchIndex;
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const DATABASE: &str = "catalogue.db";
+const SEARCH_DIR: &str = "search";
+
+// The directory `index` maintains: the `sqlite` output tables (documents
+// upserted on doc_id, full JSON in `data`) plus a tantivy index kept in step,
+// so later commands skip re-reading bundles.
+pub struct Store {
+    dir: PathBuf,
+    search: SearchIndex,
+}
+
+impl Store {
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+        output::sqlite::open_database(&dir.join(DATABASE))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            search: SearchIndex::open_or_create(&dir.join(SEARCH_DIR))?,
+        })
+    }
+
+    // Opens a store `index` has already written, rather than creating an
+    // empty one at a mistyped path.
+    pub fn open_existing(dir: &Path) -> Result<Self> {
+        if !dir.join(DATABASE).is_file() {
+            bail!(
+                "no index at {}; build one with `game-scraper index`",
+                dir.display()
+            );
+        }
+        Self::open(dir)
+    }
+
+    pub fn ingest(&mut self, bundle: &OutputBundle) -> Result<()> {
+        output::sqlite::write_database(&self.database(), bundle)?;
+        self.search.upsert_bundle(bundle)
+    }
+
+    pub fn bundle(&self) -> Result<OutputBundle> {
+        bundle::load_as(&self.database(), Some(OutputFormat::Sqlite))
+    }
+
+    pub fn search(&self) -> &SearchIndex {
+        &self.search
+    }
+
+    // A second handle on the search index; it sees every commit `ingest`
+    // makes, so a long-running reader can share the directory with a writer.
+    pub fn open_search(&self) -> Result<SearchIndex> {
+        SearchIndex::open_or_create(&self.dir.join(SEARCH_DIR))
+    }
+
+    // Size and mtime of each stored document's source file, keyed by path.
+    pub fn sources(&self) -> Result<HashMap<String, (u64, Option<i64>)>> {
+        let conn = rusqlite::Connection::open(self.database())?;
+        let mut stmt = conn.prepare(
+            "SELECT path, json_extract(data, '$.source.bytes'), json_extract(data, '$.source.mtime')
+             FROM documents",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (
+                    row.get::<_, Option<i64>>(1)?.unwrap_or(0) as u64,
+                    row.get(2)?,
+                ),
+            ))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn document_count(&self) -> Result<usize> {
+        let conn = rusqlite::Connection::open(self.database())?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    fn database(&self) -> PathBuf {
+        self.dir.join(DATABASE)
+    }
+}
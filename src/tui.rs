@@ -0,0 +1,362 @@
+use crate::model::{OutputBundle, ParsedDocument};
+use crate::output::{genres, human_size, mirror_links, title};
+use anyhow::Result;
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+
+const HELP: &str =
+    "/ filter  j/k move  J/K scroll  m copy magnet  M copy all magnets  u copy URL  q quit";
+
+struct App {
+    docs: Vec<ParsedDocument>,
+    titles: Vec<String>,
+    // Lowercased title and genres, matched against the filter.
+    haystacks: Vec<String>,
+    filter: String,
+    editing: bool,
+    matches: Vec<usize>,
+    list: ListState,
+    scroll: u16,
+    status: String,
+    // On X11/Wayland the copied text is served by this process, so the
+    // clipboard handle lives as long as the browser does.
+    clipboard: Option<arboard::Clipboard>,
+}
+
+pub fn run(bundle: OutputBundle) -> Result<()> {
+    let mut app = App::new(bundle.documents);
+    let mut terminal = ratatui::try_init()?;
+    let result = app.event_loop(&mut terminal);
+    ratatui::try_restore()?;
+    result
+}
+
+impl App {
+    fn new(docs: Vec<ParsedDocument>) -> Self {
+        let titles: Vec<String> = docs.iter().map(title).collect();
+        let haystacks = docs
+            .iter()
+            .zip(&titles)
+            .map(|(doc, title)| format!("{title} {}", genres(doc).join(" ")).to_lowercase())
+            .collect();
+        let mut app = Self {
+            docs,
+            titles,
+            haystacks,
+            filter: String::new(),
+            editing: false,
+            matches: Vec::new(),
+            list: ListState::default(),
+            scroll: 0,
+            status: HELP.to_string(),
+            clipboard: None,
+        };
+        app.refilter();
+        app
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    // Returns false when the browser should exit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+        if self.editing {
+            match key.code {
+                KeyCode::Enter => self.editing = false,
+                KeyCode::Esc => {
+                    self.editing = false;
+                    self.filter.clear();
+                    self.refilter();
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.refilter();
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.refilter();
+                }
+                KeyCode::Down => self.move_by(1),
+                KeyCode::Up => self.move_by(-1),
+                _ => {}
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.editing = true,
+            KeyCode::Char('j') | KeyCode::Down => self.move_by(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_by(-1),
+            KeyCode::PageDown => self.move_by(10),
+            KeyCode::PageUp => self.move_by(-10),
+            KeyCode::Char('g') | KeyCode::Home => self.select(0),
+            KeyCode::Char('G') | KeyCode::End => self.select(self.matches.len().saturating_sub(1)),
+            KeyCode::Char('J') => self.scroll = self.scroll.saturating_add(3),
+            KeyCode::Char('K') => self.scroll = self.scroll.saturating_sub(3),
+            KeyCode::Char('m') => {
+                if let Some(magnet) = self.current().and_then(|d| d.magnet_links.first()) {
+                    let magnet = magnet.clone();
+                    self.copy(magnet, "magnet");
+                } else {
+                    self.status = "no magnet links".into();
+                }
+            }
+            KeyCode::Char('M') => match self.current().map(|d| d.magnet_links.join("\n")) {
+                Some(magnets) if !magnets.is_empty() => self.copy(magnets, "all magnets"),
+                _ => self.status = "no magnet links".into(),
+            },
+            KeyCode::Char('u') => {
+                match self
+                    .current()
+                    .and_then(|d| d.page.as_ref()?.canonical_url.clone())
+                {
+                    Some(url) => self.copy(url, "URL"),
+                    None => self.status = "no canonical URL".into(),
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn refilter(&mut self) {
+        let needle: Vec<char> = self.filter.to_lowercase().chars().collect();
+        let mut scored: Vec<(i64, usize)> = self
+            .haystacks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, hay)| fuzzy_score(&needle, hay).map(|score| (score, i)))
+            .collect();
+        if !needle.is_empty() {
+            scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+        }
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.select(0);
+    }
+
+    fn select(&mut self, position: usize) {
+        self.scroll = 0;
+        self.list
+            .select((!self.matches.is_empty()).then(|| position.min(self.matches.len() - 1)));
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let current = self.list.selected().unwrap_or(0);
+        self.select(current.saturating_add_signed(delta));
+    }
+
+    fn current(&self) -> Option<&ParsedDocument> {
+        let index = *self.matches.get(self.list.selected()?)?;
+        self.docs.get(index)
+    }
+
+    fn copy(&mut self, text: String, what: &str) {
+        if self.clipboard.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(err) => {
+                    self.status = format!("clipboard unavailable: {err}");
+                    return;
+                }
+            }
+        }
+        if let Some(clipboard) = &mut self.clipboard {
+            self.status = match clipboard.set_text(text) {
+                Ok(()) => format!("copied {what} to the clipboard"),
+                Err(err) => format!("copy failed: {err}"),
+            };
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, footer] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body);
+
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|&i| ListItem::new(self.titles[i].as_str()))
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(
+                " Releases {}/{} ",
+                self.matches.len(),
+                self.docs.len()
+            )))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let detail = self.current().map(detail).unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(detail)
+                .block(Block::bordered().title(" Details "))
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            detail_area,
+        );
+
+        let footer_line = if self.editing || !self.filter.is_empty() {
+            Line::from(vec![
+                Span::from("filter: ").bold(),
+                Span::from(self.filter.as_str()),
+                Span::from(if self.editing { "▏" } else { "" }),
+                Span::from(format!("   {}", self.status)).dim(),
+            ])
+        } else {
+            Line::from(self.status.as_str()).dim()
+        };
+        frame.render_widget(Paragraph::new(footer_line), footer);
+    }
+}
+
+fn detail(doc: &ParsedDocument) -> Text<'static> {
+    let mut lines = vec![Line::from(title(doc)).bold(), Line::default()];
+    let mut field = |name: &str, value: Option<String>| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            lines.push(Line::from(vec![
+                Span::from(format!("{name:<12}")).dim(),
+                Span::from(value),
+            ]));
+        }
+    };
+    let post = doc.post.as_ref();
+    let release = doc.release.as_ref();
+    field(
+        "Release",
+        release
+            .and_then(|r| r.release_number)
+            .map(|n| format!("#{n}")),
+    );
+    field(
+        "Date",
+        post.and_then(|p| {
+            p.entry_datetime_rfc3339
+                .clone()
+                .or(p.entry_datetime.clone())
+        }),
+    );
+    field("Group", release.and_then(|r| r.group.clone()));
+    field(
+        "Version",
+        release
+            .and_then(|r| r.version.as_ref())
+            .map(|v| v.raw.clone()),
+    );
+    field("Genres", Some(genres(doc).join(", ")));
+    field("Companies", release.map(|r| r.companies.join(", ")));
+    field(
+        "Languages",
+        release.map(|r| {
+            r.languages
+                .iter()
+                .map(|l| l.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }),
+    );
+    field(
+        "Original",
+        release.and_then(|r| r.original_size_bytes).map(human_size),
+    );
+    field(
+        "Repack",
+        release.and_then(
+            |r| match (r.repack_size_bytes_min, r.repack_size_bytes_max) {
+                (Some(min), Some(max)) if min != max => {
+                    Some(format!("{} - {}", human_size(min), human_size(max)))
+                }
+                (Some(bytes), _) | (None, Some(bytes)) => Some(human_size(bytes)),
+                _ => None,
+            },
+        ),
+    );
+    field(
+        "URL",
+        doc.page.as_ref().and_then(|p| p.canonical_url.clone()),
+    );
+    field("doc_id", doc.doc_id.clone());
+    field("Path", Some(doc.source.path.clone()));
+
+    for spoiler in &doc.spoiler_sections {
+        lines.push(Line::default());
+        lines.push(Line::from(spoiler.title.clone()).bold());
+        lines.extend(spoiler.text.lines().map(|l| Line::from(l.to_string())));
+    }
+
+    let links: Vec<(&str, &str)> = doc
+        .magnet_links
+        .iter()
+        .map(|l| ("magnet", l.as_str()))
+        .chain(
+            doc.torrent_file_links
+                .iter()
+                .map(|l| ("torrent", l.as_str())),
+        )
+        .chain(
+            doc.direct_download_links
+                .iter()
+                .map(|l| ("direct", l.as_str())),
+        )
+        .chain(mirror_links(doc).map(|l| ("mirror", l.href.as_str())))
+        .collect();
+    if !links.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from("Links").bold());
+        for (kind, href) in links {
+            lines.push(Line::from(vec![
+                Span::from(format!("{kind:<8}")).dim(),
+                Span::from(href.to_string()),
+            ]));
+        }
+    }
+    Text::from(lines)
+}
+
+// Case-insensitive subsequence match; consecutive characters and matches at
+// word starts score higher. `needle` is already lowercased.
+fn fuzzy_score(needle: &[char], haystack: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut wanted = needle.iter().peekable();
+    let mut previous_matched = false;
+    let mut previous: Option<char> = None;
+    for c in haystack.chars() {
+        let Some(&&next) = wanted.peek() else {
+            break;
+        };
+        if c == next {
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 6;
+            }
+            wanted.next();
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+    wanted.peek().is_none().then_some(score)
+}
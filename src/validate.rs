@@ -0,0 +1,267 @@
+use crate::bundle;
+use crate::config::OutputFormat;
+use crate::model::{ParseError, ParsedDocument, SCHEMA_VERSION, Stats};
+use crate::output;
+use crate::parser::links;
+use anyhow::{Context, Result, bail};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::path::Path;
+
+const BUNDLE_FIELDS: &[&str] = &[
+    "schema_version",
+    "tool",
+    "stats",
+    "documents",
+    "errors",
+    "infohash_index",
+];
+
+static STATS_SCHEMA: Lazy<Value> = Lazy::new(output::schema_value::<Stats>);
+static ERROR_SCHEMA: Lazy<Value> = Lazy::new(output::schema_value::<ParseError>);
+static DOCUMENT_SCHEMA: Lazy<Value> = Lazy::new(output::schema_value::<ParsedDocument>);
+
+#[derive(Debug)]
+pub struct Issue {
+    pub location: String,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Checker {
+    issues: Vec<Issue>,
+}
+
+impl Checker {
+    fn push(&mut self, location: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(Issue {
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+}
+
+// Checks a saved bundle (JSON, NDJSON, MessagePack or CBOR, compressed or
+// not) against this build's model: every value must deserialize, no field may
+// be unknown, and documents must satisfy the invariants the parser guarantees.
+pub fn validate_file(path: &Path) -> Result<Vec<Issue>> {
+    let bytes = bundle::read_input(path)?;
+    let mut checker = Checker::default();
+
+    match bundle::format_for_path(path) {
+        Some(format @ (OutputFormat::Msgpack | OutputFormat::Cbor)) => {
+            let values = bundle::decode_values(&bytes, format)?;
+            match values.as_slice() {
+                [Value::Object(bundle)] if bundle.contains_key("documents") => {
+                    check_bundle(&mut checker, bundle);
+                }
+                records => {
+                    for (i, record) in records.iter().enumerate() {
+                        check_record(&mut checker, &format!("record {}", i + 1), record);
+                    }
+                }
+            }
+        }
+        Some(OutputFormat::Json | OutputFormat::Ndjson) | None => {
+            let text = String::from_utf8(bytes).context("input is not UTF-8")?;
+            match serde_json::from_str::<Value>(&text) {
+                Ok(Value::Object(bundle)) if bundle.contains_key("documents") => {
+                    check_bundle(&mut checker, &bundle);
+                }
+                _ => {
+                    for (i, line) in text.lines().enumerate() {
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        let location = format!("line {}", i + 1);
+                        match serde_json::from_str(line) {
+                            Ok(record) => check_record(&mut checker, &location, &record),
+                            Err(err) => checker.push(location, format!("invalid JSON: {err}")),
+                        }
+                    }
+                }
+            }
+        }
+        Some(other) => bail!(
+            "{} output is a flattened table and cannot be validated",
+            format!("{other:?}").to_lowercase()
+        ),
+    }
+    Ok(checker.issues)
+}
+
+fn check_bundle(checker: &mut Checker, bundle: &serde_json::Map<String, Value>) {
+    for key in bundle.keys() {
+        if !BUNDLE_FIELDS.contains(&key.as_str()) {
+            checker.push(key.as_str(), "unknown bundle field");
+        }
+    }
+    check_schema_version(checker, "schema_version", bundle.get("schema_version"));
+    if let Some(stats) = bundle.get("stats") {
+        check_as::<Stats>(checker, "stats", stats, &STATS_SCHEMA);
+    }
+    let errors = bundle.get("errors").and_then(Value::as_array);
+    for (i, err) in errors.into_iter().flatten().enumerate() {
+        check_as::<ParseError>(checker, &format!("errors[{i}]"), err, &ERROR_SCHEMA);
+    }
+    let docs = bundle.get("documents").and_then(Value::as_array);
+    for (i, doc) in docs.into_iter().flatten().enumerate() {
+        check_document(checker, &format!("documents[{i}]"), doc);
+    }
+    check_stats(checker, bundle);
+}
+
+// One record of an NDJSON (or `--records`) stream.
+fn check_record(checker: &mut Checker, location: &str, record: &Value) {
+    match record.get("type").and_then(Value::as_str) {
+        Some("error") => {
+            check_as::<ParseError>(checker, location, &record["data"], &ERROR_SCHEMA);
+        }
+        Some("summary") => {
+            check_schema_version(checker, location, record.get("schema_version"));
+            check_as::<Stats>(checker, location, &record["data"], &STATS_SCHEMA);
+        }
+        Some(other) => checker.push(location, format!("unknown record type `{other}`")),
+        None => check_document(checker, location, record),
+    }
+}
+
+fn check_schema_version(checker: &mut Checker, location: &str, version: Option<&Value>) {
+    match version.and_then(Value::as_u64) {
+        Some(v) if v > SCHEMA_VERSION as u64 => checker.push(
+            location,
+            format!("schema_version {v} is newer than this build supports ({SCHEMA_VERSION})"),
+        ),
+        Some(_) => {}
+        None => checker.push(location, "missing or non-integer schema_version"),
+    }
+}
+
+fn check_as<T: DeserializeOwned + serde::Serialize>(
+    checker: &mut Checker,
+    location: &str,
+    value: &Value,
+    schema: &Value,
+) -> Option<T> {
+    match serde_path_to_error::deserialize::<_, T>(value) {
+        Ok(parsed) => {
+            // With the empty fields filled back in, re-serializing yields every
+            // known field, so anything left over in the input is unknown.
+            if let Ok(mut known) = serde_json::to_value(&parsed) {
+                output::fill_nulls(&mut known, schema);
+                unknown_fields(checker, location, value, &known);
+            }
+            Some(parsed)
+        }
+        Err(err) => {
+            let path = err.path().to_string();
+            let location = if path == "." {
+                location.to_string()
+            } else {
+                format!("{location}.{path}")
+            };
+            checker.push(location, err.into_inner().to_string());
+            None
+        }
+    }
+}
+
+fn unknown_fields(checker: &mut Checker, location: &str, input: &Value, known: &Value) {
+    match (input, known) {
+        (Value::Object(input), Value::Object(known)) => {
+            for (key, value) in input {
+                let child = format!("{location}.{key}");
+                match known.get(key) {
+                    Some(known) => unknown_fields(checker, &child, value, known),
+                    None => checker.push(child, "unknown field"),
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(known)) => {
+            for (i, (value, known)) in input.iter().zip(known).enumerate() {
+                unknown_fields(checker, &format!("{location}[{i}]"), value, known);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_document(checker: &mut Checker, location: &str, value: &Value) {
+    let Some(doc) = check_as::<ParsedDocument>(checker, location, value, &DOCUMENT_SCHEMA) else {
+        return;
+    };
+    let mut violation = |message: String| checker.push(location, message);
+
+    if doc.torrent_file == Some(true) && doc.torrent_file_links.is_empty() {
+        violation("torrent_file is true but torrent_file_links is empty".into());
+    }
+    let from_magnets = links::magnet_infohashes(&doc.magnet_links);
+    for hash in &doc.infohashes {
+        if !from_magnets.iter().any(|h| h.eq_ignore_ascii_case(hash)) {
+            violation(format!(
+                "infohash {hash} does not appear in any magnet link"
+            ));
+        }
+    }
+    if let Some(release) = &doc.release
+        && let (Some(min), Some(max)) =
+            (release.repack_size_bytes_min, release.repack_size_bytes_max)
+        && min > max
+    {
+        violation(format!(
+            "repack_size_bytes_min {min} exceeds repack_size_bytes_max {max}"
+        ));
+    }
+    if let Some(confidence) = &doc.confidence
+        && !(0.0..=1.0).contains(&confidence.overall)
+    {
+        violation(format!(
+            "confidence.overall {} is outside 0..=1",
+            confidence.overall
+        ));
+    }
+    if let Some(post) = &doc.post
+        && let Some(ts) = &post.entry_datetime_rfc3339
+        && chrono::DateTime::parse_from_rfc3339(ts).is_err()
+    {
+        violation(format!(
+            "post.entry_datetime_rfc3339 `{ts}` is not RFC 3339"
+        ));
+    }
+    if doc.source.sha256.len() != 64 || !doc.source.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        violation("source.sha256 is not a hex SHA-256 digest".into());
+    }
+}
+
+fn check_stats(checker: &mut Checker, bundle: &serde_json::Map<String, Value>) {
+    let Some(stats) = bundle
+        .get("stats")
+        .and_then(|s| serde_json::from_value::<Stats>(s.clone()).ok())
+    else {
+        return;
+    };
+    let errors = bundle
+        .get("errors")
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len);
+    let documents = bundle
+        .get("documents")
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len);
+    if stats.parsed_err != errors {
+        checker.push(
+            "stats.parsed_err",
+            format!("{} but the bundle has {errors} errors", stats.parsed_err),
+        );
+    }
+    if documents > stats.parsed_ok {
+        checker.push(
+            "stats.parsed_ok",
+            format!(
+                "{} but the bundle has {documents} documents",
+                stats.parsed_ok
+            ),
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::fs;
+use crate::model::OutputBundle;
+use crate::parser::util::sha256_hex;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Changed,
+    Moved,
+    Missing,
+    New,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub status: Status,
+    pub path: String,
+    // Where a moved file is now.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub now_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub unchanged: usize,
+    pub findings: Vec<Finding>,
+}
+
+impl VerifyReport {
+    // New files are reported but are not an integrity problem.
+    pub fn failures(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.status != Status::New)
+            .count()
+    }
+}
+
+// Re-hashes every `source.path` in the bundle. With input directories, files
+// whose hash turns up under another path count as moved, and files the
+// bundle does not know are listed as new.
+pub fn run(
+    bundle: &OutputBundle,
+    inputs: &[PathBuf],
+    recursive: bool,
+    follow_symlinks: bool,
+) -> Result<VerifyReport> {
+    let recorded: BTreeMap<&str, &str> = bundle
+        .documents
+        .iter()
+        .map(|doc| (doc.source.path.as_str(), doc.source.sha256.as_str()))
+        .filter(|(_, sha256)| !sha256.is_empty())
+        .collect();
+
+    let mut on_disk: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    let mut scanned = Vec::new();
+    if !inputs.is_empty() {
+        for path in
+            fs::collect_html_inputs(inputs, recursive, follow_symlinks).context("collect inputs")?
+        {
+            let sha256 = hash_file(&path)?;
+            on_disk.entry(sha256).or_default().push(path.clone());
+            scanned.push(path);
+        }
+    }
+    let known: BTreeSet<PathBuf> = recorded.keys().map(|p| canonical(Path::new(p))).collect();
+
+    let mut findings = Vec::new();
+    let mut unchanged = 0;
+    for (&path, &sha256) in &recorded {
+        let status = if Path::new(path).exists() {
+            if hash_file(Path::new(path))? == sha256 {
+                unchanged += 1;
+                continue;
+            }
+            Status::Changed
+        } else {
+            Status::Missing
+        };
+        // A copy elsewhere means the save was moved or renamed, not lost.
+        let now_at = (status == Status::Missing)
+            .then(|| on_disk.get(sha256))
+            .flatten()
+            .and_then(|paths| paths.iter().find(|p| !known.contains(&canonical(p))))
+            .map(|p| p.display().to_string());
+        findings.push(Finding {
+            status: if now_at.is_some() {
+                Status::Moved
+            } else {
+                status
+            },
+            path: path.to_string(),
+            now_at,
+        });
+    }
+    let moved_to: BTreeSet<&str> = findings
+        .iter()
+        .filter_map(|f| f.now_at.as_deref())
+        .collect();
+    let new: Vec<Finding> = scanned
+        .iter()
+        .filter(|p| !known.contains(&canonical(p)))
+        .map(|p| p.display().to_string())
+        .filter(|p| !moved_to.contains(p.as_str()))
+        .map(|path| Finding {
+            status: Status::New,
+            path,
+            now_at: None,
+        })
+        .collect();
+    findings.extend(new);
+    findings.sort_by(|a, b| (a.status, &a.path).cmp(&(b.status, &b.path)));
+
+    Ok(VerifyReport {
+        checked: recorded.len(),
+        unchanged,
+        findings,
+    })
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+    Ok(sha256_hex(&bytes))
+}
+
+// Bundles record paths as they were given on the command line; compare the
+// resolved forms so `tmp/a.html` and `./tmp/a.html` are the same file.
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+pub fn write_report_table<W: Write>(out: &mut W, report: &VerifyReport) -> Result<()> {
+    for finding in &report.findings {
+        let label = match finding.status {
+            Status::Changed => "changed",
+            Status::Moved => "moved",
+            Status::Missing => "missing",
+            Status::New => "new",
+        };
+        match &finding.now_at {
+            Some(now_at) => writeln!(out, "{label:<8} {} -> {now_at}", finding.path)?,
+            None => writeln!(out, "{label:<8} {}", finding.path)?,
+        }
+    }
+    let count = |status| {
+        report
+            .findings
+            .iter()
+            .filter(|f| f.status == status)
+            .count()
+    };
+    writeln!(
+        out,
+        "\n{} files checked: {} unchanged, {} changed, {} moved, {} missing, {} new",
+        report.checked,
+        report.unchanged,
+        count(Status::Changed),
+        count(Status::Moved),
+        count(Status::Missing),
+        count(Status::New)
+    )?;
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use crate::config::Config;
+use anyhow::{Context, Result, bail};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+// `[scrape]` toggles asked about together; pressing Enter leaves the defaults
+// of every field in the group alone.
+const FIELD_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "page metadata (title, canonical URL, meta tags, head links, breadcrumbs)",
+        &[
+            "page_title",
+            "canonical_url",
+            "meta_tags",
+            "head_links",
+            "hreflang",
+            "generator",
+            "breadcrumbs",
+            "content_language",
+        ],
+    ),
+    (
+        "post metadata (ID, slug, categories, tags, dates, author)",
+        &[
+            "post_id",
+            "slug",
+            "categories",
+            "genre_taxonomy",
+            "tag_aliases",
+            "wp_tags",
+            "entry_title",
+            "entry_datetime",
+            "entry_modified",
+            "datetime_normalize",
+            "author",
+            "comments_count",
+        ],
+    ),
+    ("comments and polls", &["static_comments", "poll"]),
+    (
+        "release details (number, version, genres, companies, languages, sizes)",
+        &[
+            "release_number",
+            "release_number_gaps",
+            "game_title_line",
+            "release_group",
+            "title_slug",
+            "version",
+            "genres_tags",
+            "companies",
+            "developers_publishers",
+            "languages",
+            "languages_parsed",
+            "original_size",
+            "repack_size",
+            "size_bytes",
+        ],
+    ),
+    (
+        "store data (pricing, age ratings, Steam/GOG IDs, review scores)",
+        &[
+            "pricing",
+            "age_ratings",
+            "steam_appids",
+            "gog_ids",
+            "review_scores",
+        ],
+    ),
+    (
+        "download links (torrent, magnet, mirror sections)",
+        &[
+            "spoiler_sections",
+            "direct_download_links",
+            "download_section_presence",
+            "download_sections",
+            "torrent_file",
+            "torrent_file_name",
+            "torrent_file_link",
+            "magnet",
+        ],
+    ),
+    ("images", &["images"]),
+    ("raw HTML of the post body", &["raw_html"]),
+    ("site donation addresses", &["site_donations"]),
+];
+
+const OUTPUT_FORMATS: &[&str] = &[
+    "json", "ndjson", "csv", "tsv", "markdown", "table", "msgpack", "cbor", "xlsx", "sqlite",
+    "parquet", "arrow",
+];
+
+// `init-config --interactive`: asks about the major toggles and returns the
+// TOML to write to `path`, holding only what differs from the defaults.
+pub fn run<R: BufRead, W: Write>(input: &mut R, out: &mut W, path: &Path) -> Result<String> {
+    if path.exists()
+        && !ask_bool(
+            input,
+            out,
+            &format!("{} exists. Overwrite it?", path.display()),
+            Some(false),
+        )?
+    {
+        bail!("kept existing {}", path.display());
+    }
+    let defaults = toml::Table::try_from(Config::default()).context("serialize defaults")?;
+    let mut table = defaults.clone();
+
+    writeln!(
+        out,
+        "Press Enter to keep the default shown in capitals or brackets.\n"
+    )?;
+
+    let wordpress = ask_bool(
+        input,
+        out,
+        "Parse the WordPress release layout (FitGirl-style posts)?",
+        Some(bool_at(&defaults, "profile", "wordpress_release_layout")),
+    )?;
+    set(&mut table, "profile", "wordpress_release_layout", wordpress);
+
+    writeln!(out, "\nWhich fields should be extracted?")?;
+    for (label, fields) in FIELD_GROUPS {
+        if let Some(on) = ask_optional_bool(input, out, &format!("  {label}"))? {
+            for field in *fields {
+                set(&mut table, "scrape", field, on);
+            }
+        }
+    }
+
+    writeln!(out)?;
+    let default_format = defaults["output"]["format"].as_str().unwrap_or("json");
+    let format = ask_choice(input, out, "Output format", OUTPUT_FORMATS, default_format)?;
+    table["output"]["format"] = toml::Value::String(format.clone());
+    if format == "json" {
+        let pretty = ask_bool(
+            input,
+            out,
+            "Pretty-print JSON?",
+            Some(bool_at(&defaults, "output", "pretty_json")),
+        )?;
+        set(&mut table, "output", "pretty_json", pretty);
+    }
+
+    let cfg: Config = table.try_into().context("build config from answers")?;
+    let body = cfg.to_diff_toml()?;
+    Ok(format!(
+        "# Written by `game-scraper init-config --interactive`. Keys not listed keep\n\
+         # their defaults; `game-scraper print-config` shows the full config.\n{}{body}",
+        if body.is_empty() { "" } else { "\n" }
+    ))
+}
+
+fn bool_at(table: &toml::Table, section: &str, key: &str) -> bool {
+    table[section][key].as_bool().unwrap_or_default()
+}
+
+fn set(table: &mut toml::Table, section: &str, key: &str, value: bool) {
+    table[section][key] = toml::Value::Boolean(value);
+}
+
+fn read_answer<R: BufRead, W: Write>(input: &mut R, out: &mut W, prompt: &str) -> Result<String> {
+    write!(out, "{prompt} ")?;
+    out.flush()?;
+    let mut line = String::new();
+    // End of input takes the default for every remaining question.
+    input.read_line(&mut line).context("read answer")?;
+    Ok(line.trim().to_ascii_lowercase())
+}
+
+fn ask_bool<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+    question: &str,
+    default: Option<bool>,
+) -> Result<bool> {
+    let hint = match default {
+        Some(true) => "[Y/n]",
+        Some(false) => "[y/N]",
+        None => "[y/n]",
+    };
+    for _ in 0..3 {
+        match (
+            read_answer(input, out, &format!("{question} {hint}"))?.as_str(),
+            default,
+        ) {
+            ("y" | "yes", _) => return Ok(true),
+            ("n" | "no", _) => return Ok(false),
+            ("", Some(default)) => return Ok(default),
+            _ => writeln!(out, "  please answer y or n")?,
+        }
+    }
+    bail!("no valid answer to {question:?}")
+}
+
+// Enter keeps the per-field defaults.
+fn ask_optional_bool<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+    question: &str,
+) -> Result<Option<bool>> {
+    for _ in 0..3 {
+        match read_answer(input, out, &format!("{question} [y/n/default]"))?.as_str() {
+            "y" | "yes" => return Ok(Some(true)),
+            "n" | "no" => return Ok(Some(false)),
+            "" | "d" | "default" => return Ok(None),
+            _ => writeln!(out, "  please answer y, n or press Enter")?,
+        }
+    }
+    bail!("no valid answer to {question:?}")
+}
+
+fn ask_choice<R: BufRead, W: Write>(
+    input: &mut R,
+    out: &mut W,
+    question: &str,
+    choices: &[&str],
+    default: &str,
+) -> Result<String> {
+    for _ in 0..3 {
+        let answer = read_answer(
+            input,
+            out,
+            &format!("{question} ({}) [{default}]", choices.join(", ")),
+        )?;
+        if answer.is_empty() {
+            return Ok(default.to_string());
+        }
+        if choices.contains(&answer.as_str()) {
+            return Ok(answer);
+        }
+        writeln!(out, "  unknown choice {answer:?}")?;
+    }
+    bail!("no valid answer to {question:?}")
+}